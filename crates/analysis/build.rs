@@ -0,0 +1,9 @@
+//! Analyzing the full standard basis ahead of time, so we need not do it at every startup.
+
+fn main() {
+  let out_dir = std::env::var_os("OUT_DIR").expect("no OUT_DIR");
+  let path = std::path::Path::new(&out_dir).join("std_basis_full.bin");
+  let bytes = mlb_statics::StdBasis::full().to_bytes();
+  std::fs::write(path, bytes).expect("couldn't write std basis");
+  println!("cargo:rerun-if-changed=build.rs");
+}
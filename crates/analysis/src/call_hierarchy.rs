@@ -0,0 +1,117 @@
+//! Calls between functions, gotten from applications in the HIR.
+
+use sml_syntax::ast::{self, AstNode as _, SyntaxNodePtr};
+use sml_syntax::{SyntaxKind, SyntaxNode};
+use text_pos::Range;
+use text_size_util::TextRange;
+
+/// A function, or other value bound by a `val`, in a call hierarchy.
+#[derive(Debug)]
+pub struct CallHierarchyItem {
+  /// The name.
+  pub name: String,
+  /// The path of the file containing the binding.
+  pub path: sml_statics::DefPath,
+  /// The range of the whole binding.
+  pub range: Range,
+  /// The range of the name in the binding. Contained in `range`.
+  pub selection_range: Range,
+}
+
+/// Calls from one item to another.
+#[derive(Debug)]
+pub struct CallHierarchyCall {
+  /// The other item: the caller for incoming calls, or the callee for outgoing calls.
+  pub item: CallHierarchyItem,
+  /// The ranges of the calls, in the file of the caller.
+  pub ranges: Vec<Range>,
+}
+
+/// A call in a file.
+pub(crate) struct Call {
+  pub(crate) caller: sml_statics::Def,
+  pub(crate) callee: sml_statics::Def,
+  pub(crate) range: TextRange,
+}
+
+/// Returns the calls in the file, that is, the applications whose function is a path to a binding
+/// that are inside another binding.
+pub(crate) fn calls(file: &mlb_statics::SourceFile, path: paths::PathId) -> Vec<Call> {
+  let arenas = &file.syntax.lower.arenas;
+  let root = file.syntax.parse.root.syntax();
+  arenas
+    .exp
+    .iter()
+    .filter_map(|(_, exp)| {
+      let func = match exp {
+        sml_hir::Exp::App(Some(func), _) => *func,
+        _ => return None,
+      };
+      let name = match &arenas.exp[func] {
+        sml_hir::Exp::Path(name) => name.last(),
+        _ => return None,
+      };
+      let callee = file.info.get_def(func.into())?;
+      let node = file.syntax.lower.ptrs.hir_to_ast(func.into())?.to_node(root);
+      let caller = caller(file, path, &node)?;
+      let range = crate::name_token_range(&node, name.as_str())?;
+      Some(Call { caller, callee, range })
+    })
+    .collect()
+}
+
+/// Returns the item for the def, if it is a binding of a single name in the file.
+pub(crate) fn item(
+  file: &mlb_statics::SourceFile,
+  path: paths::PathId,
+  pat: sml_hir::la_arena::Idx<sml_hir::Pat>,
+) -> Option<CallHierarchyItem> {
+  let name = name(&file.syntax.lower.arenas, pat)?;
+  let root = file.syntax.parse.root.syntax();
+  let node = file.syntax.lower.ptrs.hir_to_ast(pat.into())?.to_node(root);
+  let selection_range = crate::name_token_range(&node, name)?;
+  // the name in a `val` is only part of the binding. the name in a `fun` points at the whole
+  // binding already.
+  let binding = node.ancestors().find(|x| !ast::Pat::can_cast(x.kind()));
+  let range = match binding {
+    Some(x) if x.kind() == SyntaxKind::ValBind => x.text_range(),
+    _ => node.text_range(),
+  };
+  Some(CallHierarchyItem {
+    name: name.to_owned(),
+    path: sml_statics::DefPath::Regular(path),
+    range: file.syntax.pos_db.range(range)?,
+    selection_range: file.syntax.pos_db.range(selection_range)?,
+  })
+}
+
+/// returns the binding of a single name that the node is in.
+fn caller(
+  file: &mlb_statics::SourceFile,
+  path: paths::PathId,
+  node: &SyntaxNode,
+) -> Option<sml_statics::Def> {
+  node.ancestors().find_map(|node| {
+    let pat = match node.kind() {
+      // the name of a `fun` points at the whole binding.
+      SyntaxKind::FunBind => node,
+      SyntaxKind::ValBind => ast::ValBind::cast(node)?.pat()?.syntax().clone(),
+      _ => return None,
+    };
+    match file.syntax.lower.ptrs.ast_to_hir(&SyntaxNodePtr::new(&pat))? {
+      sml_hir::Idx::Pat(pat) => {
+        name(&file.syntax.lower.arenas, pat)?;
+        Some(sml_statics::Def::Path(sml_statics::DefPath::Regular(path), pat.into()))
+      }
+      _ => None,
+    }
+  })
+}
+
+/// returns the name bound by the pat, if it is a single name.
+fn name(arenas: &sml_hir::Arenas, pat: sml_hir::la_arena::Idx<sml_hir::Pat>) -> Option<&str> {
+  match &arenas.pat[pat] {
+    sml_hir::Pat::Con(name, None) if name.prefix().is_empty() => Some(name.last().as_str()),
+    _ => None,
+  }
+}
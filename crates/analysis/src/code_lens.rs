@@ -0,0 +1,128 @@
+//! Code lenses above top-level declarations.
+
+use sml_syntax::ast::{self, AstNode as _};
+use sml_syntax::{SyntaxKind, SyntaxToken};
+use text_pos::{PositionDb, Range};
+
+/// A lens to show above a declaration. Computing what to show is deferred until it is resolved.
+#[derive(Debug)]
+pub struct CodeLens {
+  /// The range of the name of the declaration.
+  pub range: Range,
+  /// The kind.
+  pub kind: CodeLensKind,
+}
+
+/// A kind of code lens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CodeLensKind {
+  /// The number of references to the item.
+  References,
+  /// The inferred type of a function without type annotations.
+  Type,
+}
+
+/// Returns the lenses for the top-level `fun`, `val`, `structure`, and `functor` declarations.
+pub(crate) fn get(pos_db: &PositionDb, root: &ast::Root) -> Vec<CodeLens> {
+  let mut ac = Vec::<CodeLens>::new();
+  let decs = root
+    .dec()
+    .into_iter()
+    .flat_map(|x| x.dec_with_tail_in_seqs())
+    .filter_map(|x| x.dec_with_tail())
+    .flat_map(|x| x.dec_in_seqs())
+    .filter_map(|x| x.dec_one());
+  for dec in decs {
+    match dec {
+      ast::DecOne::ValDec(dec) => {
+        for val_bind in dec.val_binds() {
+          let pat = match val_bind.pat() {
+            Some(x) => x,
+            None => continue,
+          };
+          let names = crate::symbol::pat_names(&pat);
+          let unannotated_fn = match val_bind.eq_exp().and_then(|x| x.exp()) {
+            Some(ast::Exp::FnExp(exp)) => exp.matcher().map_or(true, |matcher| {
+              matcher
+                .match_rules()
+                .all(|rule| rule.pat().map_or(true, |pat| !has_ty_annotation(&pat)))
+            }),
+            _ => false,
+          };
+          // only a lone name, not one under a type annotation.
+          if unannotated_fn && matches!(pat, ast::Pat::ConPat(_)) {
+            push(pos_db, &mut ac, names.first(), CodeLensKind::Type);
+          }
+          for name in &names {
+            push(pos_db, &mut ac, Some(name), CodeLensKind::References);
+          }
+        }
+      }
+      ast::DecOne::FunDec(dec) => {
+        for fun_bind in dec.fun_binds() {
+          let name = fun_bind.fun_bind_cases().find_map(|case| {
+            let name = match case.fun_bind_case_head()? {
+              ast::FunBindCaseHead::PrefixFunBindCaseHead(head) => head.name_star_eq(),
+              ast::FunBindCaseHead::InfixFunBindCaseHead(head) => head.name_star_eq(),
+            };
+            name.map(|x| x.token)
+          });
+          let annotated = fun_bind.fun_bind_cases().any(|case| {
+            case.ty_annotation().is_some() || case.pats().any(|pat| has_ty_annotation(&pat))
+          });
+          if !annotated {
+            push(pos_db, &mut ac, name.as_ref(), CodeLensKind::Type);
+          }
+          push(pos_db, &mut ac, name.as_ref(), CodeLensKind::References);
+        }
+      }
+      ast::DecOne::StructureDec(dec) => {
+        for str_bind in dec.str_binds() {
+          push(pos_db, &mut ac, str_bind.name().as_ref(), CodeLensKind::References);
+        }
+      }
+      ast::DecOne::FunctorDec(dec) => {
+        for functor_bind in dec.functor_binds() {
+          let name = functor_bind.functor_name();
+          push(pos_db, &mut ac, name.as_ref(), CodeLensKind::References);
+        }
+      }
+      ast::DecOne::HoleDec(_)
+      | ast::DecOne::TyDec(_)
+      | ast::DecOne::DatDec(_)
+      | ast::DecOne::DatCopyDec(_)
+      | ast::DecOne::AbstypeDec(_)
+      | ast::DecOne::ExDec(_)
+      | ast::DecOne::OpenDec(_)
+      | ast::DecOne::InfixDec(_)
+      | ast::DecOne::InfixrDec(_)
+      | ast::DecOne::NonfixDec(_)
+      | ast::DecOne::DoDec(_)
+      | ast::DecOne::LocalDec(_)
+      | ast::DecOne::SignatureDec(_)
+      | ast::DecOne::ExpDec(_)
+      | ast::DecOne::IncludeDec(_) => {}
+    }
+  }
+  ac
+}
+
+/// returns whether there is a type annotation anywhere in the pattern, like in `(x : int)` or
+/// `{a : int, ...}`.
+fn has_ty_annotation(pat: &ast::Pat) -> bool {
+  pat
+    .syntax()
+    .descendants()
+    .any(|node| matches!(node.kind(), SyntaxKind::TypedPat | SyntaxKind::TyAnnotation))
+}
+
+fn push(
+  pos_db: &PositionDb,
+  ac: &mut Vec<CodeLens>,
+  name: Option<&SyntaxToken>,
+  kind: CodeLensKind,
+) {
+  if let Some(range) = name.and_then(|x| pos_db.range(x.text_range())) {
+    ac.push(CodeLens { range, kind });
+  }
+}
@@ -0,0 +1,86 @@
+//! Highlighting the occurrences of an item in a document.
+
+use sml_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+use text_pos::Range;
+use text_size_util::TextRange;
+
+/// An occurrence of an item in a document.
+#[derive(Debug)]
+pub struct DocumentHighlight {
+  /// The range of the occurrence.
+  pub range: Range,
+  /// The kind.
+  pub kind: DocumentHighlightKind,
+}
+
+/// A kind of occurrence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentHighlightKind {
+  /// Where the item is bound or created.
+  Write,
+  /// Where the item is used.
+  Read,
+  /// Neither, like a record label in a type.
+  Text,
+}
+
+/// Returns the occurrences of the type variable in the declaration it is scoped to.
+pub(crate) fn ty_vars(token: &SyntaxToken) -> Vec<(TextRange, DocumentHighlightKind)> {
+  // type variables are implicitly scoped at the outermost declaration they appear in.
+  let scope = token.parent_ancestors().filter(|node| is_ty_var_scope(node.kind())).last();
+  let scope = match scope.or_else(|| token.parent_ancestors().last()) {
+    Some(x) => x,
+    None => return Vec::new(),
+  };
+  tokens(&scope)
+    .filter(|tok| tok.kind() == SyntaxKind::TyVar && tok.text() == token.text())
+    .map(|tok| {
+      let is_bind = tok.parent().map_or(false, |x| x.kind() == SyntaxKind::TyVarArg);
+      let kind = if is_bind { DocumentHighlightKind::Write } else { DocumentHighlightKind::Read };
+      (tok.text_range(), kind)
+    })
+    .collect()
+}
+
+/// Returns the occurrences of the record label in the document. Labels aren't bound anywhere, so
+/// this is every label with the same name.
+pub(crate) fn labels(
+  root: &SyntaxNode,
+  token: &SyntaxToken,
+) -> Vec<(TextRange, DocumentHighlightKind)> {
+  tokens(root)
+    .filter(|tok| tok.text() == token.text())
+    .filter_map(|tok| {
+      let parent = tok.parent()?;
+      let kind = match parent.kind() {
+        SyntaxKind::Lab => match parent.parent()?.kind() {
+          SyntaxKind::ExpRow => DocumentHighlightKind::Write,
+          SyntaxKind::TyRow => DocumentHighlightKind::Text,
+          _ => DocumentHighlightKind::Read,
+        },
+        // a lone name in a record pattern is both a label and a variable.
+        SyntaxKind::NameStarEq if parent.parent()?.kind() == SyntaxKind::LabPatRow => {
+          DocumentHighlightKind::Read
+        }
+        _ => return None,
+      };
+      Some((tok.text_range(), kind))
+    })
+    .collect()
+}
+
+fn tokens(node: &SyntaxNode) -> impl Iterator<Item = SyntaxToken> {
+  node.descendants_with_tokens().filter_map(sml_syntax::rowan::NodeOrToken::into_token)
+}
+
+fn is_ty_var_scope(kind: SyntaxKind) -> bool {
+  matches!(
+    kind,
+    SyntaxKind::ValDec
+      | SyntaxKind::FunDec
+      | SyntaxKind::TyDec
+      | SyntaxKind::DatDec
+      | SyntaxKind::AbstypeDec
+      | SyntaxKind::ExDec
+  )
+}
@@ -0,0 +1,104 @@
+//! Inlay hints for the inferred types of bindings.
+
+use sml_syntax::ast::{self, AstNode as _, SyntaxNodePtr};
+use sml_syntax::{SyntaxKind, SyntaxNode};
+use text_pos::Position;
+use text_size_util::TextRange;
+
+/// A hint to show inline in a document.
+#[derive(Debug)]
+pub struct InlayHint {
+  /// Where to show the hint.
+  pub position: Position,
+  /// The text of the hint.
+  pub label: String,
+  /// The kind.
+  pub kind: InlayHintKind,
+}
+
+/// A kind of inlay hint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InlayHintKind {
+  /// The type of a variable bound by a `val`.
+  ValBind,
+  /// The type of a parameter of a `fun`.
+  FunParam,
+  /// The type of a parameter of a `fn`.
+  FnParam,
+}
+
+/// Returns the hints for the variables bound in the range that don't have a type annotation.
+pub(crate) fn get(
+  file: &mlb_statics::SourceFile,
+  syms: &sml_statics::Syms,
+  range: TextRange,
+) -> Vec<InlayHint> {
+  let mut cx = Cx { file, syms, range, ac: Vec::new() };
+  for node in file.syntax.parse.root.syntax().descendants() {
+    if range.intersect(node.text_range()).is_none() {
+      continue;
+    }
+    if let Some(val_bind) = ast::ValBind::cast(node.clone()) {
+      cx.pat(InlayHintKind::ValBind, val_bind.pat());
+    } else if let Some(case) = ast::FunBindCase::cast(node.clone()) {
+      if let Some(ast::FunBindCaseHead::InfixFunBindCaseHead(head)) = case.fun_bind_case_head() {
+        cx.pat(InlayHintKind::FunParam, head.lhs());
+        cx.pat(InlayHintKind::FunParam, head.rhs());
+      }
+      for pat in case.pats() {
+        cx.pat(InlayHintKind::FunParam, Some(pat));
+      }
+    } else if let Some(fn_exp) = ast::FnExp::cast(node) {
+      for rule in fn_exp.matcher().into_iter().flat_map(|x| x.match_rules()) {
+        cx.pat(InlayHintKind::FnParam, rule.pat());
+      }
+    }
+  }
+  cx.ac
+}
+
+struct Cx<'a> {
+  file: &'a mlb_statics::SourceFile,
+  syms: &'a sml_statics::Syms,
+  range: TextRange,
+  ac: Vec<InlayHint>,
+}
+
+impl Cx<'_> {
+  /// pushes a hint for every variable in the pat not already under a type annotation.
+  fn pat(&mut self, kind: InlayHintKind, pat: Option<ast::Pat>) {
+    let pat = match pat {
+      Some(x) => x,
+      None => return,
+    };
+    let root = pat.syntax();
+    for node in root.descendants() {
+      if node.kind() != SyntaxKind::ConPat || self.range.intersect(node.text_range()).is_none() {
+        continue;
+      }
+      // the whole pat may be the annotated one.
+      let annotated = node
+        .ancestors()
+        .take_while(|x| x != root)
+        .chain(std::iter::once(root.clone()))
+        .any(|x| x.kind() == SyntaxKind::TypedPat);
+      if annotated {
+        continue;
+      }
+      if let Some(hint) = self.hint(kind, &node) {
+        self.ac.push(hint);
+      }
+    }
+  }
+
+  fn hint(&self, kind: InlayHintKind, con_pat: &SyntaxNode) -> Option<InlayHint> {
+    let idx = self.file.syntax.lower.ptrs.ast_to_hir(&SyntaxNodePtr::new(con_pat))?;
+    // constructors and exceptions aren't bound by the pattern.
+    if self.file.info.get_val_kind(idx)? != sml_statics::ValKind::Val {
+      return None;
+    }
+    let ty = self.file.info.get_ty(self.syms, idx)?;
+    let position = self.file.syntax.pos_db.range(con_pat.text_range())?.end;
+    Some(InlayHint { position, label: format!(": {ty}"), kind })
+  }
+}
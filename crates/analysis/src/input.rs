@@ -23,6 +23,8 @@ pub struct Input {
   pub(crate) root_group_paths: Vec<PathId>,
   /// Severities to override.
   pub(crate) severities: Severities,
+  /// The language settings.
+  pub(crate) lang: mlb_statics::Language,
 }
 
 impl Input {
@@ -41,6 +43,7 @@ impl Input {
       groups: PathMap::default(),
       root_group_paths: Vec::new(),
       severities: root.config.severities,
+      lang: root.config.lang,
     };
     for group in root.groups {
       let f = match group.kind {
@@ -1,8 +1,8 @@
 //! Lower a CM file into paths and exports.
 
 use crate::input::util::{
-  get_path_id_in_group, read_file, Error, ErrorKind, ErrorSource, GroupPathToProcess, Result,
-  StartedGroup,
+  builtin_libs, get_path_id_in_group, read_file, Error, ErrorKind, ErrorSource, GroupPathToProcess,
+  Result, StartedGroup,
 };
 use crate::input::Group;
 use fast_hash::FxHashSet;
@@ -34,9 +34,16 @@ where
       })
       .collect();
     let path_decs: Vec<_> = cm_file
-      .cm_paths
+      .builtin_libs
       .iter()
-      .map(|&p| mlb_statics::BasDec::Path(p, mlb_statics::PathKind::Group))
+      .copied()
+      .map(mlb_statics::BasDec::Builtin)
+      .chain(
+        cm_file
+          .cm_paths
+          .iter()
+          .map(|&p| mlb_statics::BasDec::Path(p, mlb_statics::PathKind::Group)),
+      )
       .chain(std::iter::once(mlb_statics::BasDec::SourcePathSet(cm_file.sml_paths)))
       .collect();
     let bas_dec = mlb_statics::BasDec::Local(
@@ -63,6 +70,7 @@ struct St<'a, F> {
 struct CmFile {
   /// only optional so this can derive default.
   pos_db: Option<text_pos::PositionDb>,
+  builtin_libs: Vec<mlb_statics::BuiltinLib>,
   cm_paths: Vec<paths::PathId>,
   sml_paths: FxHashSet<paths::PathId>,
   exports: NameExports,
@@ -99,6 +107,9 @@ where
       ))
     }
   };
+  for path in &cm.std_basis_paths {
+    ret.builtin_libs.extend_from_slice(builtin_libs(path.val.as_str()));
+  }
   for pp in cm.paths {
     let (path_id, path, source) =
       get_path_id_in_group(st.fs, st.store, &group, pp.val.as_path(), pp.range)?;
@@ -163,7 +174,7 @@ where
     cm_syntax::Export::Library(lib) => {
       let p = match &lib.val {
         cm_syntax::PathOrStdBasis::Path(p) => p,
-        cm_syntax::PathOrStdBasis::StdBasis => return Ok(()),
+        cm_syntax::PathOrStdBasis::StdBasis(_) => return Ok(()),
       };
       get_one_and_extend_with(st, cx.group, cx.cur_path_id, p.as_path(), lib.range, ac)?;
     }
@@ -1,8 +1,8 @@
 //! Lower a MLB root group into a map of source files and parsed groups.
 
 use crate::input::util::{
-  get_path_id_in_group, read_file, Error, ErrorKind, ErrorSource, GroupPathToProcess, Result,
-  StartedGroup,
+  builtin_libs, get_path_id_in_group, read_file, Error, ErrorKind, ErrorSource, GroupPathToProcess,
+  Result, StartedGroup,
 };
 use crate::input::Group;
 use fast_hash::FxHashSet;
@@ -132,6 +132,9 @@ where
       };
       mlb_statics::BasDec::Path(path_id, kind)
     }
+    mlb_syntax::BasDec::StdBasisPath(path) => mlb_statics::BasDec::seq(
+      builtin_libs(path.val.as_str()).iter().copied().map(mlb_statics::BasDec::Builtin).collect(),
+    ),
     mlb_syntax::BasDec::Ann(_, dec) => get_bas_dec(st, cx, *dec)?,
     mlb_syntax::BasDec::Seq(decs) => mlb_statics::BasDec::seq(
       decs.into_iter().map(|dec| get_bas_dec(st, cx, dec)).collect::<Result<Vec<_>>>()?,
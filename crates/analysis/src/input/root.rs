@@ -81,6 +81,7 @@ pub(crate) type Severities = FxHashMap<diagnostic_util::Code, Option<diagnostic_
 pub(crate) struct Config {
   pub(crate) path_vars: paths::slash_var_path::Env,
   pub(crate) severities: Severities,
+  pub(crate) lang: mlb_statics::Language,
 }
 
 struct ConfigFromFile {
@@ -159,6 +160,12 @@ impl ConfigFromFile {
         }
       }
     }
+    if let Some(lang) = parsed.language {
+      if let Some(implicit_std_basis) = lang.implicit_std_basis {
+        ret.config.lang.implicit_std_basis = implicit_std_basis;
+      }
+      ret.config.lang.dialect = lang.dialect;
+    }
     for (code, config) in parsed.diagnostics.into_iter().flatten() {
       let code = match code.parse::<diagnostic_util::Code>() {
         Ok(x) => x,
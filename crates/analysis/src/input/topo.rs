@@ -20,7 +20,9 @@ where
 
 fn bas_dec_paths(ac: &mut BTreeSet<PathId>, dec: &mlb_statics::BasDec) {
   match dec {
-    mlb_statics::BasDec::Open(_) | mlb_statics::BasDec::Export(_, _, _) => {}
+    mlb_statics::BasDec::Open(_)
+    | mlb_statics::BasDec::Export(_, _, _)
+    | mlb_statics::BasDec::Builtin(_) => {}
     mlb_statics::BasDec::Path(p, _) => {
       ac.insert(*p);
     }
@@ -255,3 +255,25 @@ pub(crate) enum GroupPathKind {
   /// ML Basis files.
   Mlb,
 }
+
+/// Returns the built-in libraries asked for by a std basis path, as written in a group file.
+///
+/// Paths not known here get nothing.
+pub(crate) fn builtin_libs(path: &str) -> &'static [mlb_statics::BuiltinLib] {
+  match path {
+    "$(SML_LIB)/basis/basis.mlb"
+    | "$(SML_LIB)/basis/basis-1997.mlb"
+    | "$(SML_LIB)/basis/basis-2002.mlb" => &[mlb_statics::BuiltinLib::StdBasis],
+    "$(SML_LIB)/basis/mlton.mlb" => &[mlb_statics::BuiltinLib::Mlton],
+    "$(SML_LIB)/basis/sml-nj.mlb" => &[mlb_statics::BuiltinLib::SmlOfNj],
+    "$/basis.cm" => &[mlb_statics::BuiltinLib::StdBasis, mlb_statics::BuiltinLib::SmlOfNj],
+    "$/smlnj-lib.cm" => &[mlb_statics::BuiltinLib::SmlnjLib],
+    _ => {
+      if path.starts_with("$(SML_LIB)/smlnj-lib/") || path.starts_with("$SMLNJ-LIB/") {
+        &[mlb_statics::BuiltinLib::SmlnjLib]
+      } else {
+        &[]
+      }
+    }
+  }
+}
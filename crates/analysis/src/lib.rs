@@ -4,24 +4,55 @@
 
 pub mod input;
 
+mod call_hierarchy;
+mod code_lens;
+mod document_highlight;
+mod inlay_hint;
+mod missing_arms;
+mod semantic_tokens;
+mod stubs;
+mod symbol;
+mod unused;
+mod workspace_symbol;
+
 use diagnostic_util::Diagnostic;
+use fast_hash::{FxHashMap, FxHashSet};
 use fmt_util::sep_seq;
+use once_cell::sync::{Lazy, OnceCell};
 use paths::{PathId, PathMap, WithPath};
 use sml_syntax::ast::{self, AstNode as _, SyntaxNodePtr};
 use sml_syntax::{rowan::TokenAtOffset, SyntaxKind, SyntaxNode, SyntaxToken};
 use std::fmt;
 use text_pos::{Position, Range};
-use text_size_util::TextRange;
+use text_size_util::{TextRange, TextSize, WithRange};
+
+pub use call_hierarchy::{CallHierarchyCall, CallHierarchyItem};
+pub use code_lens::{CodeLens, CodeLensKind};
+pub use document_highlight::{DocumentHighlight, DocumentHighlightKind};
+pub use inlay_hint::{InlayHint, InlayHintKind};
+pub use semantic_tokens::{SemanticToken, SemanticTokenKind};
+pub use sml_statics::{CompletionItem, CompletionKind, DefPath, EQUALITY_CHECKS_ENABLED};
+pub use symbol::{DocumentSymbol, SymbolKind};
+pub use workspace_symbol::WorkspaceSymbol;
+
+/// The most suggestions to give for what to fill a hole with.
+const MAX_HOLE_FILLS: usize = 15;
 
-pub use sml_statics::EQUALITY_CHECKS_ENABLED;
+/// The most workspace symbols to return for a query.
+const MAX_WORKSPACE_SYMBOLS: usize = 100;
 
 /// Performs analysis.
 #[derive(Debug)]
 pub struct Analysis {
   std_basis: mlb_statics::StdBasis,
   diagnostics_options: DiagnosticsOptions,
+  threads: usize,
   source_files: PathMap<mlb_statics::SourceFile>,
+  cache: mlb_statics::Cache,
   syms: sml_statics::Syms,
+  /// Built on first use, since most sessions never ask for them.
+  std_basis_symbols: OnceCell<Vec<WorkspaceSymbol>>,
+  source_file_symbols: Vec<WorkspaceSymbol>,
 }
 
 impl Analysis {
@@ -36,17 +67,29 @@ impl Analysis {
     Self {
       std_basis: std_basis.to_mlb_statics(),
       diagnostics_options: DiagnosticsOptions { lines, filter, format },
+      threads: std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get),
       source_files: PathMap::default(),
+      cache: mlb_statics::Cache::default(),
       syms: sml_statics::Syms::default(),
+      std_basis_symbols: OnceCell::new(),
+      source_file_symbols: Vec::new(),
     }
   }
 
-  /// Given the contents of one isolated file, return the diagnostics for it.
-  pub fn get_one(&self, contents: &str) -> Vec<Diagnostic> {
+  /// Sets how many threads to parse source files with. The default is the available parallelism.
+  ///
+  /// The results of analysis do not depend on this.
+  pub fn set_threads(&mut self, threads: usize) {
+    self.threads = threads;
+  }
+
+  /// Given the contents of one isolated file, written in the dialect if any, return the
+  /// diagnostics for it.
+  pub fn get_one(&self, contents: &str, dialect: Option<config::Dialect>) -> Vec<Diagnostic> {
     let mut fix_env = sml_parse::parser::STD_BASIS.clone();
-    let syntax = mlb_statics::SourceFileSyntax::new(&mut fix_env, contents);
+    let syntax = mlb_statics::SourceFileSyntax::new(&mut fix_env, dialect, contents);
     let mut syms = self.std_basis.syms().clone();
-    let basis = self.std_basis.basis().clone();
+    let basis = self.std_basis.dialect_basis(dialect);
     let mode = sml_statics::Mode::Regular(None);
     let checked =
       sml_statics::get(&mut syms, &basis, mode, &syntax.lower.arenas, syntax.lower.root);
@@ -59,15 +102,38 @@ impl Analysis {
   /// Given information about many interdependent source files and their groupings, returns a
   /// mapping from source paths to diagnostics.
   pub fn get_many(&mut self, input: &input::Input) -> PathMap<Vec<Diagnostic>> {
-    let syms = self.std_basis.syms().clone();
-    let basis = self.std_basis.basis();
+    let std_basis = &self.std_basis;
     let groups: paths::PathMap<_> =
       input.groups.iter().map(|(&path, group)| (path, &group.bas_dec)).collect();
+    let cache = std::mem::take(&mut self.cache);
+    let prev_sml = std::mem::take(&mut self.source_files);
     let res = elapsed::log("mlb_statics::get", || {
-      mlb_statics::get(syms, basis, &input.sources, &groups, &input.root_group_paths)
+      mlb_statics::get(
+        std_basis,
+        input.lang,
+        self.threads,
+        &input.sources,
+        &groups,
+        &input.root_group_paths,
+        cache,
+        prev_sml,
+      )
     });
     self.source_files = res.sml;
+    self.cache = res.cache;
     self.syms = res.syms;
+    self.source_file_symbols = self
+      .source_files
+      .iter()
+      .flat_map(|(&path, file)| {
+        let lower = &file.syntax.lower;
+        let path = sml_statics::DefPath::Regular(path);
+        workspace_symbol::get(&lower.arenas, lower.root, path, |idx| {
+          let ptr = lower.ptrs.hir_to_ast(idx)?;
+          file.syntax.pos_db.range(ptr.text_range())
+        })
+      })
+      .collect();
     std::iter::empty()
       .chain(res.mlb_errors.into_iter().filter_map(|err| {
         let path = err.path();
@@ -98,16 +164,7 @@ impl Analysis {
       Some((ptr, idx)) => {
         ty_md = ft.file.info.get_ty_md(&self.syms, idx);
         parts.extend(ty_md.as_deref());
-        parts.extend(ft.file.info.get_def(idx).and_then(|def| match def {
-          sml_statics::Def::Path(path, idx) => {
-            let info = match path {
-              sml_statics::DefPath::Regular(path) => &self.source_files.get(&path)?.info,
-              sml_statics::DefPath::BuiltinLib(name) => self.std_basis.get_info(name)?,
-            };
-            info.get_doc(idx)
-          }
-          sml_statics::Def::Primitive => None,
-        }));
+        parts.extend(ft.file.info.get_def(idx).and_then(|def| self.def_doc(def)));
         ptr.to_node(ft.file.syntax.parse.root.syntax()).text_range()
       }
       None => ft.token.text_range(),
@@ -122,18 +179,19 @@ impl Analysis {
     Some((parts.join("\n\n---\n\n"), range))
   }
 
-  /// Returns the range of the definition of the item at this position.
+  /// Returns the path and range of the definition of the item at this position. The definition
+  /// may be in a built-in library file.
   #[must_use]
-  pub fn get_def(&self, pos: WithPath<Position>) -> Option<WithPath<Range>> {
+  pub fn get_def(&self, pos: WithPath<Position>) -> Option<(sml_statics::DefPath, Range)> {
     let ft = self.get_file_and_token(pos)?;
     let (_, idx) = ft.get_ptr_and_idx()?;
     self.def_to_path_and_range(ft.file.info.get_def(idx)?)
   }
 
-  /// Returns the ranges of the definitions of the types involved in the type of the item at this
-  /// position.
+  /// Returns the paths and ranges of the definitions of the types involved in the type of the item
+  /// at this position.
   #[must_use]
-  pub fn get_ty_defs(&self, pos: WithPath<Position>) -> Option<Vec<WithPath<Range>>> {
+  pub fn get_ty_defs(&self, pos: WithPath<Position>) -> Option<Vec<(sml_statics::DefPath, Range)>> {
     let ft = self.get_file_and_token(pos)?;
     let (_, idx) = ft.get_ptr_and_idx()?;
     Some(
@@ -146,20 +204,444 @@ impl Analysis {
     )
   }
 
+  /// Returns the paths and ranges of the implementations of the signature spec at this position,
+  /// or, if this is a definition, the signature specs it implements.
+  #[must_use]
+  pub fn get_impls(&self, pos: WithPath<Position>) -> Option<Vec<(sml_statics::DefPath, Range)>> {
+    let ft = self.get_file_and_token(pos)?;
+    let (_, idx) = ft.get_ptr_and_idx()?;
+    let def = ft.get_def_or_self(pos.path, idx)?;
+    let infos = std::iter::empty()
+      .chain(self.source_files.values().map(|file| &file.info))
+      .chain(self.std_basis.files().map(|(_, file)| &file.info));
+    let mut defs = FxHashSet::<sml_statics::Def>::default();
+    for info in infos {
+      for &(spec, other) in info.impls() {
+        if spec == def {
+          defs.insert(other);
+        } else if other == def {
+          defs.insert(spec);
+        }
+      }
+    }
+    Some(defs.into_iter().filter_map(|def| self.def_to_path_and_range(def)).collect())
+  }
+
+  /// Returns the ranges of all the references to the item at this position, across all files. If
+  /// `include_def` is true, also includes the range of the definition.
+  ///
+  /// The references to the signature specs the definition implements (or, if this is a spec, the
+  /// definitions implementing it) are included as well.
+  #[must_use]
+  pub fn get_refs(
+    &self,
+    pos: WithPath<Position>,
+    include_def: bool,
+  ) -> Option<Vec<WithPath<Range>>> {
+    let ft = self.get_file_and_token(pos)?;
+    if !is_name_like(ft.token.kind()) {
+      return None;
+    }
+    let (_, idx) = ft.get_ptr_and_idx()?;
+    let def = ft.get_def_or_self(pos.path, idx)?;
+    let defs = self.related_defs(def);
+    Some(self.name_ranges(&defs, ft.token.text(), include_def))
+  }
+
+  /// Returns the ranges of the occurrences of the item at this position in its file, marking
+  /// where it is bound and where it is used.
+  #[must_use]
+  pub fn document_highlights(&self, pos: WithPath<Position>) -> Option<Vec<DocumentHighlight>> {
+    let ft = self.get_file_and_token(pos)?;
+    let root = ft.file.syntax.parse.root.syntax();
+    let is_lab = ft.token.parent().map_or(false, |x| x.kind() == SyntaxKind::Lab);
+    let mut ranges = if ft.token.kind() == SyntaxKind::TyVar {
+      document_highlight::ty_vars(&ft.token)
+    } else if is_lab {
+      document_highlight::labels(root, &ft.token)
+    } else if is_name_like(ft.token.kind()) {
+      let (_, idx) = ft.get_ptr_and_idx()?;
+      let def = ft.get_def_or_self(pos.path, idx)?;
+      let def_site = match def {
+        sml_statics::Def::Path(sml_statics::DefPath::Regular(p), idx) => {
+          (p == pos.path).then_some((idx, DocumentHighlightKind::Write))
+        }
+        sml_statics::Def::Path(sml_statics::DefPath::BuiltinLib(_), _)
+        | sml_statics::Def::Primitive => None,
+      };
+      let uses = ft
+        .file
+        .info
+        .defs()
+        .filter_map(|(idx, other)| (other == def).then_some((idx, DocumentHighlightKind::Read)));
+      uses
+        .chain(def_site)
+        .filter_map(|(idx, kind)| {
+          let ptr = ft.file.syntax.lower.ptrs.hir_to_ast(idx)?;
+          Some((name_token_range(&ptr.to_node(root), ft.token.text())?, kind))
+        })
+        .collect()
+    } else {
+      return None;
+    };
+    // many indices may point at the same syntax. prefer the binding site.
+    ranges.sort_unstable_by_key(|&(r, kind)| {
+      (r.start(), r.end(), kind != DocumentHighlightKind::Write)
+    });
+    ranges.dedup_by_key(|&mut (r, _)| r);
+    let ret = ranges
+      .into_iter()
+      .filter_map(|(range, kind)| {
+        Some(DocumentHighlight { range: ft.file.syntax.pos_db.range(range)?, kind })
+      })
+      .collect();
+    Some(ret)
+  }
+
+  /// Returns the function or other value bound by a `val` at this position, for a call hierarchy.
+  #[must_use]
+  pub fn prepare_call_hierarchy(&self, pos: WithPath<Position>) -> Option<CallHierarchyItem> {
+    let (path, pat) = self.call_hierarchy_def(pos)?;
+    call_hierarchy::item(self.source_files.get(&path)?, path, pat)
+  }
+
+  /// Returns the calls to the item at this position, grouped by caller, across all files.
+  #[must_use]
+  pub fn incoming_calls(&self, pos: WithPath<Position>) -> Option<Vec<CallHierarchyCall>> {
+    let (path, pat) = self.call_hierarchy_def(pos)?;
+    let def = sml_statics::Def::Path(sml_statics::DefPath::Regular(path), pat.into());
+    let mut ret = Vec::<CallHierarchyCall>::new();
+    for (&path, file) in &self.source_files {
+      let mut callers = FxHashMap::<sml_statics::Def, Vec<Range>>::default();
+      for call in call_hierarchy::calls(file, path) {
+        if call.callee != def {
+          continue;
+        }
+        if let Some(range) = file.syntax.pos_db.range(call.range) {
+          callers.entry(call.caller).or_default().push(range);
+        }
+      }
+      ret.extend(callers.into_iter().filter_map(|(caller, ranges)| {
+        let item = self.call_hierarchy_item(caller)?;
+        Some(CallHierarchyCall { item, ranges })
+      }));
+    }
+    Some(ret)
+  }
+
+  /// Returns the calls from the item at this position, grouped by callee.
+  #[must_use]
+  pub fn outgoing_calls(&self, pos: WithPath<Position>) -> Option<Vec<CallHierarchyCall>> {
+    let (path, pat) = self.call_hierarchy_def(pos)?;
+    let def = sml_statics::Def::Path(sml_statics::DefPath::Regular(path), pat.into());
+    let file = self.source_files.get(&path)?;
+    let mut callees = FxHashMap::<sml_statics::Def, Vec<Range>>::default();
+    for call in call_hierarchy::calls(file, path) {
+      if call.caller != def {
+        continue;
+      }
+      if let Some(range) = file.syntax.pos_db.range(call.range) {
+        callees.entry(call.callee).or_default().push(range);
+      }
+    }
+    let ret = callees
+      .into_iter()
+      .filter_map(|(callee, ranges)| {
+        let item = self.call_hierarchy_item(callee)?;
+        Some(CallHierarchyCall { item, ranges })
+      })
+      .collect();
+    Some(ret)
+  }
+
+  /// Returns the range of the name at this position if it can be renamed.
+  #[must_use]
+  pub fn prepare_rename(&self, pos: WithPath<Position>) -> Option<Range> {
+    let ft = self.get_file_and_token(pos)?;
+    if !is_name_like(ft.token.kind()) {
+      return None;
+    }
+    self.renamable_defs(&ft, pos.path)?;
+    ft.file.syntax.pos_db.range(ft.token.text_range())
+  }
+
+  /// Returns the ranges to replace with `new_name` to rename the item at this position.
+  ///
+  /// This includes the definition, all the uses, the signature specs the definition implements,
+  /// and, for structures, signatures, and functors, the names in the exports of the groups.
+  ///
+  /// # Errors
+  ///
+  /// - The new name is not a valid name
+  /// - There is no item that may be renamed at the position
+  pub fn rename(
+    &self,
+    input: &input::Input,
+    pos: WithPath<Position>,
+    new_name: &str,
+  ) -> Result<Vec<WithPath<Range>>, RenameError> {
+    let ft = self.get_file_and_token(pos).ok_or(RenameError::NoItem)?;
+    if !is_name_like(ft.token.kind()) {
+      return Err(RenameError::NoItem);
+    }
+    if !is_valid_name(ft.token.text(), new_name) {
+      return Err(RenameError::InvalidName);
+    }
+    let defs = self.renamable_defs(&ft, pos.path).ok_or(RenameError::NoItem)?;
+    let old_name = ft.token.text();
+    let mut ret = self.name_ranges(&defs, old_name, true);
+    let namespaces: FxHashSet<_> = defs.iter().filter_map(|&def| self.def_namespace(def)).collect();
+    for (&path, group) in &input.groups {
+      let mut exports = Vec::new();
+      bas_dec_exports(&mut exports, &group.bas_dec);
+      let mut ranges: Vec<_> = exports
+        .into_iter()
+        .filter(|&(ns, _, rhs)| namespaces.contains(&ns) && rhs.val.as_str() == old_name)
+        .flat_map(|(_, lhs, rhs)| {
+          // if the export does not rename the item, we rename the exported name too.
+          let lhs = (lhs.val == rhs.val).then_some(lhs.range);
+          std::iter::once(rhs.range).chain(lhs)
+        })
+        .collect();
+      ranges.sort_unstable_by_key(|r| (r.start(), r.end()));
+      ranges.dedup();
+      ret.extend(ranges.into_iter().filter_map(|r| Some(path.wrap(group.pos_db.range(r)?))));
+    }
+    Ok(ret)
+  }
+
+  /// Returns the items that may complete the name being written at this position.
+  ///
+  /// After a structure path like `Foo.`, these are the members of `Foo`. In a record label
+  /// position, these are the labels of the record type. Otherwise, these are all the items in scope.
+  #[must_use]
+  pub fn completions(&self, pos: WithPath<Position>) -> Option<Vec<CompletionItem>> {
+    let file = self.source_files.get(&pos.path)?;
+    let offset = file.syntax.pos_db.text_size(pos.val)?;
+    let root = file.syntax.parse.root.syntax();
+    if !root.text_range().contains_inclusive(offset) {
+      return None;
+    }
+    // the cursor is usually just after what is being written.
+    let token = root.token_at_offset(offset).left_biased()?;
+    if let Some(record) = record_label_position(&token) {
+      let idx = file.syntax.lower.ptrs.ast_to_hir(&SyntaxNodePtr::new(&record))?;
+      return self.record_labels(file, idx);
+    }
+    let mut prefix = Vec::<str_util::Name>::new();
+    let mut cur =
+      if token.kind() == SyntaxKind::Name { token.prev_token() } else { Some(token.clone()) };
+    while let Some(dot) = cur.filter(|tok| tok.kind() == SyntaxKind::Dot) {
+      let name = match dot.prev_token() {
+        Some(tok) if tok.kind() == SyntaxKind::Name => tok,
+        _ => break,
+      };
+      prefix.push(str_util::Name::new(name.text()));
+      cur = name.prev_token();
+    }
+    prefix.reverse();
+    let scopes: Vec<_> = token
+      .parent_ancestors()
+      .filter_map(|node| file.syntax.lower.ptrs.ast_to_hir(&SyntaxNodePtr::new(&node)))
+      .collect();
+    Some(file.info.get_completions(&self.syms, &scopes, &prefix))
+  }
+
+  /// Returns help for the signature of the function applied to the argument at this position.
+  #[must_use]
+  pub fn signature_help(&self, pos: WithPath<Position>) -> Option<SignatureHelp> {
+    let ft = self.get_file_and_token(pos)?;
+    let offset = ft.file.syntax.pos_db.text_size(pos.val)?;
+    // the innermost application with the position in its argument.
+    let (app, arg) = ft.token.parent_ancestors().filter_map(ast::AppExp::cast).find_map(|app| {
+      let arg = app.arg()?;
+      arg.syntax().text_range().contains_inclusive(offset).then_some((app, arg))
+    })?;
+    // `f a b` is `(f a) b`, so the number of applications in the function is the number of
+    // curried arguments before this one.
+    let mut curried = 0usize;
+    let mut func = app.func()?;
+    while let ast::Exp::AppExp(inner) = func {
+      curried += 1;
+      func = inner.func()?;
+    }
+    let component = match &arg {
+      ast::Exp::TupleExp(tuple) => tuple
+        .exp_args()
+        .filter_map(|arg| {
+          arg.syntax().children_with_tokens().find(|x| x.kind() == SyntaxKind::Comma)
+        })
+        .filter(|comma| comma.text_range().end() <= offset)
+        .count(),
+      _ => 0,
+    };
+    let idx = ft.file.syntax.lower.ptrs.ast_to_hir(&SyntaxNodePtr::new(func.syntax()))?;
+    let mut label = String::new();
+    // the text of the function without comments, and with whitespace collapsed.
+    let mut space = false;
+    for token in
+      func.syntax().descendants_with_tokens().filter_map(sml_syntax::rowan::NodeOrToken::into_token)
+    {
+      if matches!(token.kind(), SyntaxKind::Whitespace | SyntaxKind::BlockComment) {
+        space = !label.is_empty();
+      } else {
+        if std::mem::take(&mut space) {
+          label.push(' ');
+        }
+        label.push_str(token.text());
+      }
+    }
+    label.push_str(" : ");
+    let params = ft.file.info.get_fn_params(&self.syms, idx, &mut label)?;
+    let active_param = params.get(curried).map(|ranges| {
+      let before: usize = params[..curried].iter().map(Vec::len).sum();
+      before + component.min(ranges.len().saturating_sub(1))
+    });
+    let doc = ft.file.info.get_def(idx).and_then(|def| self.def_doc(def)).map(ToOwned::to_owned);
+    Some(SignatureHelp { label, doc, params: params.into_iter().flatten().collect(), active_param })
+  }
+
+  /// Returns the ranges in the file at this path that may be folded, like long `struct ... end`
+  /// blocks and multi-line comments.
+  #[must_use]
+  pub fn folding_ranges(&self, path: PathId) -> Option<Vec<FoldingRange>> {
+    let file = self.source_files.get(&path)?;
+    let ret = file
+      .syntax
+      .parse
+      .root
+      .syntax()
+      .descendants_with_tokens()
+      .filter_map(|elem| {
+        let kind = match elem.kind() {
+          SyntaxKind::StructStrExp
+          | SyntaxKind::SigSigExp
+          | SyntaxKind::LetExp
+          | SyntaxKind::LetStrExp
+          | SyntaxKind::LocalDec
+          | SyntaxKind::AbstypeDec
+          | SyntaxKind::CaseExp => FoldingRangeKind::Region,
+          SyntaxKind::BlockComment => FoldingRangeKind::Comment,
+          _ => return None,
+        };
+        let range = file.syntax.pos_db.range(elem.text_range())?;
+        (range.start.line < range.end.line).then_some(FoldingRange { range, kind })
+      })
+      .collect();
+    Some(ret)
+  }
+
+  /// Returns, for each position in the file at this path, the ranges of the token at the position
+  /// and of each of the syntax nodes containing it, innermost first.
+  #[must_use]
+  pub fn selection_ranges(&self, path: PathId, positions: &[Position]) -> Option<Vec<Vec<Range>>> {
+    let file = self.source_files.get(&path)?;
+    let root = file.syntax.parse.root.syntax();
+    let ret = positions
+      .iter()
+      .map(|&pos| {
+        let token = match file.syntax.pos_db.text_size(pos).map(|x| root.token_at_offset(x)) {
+          Some(TokenAtOffset::Single(t) | TokenAtOffset::Between(_, t)) => t,
+          Some(TokenAtOffset::None) | None => return Vec::new(),
+        };
+        let mut ranges: Vec<_> = std::iter::once(token.text_range())
+          .chain(token.parent_ancestors().map(|node| node.text_range()))
+          .collect();
+        // many nodes may have the same range, like a dec with only one dec in it.
+        ranges.dedup();
+        ranges.into_iter().filter_map(|range| file.syntax.pos_db.range(range)).collect()
+      })
+      .collect();
+    Some(ret)
+  }
+
+  /// Returns the symbols in the file at this path, nested as they are in the file.
+  #[must_use]
+  pub fn document_symbols(&self, path: PathId) -> Option<Vec<DocumentSymbol>> {
+    let file = self.source_files.get(&path)?;
+    Some(symbol::get(&file.syntax.pos_db, &file.syntax.parse.root))
+  }
+
+  /// Returns the inferred types of the variables bound in the range of the file at this path,
+  /// except those that already have a type annotation.
+  #[must_use]
+  pub fn inlay_hints(&self, path: PathId, range: Range) -> Option<Vec<InlayHint>> {
+    let file = self.source_files.get(&path)?;
+    let range = file.syntax.pos_db.text_range(range)?;
+    Some(inlay_hint::get(file, &self.syms, range))
+  }
+
+  /// Returns the code lenses for the top-level declarations in the file at this path.
+  #[must_use]
+  pub fn code_lenses(&self, path: PathId) -> Option<Vec<CodeLens>> {
+    let file = self.source_files.get(&path)?;
+    Some(code_lens::get(&file.syntax.pos_db, &file.syntax.parse.root))
+  }
+
+  /// Returns the name and inferred type of the value bound at this position, on one line.
+  #[must_use]
+  pub fn get_inferred_ty(&self, pos: WithPath<Position>) -> Option<String> {
+    let ft = self.get_file_and_token(pos)?;
+    if !is_name_like(ft.token.kind()) {
+      return None;
+    }
+    let (_, idx) = ft.get_ptr_and_idx()?;
+    let ty = ft.file.info.get_bind_ty(&self.syms, idx)?;
+    Some(format!("{} : {ty}", ft.token.text()))
+  }
+
+  /// Returns the contents of the built-in library file with this name, as they were analyzed.
+  #[must_use]
+  pub fn builtin_file_contents(&self, name: &str) -> Option<&str> {
+    self.std_basis.get_file(name).map(|file| file.contents.as_ref())
+  }
+
+  /// Returns the semantic tokens in the file at this path, optionally only those in the range, in
+  /// order.
+  #[must_use]
+  pub fn semantic_tokens(&self, path: PathId, range: Option<Range>) -> Option<Vec<SemanticToken>> {
+    let file = self.source_files.get(&path)?;
+    let range = match range {
+      Some(range) => Some(file.syntax.pos_db.text_range(range)?),
+      None => None,
+    };
+    Some(semantic_tokens::get(file, range))
+  }
+
+  /// Returns the symbols in all the source files and the std basis whose names fuzzily match the
+  /// query, best matches first, up to a limit.
+  #[must_use]
+  pub fn workspace_symbols(&self, query: &str) -> Vec<WorkspaceSymbol> {
+    let std_basis_symbols = self.std_basis_symbols.get_or_init(|| {
+      self
+        .std_basis
+        .files()
+        .flat_map(|(name, file)| {
+          let path = sml_statics::DefPath::BuiltinLib(name);
+          workspace_symbol::get(&file.lower.arenas, file.lower.root, path, |idx| {
+            let ptr = file.lower.ptrs.hir_to_ast(idx)?;
+            file.pos_db.range(ptr.text_range())
+          })
+        })
+        .collect()
+    });
+    let symbols = self.source_file_symbols.iter().chain(std_basis_symbols.iter());
+    workspace_symbol::search(symbols, query, MAX_WORKSPACE_SYMBOLS)
+  }
+
+  /// Returns whether [`Self::fill_case`] can fill the `case` expression at this position.
+  #[must_use]
+  pub fn can_fill_case(&self, pos: WithPath<Position>) -> bool {
+    self.get_case_and_variants(pos).is_some()
+  }
+
   /// Given a position on a `case` expression, return the code and its range to fill the case with
   /// all of the variants of the head's type.
   #[must_use]
   pub fn fill_case(&self, pos: WithPath<Position>) -> Option<(Range, String)> {
-    let ft = self.get_file_and_token(pos)?;
-    let (ptr, _) = ft.get_ptr_and_idx()?;
-    let ptr = ptr.cast::<ast::CaseExp>()?;
-    let case = ptr.to_node(ft.file.syntax.parse.root.syntax());
+    let (file, case, variants) = self.get_case_and_variants(pos)?;
     let range = TextRange::empty(case.syntax().text_range().end());
-    let range = ft.file.syntax.pos_db.range(range)?;
-    let head_ast = case.exp()?;
-    let head_ptr = SyntaxNodePtr::new(head_ast.syntax());
-    let head = ft.file.syntax.lower.ptrs.ast_to_hir(&head_ptr)?;
-    let variants = ft.file.info.get_variants(&self.syms, head)?;
+    let range = file.syntax.pos_db.range(range)?;
     let case = CaseDisplay {
       needs_starting_bar: case.matcher().map_or(false, |x| x.match_rules().count() > 0),
       variants: &variants,
@@ -167,6 +649,117 @@ impl Analysis {
     Some((range, case.to_string()))
   }
 
+  /// Returns whether [`Self::fill_missing_arms`] can fill arms at this position.
+  #[must_use]
+  pub fn can_fill_missing_arms(&self, pos: WithPath<Position>) -> bool {
+    self
+      .file_and_offset(pos)
+      .map_or(false, |(file, offset)| missing_arms::is_available(file, offset))
+  }
+
+  /// Returns where to insert the arms that the innermost non-exhaustive `case`, `fn`, or `fun`
+  /// containing this position is missing, and the text of those arms.
+  #[must_use]
+  pub fn fill_missing_arms(&self, pos: WithPath<Position>) -> Option<(Range, String)> {
+    let (file, offset) = self.file_and_offset(pos)?;
+    let (range, new_text) = missing_arms::get(file, &self.syms, offset)?;
+    Some((file.syntax.pos_db.range(range)?, new_text))
+  }
+
+  /// Returns whether [`Self::stub_missing_items`] can stub items at this position.
+  #[must_use]
+  pub fn can_stub_missing_items(&self, pos: WithPath<Position>) -> bool {
+    self.file_and_offset(pos).map_or(false, |(file, offset)| stubs::is_available(file, offset))
+  }
+
+  /// Returns where to insert stubs for the items that the innermost structure containing this
+  /// position is missing from its signature, and the text of those stubs.
+  #[must_use]
+  pub fn stub_missing_items(&self, pos: WithPath<Position>) -> Option<(Range, String)> {
+    let (file, offset) = self.file_and_offset(pos)?;
+    let (range, new_text) = stubs::get(file, offset)?;
+    Some((file.syntax.pos_db.range(range)?, new_text))
+  }
+
+  /// Returns whether [`Self::ignore_unused`] can ignore a value at this position.
+  #[must_use]
+  pub fn can_ignore_unused(&self, pos: WithPath<Position>) -> bool {
+    self.file_and_offset(pos).map_or(false, |(file, offset)| unused::can_ignore(file, offset))
+  }
+
+  /// Returns where to edit to stop binding the unused value at this position, and the text to
+  /// replace that range with.
+  #[must_use]
+  pub fn ignore_unused(&self, pos: WithPath<Position>) -> Option<(Range, String)> {
+    let (file, offset) = self.file_and_offset(pos)?;
+    let (range, new_text) = unused::ignore(file, offset)?;
+    Some((file.syntax.pos_db.range(range)?, new_text))
+  }
+
+  /// Returns whether [`Self::delete_unused_val`] can delete a declaration at this position.
+  #[must_use]
+  pub fn can_delete_unused_val(&self, pos: WithPath<Position>) -> bool {
+    self.file_and_offset(pos).map_or(false, |(file, offset)| unused::can_delete_val(file, offset))
+  }
+
+  /// Returns the range of the `val` declaration binding only the unused value at this position, if
+  /// the declaration may be deleted without changing the behavior of the program.
+  #[must_use]
+  pub fn delete_unused_val(&self, pos: WithPath<Position>) -> Option<Range> {
+    let (file, offset) = self.file_and_offset(pos)?;
+    let range = unused::delete_val(file, offset)?;
+    file.syntax.pos_db.range(range)
+  }
+
+  /// Returns a similar name in scope to replace the undefined name at this position with.
+  #[must_use]
+  pub fn get_similar_name(&self, pos: WithPath<Position>) -> Option<&str> {
+    let (_, _, similar) = self.get_undefined(pos)?;
+    Some(similar)
+  }
+
+  /// Returns the range of the undefined name at this position, and a similar name in scope to
+  /// replace it with.
+  #[must_use]
+  pub fn fix_undefined(&self, pos: WithPath<Position>) -> Option<(Range, String)> {
+    let (node, name, similar) = self.get_undefined(pos)?;
+    let range = name_token_range(&node, name)?;
+    let file = self.source_files.get(&pos.path)?;
+    Some((file.syntax.pos_db.range(range)?, similar.to_owned()))
+  }
+
+  /// Returns the range of the expression hole at this position.
+  #[must_use]
+  pub fn get_hole_range(&self, pos: WithPath<Position>) -> Option<Range> {
+    let (ft, node, _) = self.get_hole(pos)?;
+    ft.file.syntax.pos_db.range(node.text_range())
+  }
+
+  /// Returns the text to replace the expression hole at this position with for each of the values
+  /// in scope that may fill it, most specific first.
+  #[must_use]
+  pub fn get_hole_fills(&self, pos: WithPath<Position>) -> Option<Vec<String>> {
+    let (ft, node, idx) = self.get_hole(pos)?;
+    let is_arg = node
+      .parent()
+      .and_then(ast::AppExp::cast)
+      .and_then(|app| app.arg())
+      .map_or(false, |arg| *arg.syntax() == node);
+    let fix_env = self.cache.fix_env(pos.path);
+    let fills = ft
+      .file
+      .info
+      .get_hole_fills(&self.syms, idx)
+      .iter()
+      .take(MAX_HOLE_FILLS)
+      .map(|fill| {
+        let infix = fix_env.map_or(false, |fix_env| fix_env.contains_key(fill.name.as_str()));
+        HoleFillDisplay { fill, is_arg, infix }.to_string()
+      })
+      .collect();
+    Some(fills)
+  }
+
   /// Format the given file, and return the end position of the file.
   ///
   /// # Errors
@@ -183,6 +776,22 @@ impl Analysis {
     Ok((buf, file.syntax.pos_db.end_position()))
   }
 
+  /// returns the `case` expression at this position, and the variants of the type of its head.
+  fn get_case_and_variants(
+    &self,
+    pos: WithPath<Position>,
+  ) -> Option<(&mlb_statics::SourceFile, ast::CaseExp, Vec<(str_util::Name, bool)>)> {
+    let ft = self.get_file_and_token(pos)?;
+    let (ptr, _) = ft.get_ptr_and_idx()?;
+    let ptr = ptr.cast::<ast::CaseExp>()?;
+    let case = ptr.to_node(ft.file.syntax.parse.root.syntax());
+    let head_ast = case.exp()?;
+    let head_ptr = SyntaxNodePtr::new(head_ast.syntax());
+    let head = ft.file.syntax.lower.ptrs.ast_to_hir(&head_ptr)?;
+    let variants = ft.file.info.get_variants(&self.syms, head)?;
+    Some((ft.file, case, variants))
+  }
+
   fn get_file_and_token(&self, pos: WithPath<Position>) -> Option<FileAndToken<'_>> {
     let file = self.source_files.get(&pos.path)?;
     let idx = file.syntax.pos_db.text_size(pos.val)?;
@@ -203,16 +812,214 @@ impl Analysis {
     Some(FileAndToken { file, token })
   }
 
-  fn def_to_path_and_range(&self, def: sml_statics::Def) -> Option<WithPath<Range>> {
+  /// Returns the def, plus all of the signature specs and implementations related to it.
+  fn related_defs(&self, def: sml_statics::Def) -> FxHashSet<sml_statics::Def> {
+    let mut ret = FxHashSet::<sml_statics::Def>::default();
+    let mut stack = vec![def];
+    while let Some(def) = stack.pop() {
+      if !ret.insert(def) {
+        continue;
+      }
+      for file in self.source_files.values() {
+        for &(spec, other) in file.info.impls() {
+          if spec == def {
+            stack.push(other);
+          } else if other == def {
+            stack.push(spec);
+          }
+        }
+      }
+    }
+    ret
+  }
+
+  fn file_and_offset(
+    &self,
+    pos: WithPath<Position>,
+  ) -> Option<(&mlb_statics::SourceFile, TextSize)> {
+    let file = self.source_files.get(&pos.path)?;
+    let offset = file.syntax.pos_db.text_size(pos.val)?;
+    Some((file, offset))
+  }
+
+  /// returns the innermost node with an undefined name containing the position, the name, and a
+  /// similar name in scope.
+  fn get_undefined(&self, pos: WithPath<Position>) -> Option<(SyntaxNode, &str, &str)> {
+    let (file, offset) = self.file_and_offset(pos)?;
+    let root = file.syntax.parse.root.syntax();
+    file
+      .statics_errors
+      .iter()
+      .filter_map(|err| {
+        let (name, similar) = err.similar()?;
+        let node = file.syntax.lower.ptrs.hir_to_ast(err.idx())?.to_node(root);
+        node.text_range().contains_inclusive(offset).then_some((node, name, similar))
+      })
+      .min_by_key(|(node, _, _)| node.text_range().len())
+  }
+
+  /// returns the expression hole at the position, its syntax, and its index.
+  fn get_hole(
+    &self,
+    pos: WithPath<Position>,
+  ) -> Option<(FileAndToken<'_>, SyntaxNode, sml_hir::Idx)> {
+    let ft = self.get_file_and_token(pos)?;
+    let (ptr, idx) = ft.get_ptr_and_idx()?;
+    match idx {
+      sml_hir::Idx::Exp(exp)
+        if matches!(ft.file.syntax.lower.arenas.exp[exp], sml_hir::Exp::Hole) => {}
+      _ => return None,
+    }
+    let node = ptr.to_node(ft.file.syntax.parse.root.syntax());
+    Some((ft, node, idx))
+  }
+
+  /// returns the defs related to the item at the token, if they may all be renamed, i.e. none of
+  /// them are in built-in libraries.
+  fn renamable_defs(
+    &self,
+    ft: &FileAndToken<'_>,
+    path: PathId,
+  ) -> Option<FxHashSet<sml_statics::Def>> {
+    let (_, idx) = ft.get_ptr_and_idx()?;
+    let def = ft.get_def_or_self(path, idx)?;
+    let defs = self.related_defs(def);
+    let all_regular = defs
+      .iter()
+      .all(|def| matches!(def, sml_statics::Def::Path(sml_statics::DefPath::Regular(_), _)));
+    all_regular.then_some(defs)
+  }
+
+  /// returns the labels for the record expression or pattern at the idx.
+  fn record_labels(
+    &self,
+    file: &mlb_statics::SourceFile,
+    idx: sml_hir::Idx,
+  ) -> Option<Vec<CompletionItem>> {
+    if let sml_hir::Idx::Exp(exp) = idx {
+      // the record expression's own type has only the labels written so far, so try to use the
+      // type of the function it's an argument to.
+      let func = file.syntax.lower.arenas.exp.iter().find_map(|(_, other)| match other {
+        sml_hir::Exp::App(func, Some(arg)) if *arg == exp => *func,
+        _ => None,
+      });
+      if let Some(func) = func {
+        return file.info.get_record_labels(&self.syms, func.into(), true);
+      }
+    }
+    file.info.get_record_labels(&self.syms, idx, false)
+  }
+
+  fn def_doc(&self, def: sml_statics::Def) -> Option<&str> {
+    match def {
+      sml_statics::Def::Path(path, idx) => {
+        let info = match path {
+          sml_statics::DefPath::Regular(path) => &self.source_files.get(&path)?.info,
+          sml_statics::DefPath::BuiltinLib(name) => self.std_basis.get_info(name)?,
+        };
+        info.get_doc(idx)
+      }
+      sml_statics::Def::Primitive => None,
+    }
+  }
+
+  /// returns the binding of the item at this position, if it is a binding in a source file.
+  fn call_hierarchy_def(
+    &self,
+    pos: WithPath<Position>,
+  ) -> Option<(PathId, sml_hir::la_arena::Idx<sml_hir::Pat>)> {
+    let ft = self.get_file_and_token(pos)?;
+    if !is_name_like(ft.token.kind()) {
+      return None;
+    }
+    let (_, idx) = ft.get_ptr_and_idx()?;
+    match ft.get_def_or_self(pos.path, idx)? {
+      sml_statics::Def::Path(sml_statics::DefPath::Regular(path), sml_hir::Idx::Pat(pat)) => {
+        Some((path, pat))
+      }
+      _ => None,
+    }
+  }
+
+  fn call_hierarchy_item(&self, def: sml_statics::Def) -> Option<CallHierarchyItem> {
+    match def {
+      sml_statics::Def::Path(sml_statics::DefPath::Regular(path), sml_hir::Idx::Pat(pat)) => {
+        call_hierarchy::item(self.source_files.get(&path)?, path, pat)
+      }
+      _ => None,
+    }
+  }
+
+  /// Returns the ranges of the name tokens with the given text in the uses of the defs, and
+  /// optionally in the defs themselves.
+  fn name_ranges(
+    &self,
+    defs: &FxHashSet<sml_statics::Def>,
+    name: &str,
+    include_def: bool,
+  ) -> Vec<WithPath<Range>> {
+    let mut ret = Vec::<WithPath<Range>>::new();
+    for (&path, file) in &self.source_files {
+      let root = file.syntax.parse.root.syntax();
+      let uses = file.info.defs().filter_map(|(idx, def)| defs.contains(&def).then_some(idx));
+      let def_sites = defs.iter().filter_map(|&def| match def {
+        sml_statics::Def::Path(sml_statics::DefPath::Regular(p), idx) => {
+          (include_def && p == path).then_some(idx)
+        }
+        sml_statics::Def::Path(sml_statics::DefPath::BuiltinLib(_), _)
+        | sml_statics::Def::Primitive => None,
+      });
+      let mut ranges: Vec<_> = uses
+        .chain(def_sites)
+        .filter_map(|idx| {
+          let ptr = file.syntax.lower.ptrs.hir_to_ast(idx)?;
+          name_token_range(&ptr.to_node(root), name)
+        })
+        .collect();
+      // many indices may point at the same syntax.
+      ranges.sort_unstable_by_key(|r| (r.start(), r.end()));
+      ranges.dedup();
+      ret.extend(ranges.into_iter().filter_map(|r| Some(path.wrap(file.syntax.pos_db.range(r)?))));
+    }
+    ret
+  }
+
+  /// Returns the namespace of the def, if it is a structure, signature, or functor.
+  fn def_namespace(&self, def: sml_statics::Def) -> Option<sml_statics::basis::Namespace> {
     let (path, idx) = match def {
-      sml_statics::Def::Path(sml_statics::DefPath::Regular(a), b) => (a, b),
-      sml_statics::Def::Path(sml_statics::DefPath::BuiltinLib(_), _)
-      | sml_statics::Def::Primitive => return None,
+      sml_statics::Def::Path(sml_statics::DefPath::Regular(path), sml_hir::Idx::StrDec(idx)) => {
+        (path, idx)
+      }
+      _ => return None,
     };
-    let def_file = self.source_files.get(&path)?;
-    let ptr = def_file.syntax.lower.ptrs.hir_to_ast(idx)?;
-    let def_range = ptr.to_node(def_file.syntax.parse.root.syntax()).text_range();
-    Some(path.wrap(def_file.syntax.pos_db.range(def_range)?))
+    let file = self.source_files.get(&path)?;
+    match file.syntax.lower.arenas.str_dec[idx] {
+      sml_hir::StrDec::Structure(_) => Some(sml_statics::basis::Namespace::Structure),
+      sml_hir::StrDec::Signature(_) => Some(sml_statics::basis::Namespace::Signature),
+      sml_hir::StrDec::Functor(_) => Some(sml_statics::basis::Namespace::Functor),
+      sml_hir::StrDec::Dec(_) | sml_hir::StrDec::Local(_, _) | sml_hir::StrDec::Seq(_) => None,
+    }
+  }
+
+  fn def_to_path_and_range(&self, def: sml_statics::Def) -> Option<(sml_statics::DefPath, Range)> {
+    let (path, idx) = match def {
+      sml_statics::Def::Path(a, b) => (a, b),
+      sml_statics::Def::Primitive => return None,
+    };
+    let range = match path {
+      sml_statics::DefPath::Regular(path) => {
+        let def_file = self.source_files.get(&path)?;
+        let ptr = def_file.syntax.lower.ptrs.hir_to_ast(idx)?;
+        let def_range = ptr.to_node(def_file.syntax.parse.root.syntax()).text_range();
+        def_file.syntax.pos_db.range(def_range)?
+      }
+      sml_statics::DefPath::BuiltinLib(name) => {
+        let def_file = self.std_basis.get_file(name)?;
+        let ptr = def_file.lower.ptrs.hir_to_ast(idx)?;
+        def_file.pos_db.range(ptr.text_range())?
+      }
+    };
+    Some((path, range))
   }
 }
 
@@ -223,17 +1030,29 @@ pub enum StdBasis {
   Minimal,
   /// The full one.
   Full,
+  /// The full one, but analyzed when first used, instead of ahead of time by the build script.
+  /// Much slower. Mostly useful to check the ahead-of-time results are the same.
+  FullLive,
 }
 
 impl StdBasis {
   fn to_mlb_statics(self) -> mlb_statics::StdBasis {
     match self {
       StdBasis::Minimal => mlb_statics::StdBasis::minimal(),
-      StdBasis::Full => mlb_statics::StdBasis::full(),
+      StdBasis::Full => FULL_STD_BASIS.clone(),
+      StdBasis::FullLive => mlb_statics::StdBasis::full(),
     }
   }
 }
 
+/// analyzed by the build script.
+static FULL_STD_BASIS: Lazy<mlb_statics::StdBasis> = Lazy::new(|| {
+  mlb_statics::StdBasis::full_from_bytes(include_bytes!(concat!(
+    env!("OUT_DIR"),
+    "/std_basis_full.bin"
+  )))
+});
+
 /// An error when formatting a file.
 #[derive(Debug)]
 pub enum FormatError {
@@ -245,6 +1064,47 @@ pub enum FormatError {
   Format(sml_fmt::Error),
 }
 
+/// Help for the signature of a function.
+#[derive(Debug)]
+pub struct SignatureHelp {
+  /// The name and type of the function.
+  pub label: String,
+  /// The documentation for the function.
+  pub doc: Option<String>,
+  /// The byte ranges of the parameters in the label. A tuple parameter has a range for each
+  /// component.
+  pub params: Vec<std::ops::Range<usize>>,
+  /// The index into `params` of the parameter being written.
+  pub active_param: Option<usize>,
+}
+
+/// A range that may be folded.
+#[derive(Debug)]
+pub struct FoldingRange {
+  /// The range.
+  pub range: Range,
+  /// The kind.
+  pub kind: FoldingRangeKind,
+}
+
+/// A kind of folding range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FoldingRangeKind {
+  /// A syntactic block, like `let ... in ... end`.
+  Region,
+  /// A comment.
+  Comment,
+}
+
+/// An error when renaming.
+#[derive(Debug)]
+pub enum RenameError {
+  /// The new name was not a valid name.
+  InvalidName,
+  /// There was no item to rename.
+  NoItem,
+}
+
 fn priority(kind: SyntaxKind) -> u8 {
   match kind {
     SyntaxKind::Name => 5,
@@ -260,6 +1120,106 @@ fn priority(kind: SyntaxKind) -> u8 {
   }
 }
 
+fn is_name_like(kind: SyntaxKind) -> bool {
+  matches!(kind, SyntaxKind::Name | SyntaxKind::Star)
+}
+
+/// returns whether `new` is a valid SML name to replace `old` with.
+///
+/// it must not be a reserved word, and it must be alphanumeric iff `old` is, since e.g. a symbolic
+/// name may appear without spaces around it.
+fn is_valid_name(old: &str, new: &str) -> bool {
+  let is_alpha = |s: &str| s.starts_with(|c: char| c.is_ascii_alphabetic());
+  if is_alpha(old) != is_alpha(new) {
+    return false;
+  }
+  let mut chars = new.chars();
+  match chars.next() {
+    None => false,
+    Some(c) => {
+      if c.is_ascii_alphabetic() {
+        chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '\''))
+          && SyntaxKind::keyword(new.as_bytes()).is_none()
+      } else {
+        new.chars().all(|c| "!%&$#+-/:<=>?@\\~`^|*".contains(c))
+          && (new == "*"
+            || !SyntaxKind::PUNCTUATION.iter().any(|&(text, _)| text == new.as_bytes()))
+      }
+    }
+  }
+}
+
+/// returns the record expression or pattern node if the token is where a label would be in it.
+fn record_label_position(token: &SyntaxToken) -> Option<SyntaxNode> {
+  for node in token.parent_ancestors() {
+    match node.kind() {
+      SyntaxKind::Lab
+      | SyntaxKind::NameStarEq
+      | SyntaxKind::ExpRow
+      | SyntaxKind::PatRow
+      | SyntaxKind::LabPatRow
+      | SyntaxKind::RestPatRow => {}
+      SyntaxKind::RecordExp | SyntaxKind::RecordPat => return Some(node),
+      _ => return None,
+    }
+  }
+  None
+}
+
+/// returns the range of the first name token in the node with the given text.
+fn name_token_range(node: &SyntaxNode, name: &str) -> Option<TextRange> {
+  node
+    .descendants_with_tokens()
+    .filter_map(sml_syntax::rowan::NodeOrToken::into_token)
+    .find(|tok| is_name_like(tok.kind()) && tok.text() == name)
+    .map(|tok| tok.text_range())
+}
+
+fn bas_dec_exports<'a>(
+  ac: &mut Vec<(
+    sml_statics::basis::Namespace,
+    &'a WithRange<str_util::Name>,
+    &'a WithRange<str_util::Name>,
+  )>,
+  dec: &'a mlb_statics::BasDec,
+) {
+  match dec {
+    mlb_statics::BasDec::Basis(_, exp) => bas_exp_exports(ac, exp),
+    mlb_statics::BasDec::Export(ns, lhs, rhs) => ac.push((*ns, lhs, rhs)),
+    mlb_statics::BasDec::Local(local_dec, in_dec) => {
+      bas_dec_exports(ac, local_dec);
+      bas_dec_exports(ac, in_dec);
+    }
+    mlb_statics::BasDec::Seq(decs) => {
+      for dec in decs {
+        bas_dec_exports(ac, dec);
+      }
+    }
+    mlb_statics::BasDec::Open(_)
+    | mlb_statics::BasDec::Path(_, _)
+    | mlb_statics::BasDec::SourcePathSet(_)
+    | mlb_statics::BasDec::Builtin(_) => {}
+  }
+}
+
+fn bas_exp_exports<'a>(
+  ac: &mut Vec<(
+    sml_statics::basis::Namespace,
+    &'a WithRange<str_util::Name>,
+    &'a WithRange<str_util::Name>,
+  )>,
+  exp: &'a mlb_statics::BasExp,
+) {
+  match exp {
+    mlb_statics::BasExp::Bas(dec) => bas_dec_exports(ac, dec),
+    mlb_statics::BasExp::Name(_) => {}
+    mlb_statics::BasExp::Let(dec, exp) => {
+      bas_dec_exports(ac, dec);
+      bas_exp_exports(ac, exp);
+    }
+  }
+}
+
 #[derive(Debug, Clone, Copy)]
 struct DiagnosticsOptions {
   lines: config::ErrorLines,
@@ -398,12 +1358,56 @@ impl fmt::Display for ArmDisplay<'_> {
   }
 }
 
+struct HoleFillDisplay<'a> {
+  fill: &'a sml_statics::HoleFill,
+  /// arguments must be atomic, so an application filling one needs parentheses.
+  is_arg: bool,
+  /// whether the name is infix where the hole is, so it needs `op`.
+  infix: bool,
+}
+
+impl fmt::Display for HoleFillDisplay<'_> {
+  fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+    let parens = self.is_arg && self.fill.args != 0;
+    if parens {
+      write!(f, "(")?;
+    }
+    if self.infix {
+      write!(f, "op ")?;
+    }
+    write!(f, "{}", self.fill.name)?;
+    for _ in 0..self.fill.args {
+      write!(f, " _")?;
+    }
+    if parens {
+      write!(f, ")")?;
+    }
+    Ok(())
+  }
+}
+
 struct FileAndToken<'a> {
   file: &'a mlb_statics::SourceFile,
   token: SyntaxToken,
 }
 
 impl FileAndToken<'_> {
+  /// returns the def for the idx. if there is none, the idx may itself be a def site, like a
+  /// variable pattern, so we return a def for the idx in that case.
+  fn get_def_or_self(&self, path: PathId, idx: sml_hir::Idx) -> Option<sml_statics::Def> {
+    match self.file.info.get_def(idx) {
+      Some(x) => Some(x),
+      None => matches!(
+        idx,
+        sml_hir::Idx::Pat(_)
+          | sml_hir::Idx::Dec(_)
+          | sml_hir::Idx::StrDec(_)
+          | sml_hir::Idx::Spec(_)
+      )
+      .then_some(sml_statics::Def::Path(sml_statics::DefPath::Regular(path), idx)),
+    }
+  }
+
   fn get_ptr_and_idx(&self) -> Option<(SyntaxNodePtr, sml_hir::Idx)> {
     let mut node = self.token.parent()?;
     loop {
@@ -0,0 +1,119 @@
+//! Filling in the arms that a non-exhaustive match is missing.
+
+use sml_syntax::ast::{self, AstNode as _};
+use sml_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+use std::fmt::Write as _;
+use text_pos::PositionDb;
+use text_size_util::{TextRange, TextSize};
+
+/// Returns whether there are missing arms to insert for the offset, without computing them.
+pub(crate) fn is_available(file: &mlb_statics::SourceFile, offset: TextSize) -> bool {
+  find(file, offset).is_some()
+}
+
+/// Returns where to insert the missing arms of the innermost non-exhaustive `case`, `fn`, or `fun`
+/// containing the offset, and the text of the arms.
+pub(crate) fn get(
+  file: &mlb_statics::SourceFile,
+  syms: &sml_statics::Syms,
+  offset: TextSize,
+) -> Option<(TextRange, String)> {
+  let (matcher, err) = find(file, offset)?;
+  let pos_db = &file.syntax.pos_db;
+  let mut ret = String::new();
+  let last = match matcher {
+    Matcher::Fun(fun_bind) => {
+      let case = fun_bind.fun_bind_cases().next()?;
+      let (name, curried) = match case.fun_bind_case_head()? {
+        ast::FunBindCaseHead::PrefixFunBindCaseHead(head) => {
+          (head.name_star_eq()?.token.text().to_owned(), case.pats().count())
+        }
+        // the lhs and rhs are the first curried parameter, as a tuple.
+        ast::FunBindCaseHead::InfixFunBindCaseHead(head) => {
+          (format!("op {}", head.name_star_eq()?.token.text()), case.pats().count() + 1)
+        }
+      };
+      let last = fun_bind.fun_bind_cases().last()?;
+      let indent = bar_indent(pos_db, last.syntax())?;
+      for pat in err.missing_pats(syms, Some(curried))? {
+        write!(ret, "\n{indent}| {name} {pat} = _").unwrap();
+      }
+      last.syntax().clone()
+    }
+    Matcher::Exp(matcher) => {
+      let last = matcher.match_rules().last()?;
+      let indent = bar_indent(pos_db, last.syntax())?;
+      for pat in err.missing_pats(syms, None)? {
+        write!(ret, "\n{indent}| {pat} => _").unwrap();
+      }
+      last.syntax().clone()
+    }
+  };
+  let end = non_trivia(
+    last.descendants_with_tokens().filter_map(sml_syntax::rowan::NodeOrToken::into_token),
+  )
+  .last()?
+  .text_range()
+  .end();
+  Some((TextRange::empty(end), ret))
+}
+
+enum Matcher {
+  Fun(ast::FunBind),
+  Exp(ast::Matcher),
+}
+
+/// returns the innermost non-exhaustive matcher containing the offset, and its error.
+fn find(
+  file: &mlb_statics::SourceFile,
+  offset: TextSize,
+) -> Option<(Matcher, &sml_statics::Error)> {
+  let root = file.syntax.parse.root.syntax();
+  let (node, err) = file
+    .statics_errors
+    .iter()
+    .filter(|err| err.code() == diagnostic_util::Code::n(5011))
+    .filter_map(|err| {
+      let node = file.syntax.lower.ptrs.hir_to_ast(err.idx())?.to_node(root);
+      node.text_range().contains_inclusive(offset).then_some((node, err))
+    })
+    .min_by_key(|(node, _)| node.text_range().len())?;
+  if let Some(fun_bind) = ast::FunBind::cast(node.clone()) {
+    return Some((Matcher::Fun(fun_bind), err));
+  }
+  let matcher = match ast::Exp::cast(node)? {
+    ast::Exp::CaseExp(exp) => exp.matcher(),
+    ast::Exp::FnExp(exp) => exp.matcher(),
+    // `handle` need not be exhaustive, since unhandled exceptions propagate.
+    _ => None,
+  };
+  Some((Matcher::Exp(matcher?), err))
+}
+
+/// returns the indentation for a new bar before a clause like the node. if there is already a bar
+/// before the node on the same line, use the same indentation as it. else, put the bar before
+/// where the node starts.
+fn bar_indent(pos_db: &PositionDb, node: &SyntaxNode) -> Option<String> {
+  let first = node.first_token()?;
+  let start = pos_db.range(first.text_range())?.start;
+  let prev = non_trivia(std::iter::successors(first.prev_token(), SyntaxToken::prev_token)).next();
+  let col = match prev {
+    Some(bar) if bar.kind() == SyntaxKind::Bar => {
+      let bar = pos_db.range(bar.text_range())?.start;
+      if bar.line == start.line {
+        bar.character
+      } else {
+        start.character.saturating_sub(2)
+      }
+    }
+    _ => start.character.saturating_sub(2),
+  };
+  Some(" ".repeat(usize::try_from(col).ok()?))
+}
+
+fn non_trivia<I>(iter: I) -> impl Iterator<Item = SyntaxToken>
+where
+  I: Iterator<Item = SyntaxToken>,
+{
+  iter.filter(|tok| !matches!(tok.kind(), SyntaxKind::Whitespace | SyntaxKind::BlockComment))
+}
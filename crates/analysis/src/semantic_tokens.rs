@@ -0,0 +1,205 @@
+//! Semantic tokens, classified with information from statics.
+
+use sml_syntax::ast::{self, AstNode as _, SyntaxNodePtr};
+use sml_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+use text_pos::Range;
+use text_size_util::TextRange;
+
+/// A token in a document, classified by what it refers to.
+#[derive(Debug)]
+pub struct SemanticToken {
+  /// The range of the token. Always on one line.
+  pub range: Range,
+  /// The kind.
+  pub kind: SemanticTokenKind,
+  /// Whether this token is where the item is declared.
+  pub declaration: bool,
+  /// Whether the item is defined in the std basis.
+  pub default_library: bool,
+}
+
+/// A kind of semantic token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SemanticTokenKind {
+  /// A datatype constructor.
+  Constructor,
+  /// An exception.
+  Exception,
+  /// A value that is not a constructor or exception, like a variable or function.
+  Value,
+  /// A type.
+  Type,
+  /// A structure.
+  Structure,
+  /// A signature.
+  Signature,
+  /// A functor.
+  Functor,
+  /// A type variable.
+  TypeVariable,
+  /// A record label.
+  Label,
+  /// A symbolic or infix value.
+  Operator,
+}
+
+/// Returns the semantic tokens in the file, optionally only those in the range, in order.
+pub(crate) fn get(file: &mlb_statics::SourceFile, range: Option<TextRange>) -> Vec<SemanticToken> {
+  file
+    .syntax
+    .parse
+    .root
+    .syntax()
+    .descendants_with_tokens()
+    .filter_map(sml_syntax::rowan::NodeOrToken::into_token)
+    .filter(|token| range.map_or(true, |range| range.intersect(token.text_range()).is_some()))
+    .filter_map(|token| {
+      let class = classify(file, &token)?;
+      let default_library = matches!(
+        class.def,
+        Some(
+          sml_statics::Def::Path(sml_statics::DefPath::BuiltinLib(_), _)
+            | sml_statics::Def::Primitive
+        )
+      );
+      Some(SemanticToken {
+        range: file.syntax.pos_db.range(token.text_range())?,
+        kind: class.kind,
+        declaration: class.declaration,
+        default_library,
+      })
+    })
+    .collect()
+}
+
+struct Class {
+  kind: SemanticTokenKind,
+  declaration: bool,
+  def: Option<sml_statics::Def>,
+}
+
+impl Class {
+  fn new(kind: SemanticTokenKind) -> Self {
+    Self { kind, declaration: false, def: None }
+  }
+
+  fn decl(kind: SemanticTokenKind) -> Self {
+    Self { kind, declaration: true, def: None }
+  }
+}
+
+fn classify(file: &mlb_statics::SourceFile, token: &SyntaxToken) -> Option<Class> {
+  let parent = token.parent()?;
+  match token.kind() {
+    SyntaxKind::TyVar => {
+      let declaration = parent.kind() == SyntaxKind::TyVarArg;
+      return Some(Class { kind: SemanticTokenKind::TypeVariable, declaration, def: None });
+    }
+    SyntaxKind::Name | SyntaxKind::Star | SyntaxKind::Eq | SyntaxKind::IntLit => {}
+    _ => return None,
+  }
+  let mut ret = match parent.kind() {
+    SyntaxKind::Lab => Class::new(SemanticTokenKind::Label),
+    SyntaxKind::NameStarEq => name_star_eq(file, &parent)?,
+    SyntaxKind::DatBind | SyntaxKind::TyBind | SyntaxKind::DatCopyDec => {
+      Class::decl(SemanticTokenKind::Type)
+    }
+    SyntaxKind::StrBind | SyntaxKind::FunctorArgNameSigExp => {
+      Class::decl(SemanticTokenKind::Structure)
+    }
+    SyntaxKind::SigBind => Class::decl(SemanticTokenKind::Signature),
+    SyntaxKind::FunctorBind => Class::decl(SemanticTokenKind::Functor),
+    SyntaxKind::NameSigExp => with_def(file, &parent, SemanticTokenKind::Signature),
+    SyntaxKind::AppStrExp => with_def(file, &parent, SemanticTokenKind::Functor),
+    _ => return None,
+  };
+  if ret.kind == SemanticTokenKind::Value && !is_alphanumeric(token.text()) {
+    ret.kind = SemanticTokenKind::Operator;
+  }
+  Some(ret)
+}
+
+/// classifies the name in a `NameStarEq` node by where that node is.
+fn name_star_eq(file: &mlb_statics::SourceFile, node: &SyntaxNode) -> Option<Class> {
+  let parent = node.parent()?;
+  let ret = match parent.kind() {
+    SyntaxKind::NameStarEqDot => {
+      // every name in a path but the last is a structure.
+      if parent.children_with_tokens().any(|x| x.kind() == SyntaxKind::Dot) {
+        return Some(Class::new(SemanticTokenKind::Structure));
+      }
+      let path = parent.parent()?;
+      let ctx = path.parent()?;
+      match ctx.kind() {
+        SyntaxKind::PathExp => val(file, &ctx, false),
+        SyntaxKind::ConPat => {
+          let mut ret = val(file, &ctx, false);
+          // a lone name with no argument that isn't a constructor or exception is a variable.
+          let no_arg = ast::ConPat::cast(ctx.clone()).map_or(false, |x| x.pat().is_none());
+          let lone_name = no_arg && path.children().count() == 1;
+          ret.declaration = ret.kind == SemanticTokenKind::Value && lone_name;
+          ret
+        }
+        SyntaxKind::ConTy | SyntaxKind::OneArgConTy => {
+          with_def(file, &ctx, SemanticTokenKind::Type)
+        }
+        SyntaxKind::PathStrExp => with_def(file, &ctx, SemanticTokenKind::Structure),
+        SyntaxKind::WhereTypeSigExp | SyntaxKind::DatCopyDec => Class::new(SemanticTokenKind::Type),
+        SyntaxKind::OpenDec | SyntaxKind::WhereSigExp => Class::new(SemanticTokenKind::Structure),
+        SyntaxKind::EqPath => Class::new(SemanticTokenKind::Exception),
+        SyntaxKind::PathEq => {
+          let is_ty = ctx.parent().map_or(false, |sharing| {
+            sharing.children_with_tokens().any(|x| x.kind() == SyntaxKind::TypeKw)
+          });
+          let kind = if is_ty { SemanticTokenKind::Type } else { SemanticTokenKind::Structure };
+          Class::new(kind)
+        }
+        _ => Class::new(SemanticTokenKind::Value),
+      }
+    }
+    SyntaxKind::ConBind => Class::decl(SemanticTokenKind::Constructor),
+    SyntaxKind::ExBind => Class::decl(SemanticTokenKind::Exception),
+    SyntaxKind::PrefixFunBindCaseHead
+    | SyntaxKind::InfixFunBindCaseHead
+    | SyntaxKind::LabPatRow => Class::decl(SemanticTokenKind::Value),
+    SyntaxKind::InfixExp | SyntaxKind::InfixPat => val(file, &parent, true),
+    SyntaxKind::InfixDec | SyntaxKind::InfixrDec | SyntaxKind::NonfixDec => {
+      Class::new(SemanticTokenKind::Operator)
+    }
+    _ => return None,
+  };
+  Some(ret)
+}
+
+/// classifies a value by its id status, for the HIR index of the node. if `infix`, the node is an
+/// infix application.
+fn val(file: &mlb_statics::SourceFile, node: &SyntaxNode, infix: bool) -> Class {
+  let mut idx = file.syntax.lower.ptrs.ast_to_hir(&SyntaxNodePtr::new(node));
+  // infix expressions lower to applications, and only the whole application has the pointer.
+  if let (true, Some(sml_hir::Idx::Exp(exp))) = (infix, idx) {
+    if let sml_hir::Exp::App(Some(func), _) = &file.syntax.lower.arenas.exp[exp] {
+      idx = Some((*func).into());
+    }
+  }
+  let kind = match idx.and_then(|idx| file.info.get_val_kind(idx)) {
+    Some(sml_statics::ValKind::Con) => SemanticTokenKind::Constructor,
+    Some(sml_statics::ValKind::Exn) => SemanticTokenKind::Exception,
+    Some(sml_statics::ValKind::Val) | None => {
+      if infix {
+        SemanticTokenKind::Operator
+      } else {
+        SemanticTokenKind::Value
+      }
+    }
+  };
+  Class { kind, declaration: false, def: idx.and_then(|idx| file.info.get_def(idx)) }
+}
+
+fn with_def(file: &mlb_statics::SourceFile, node: &SyntaxNode, kind: SemanticTokenKind) -> Class {
+  let idx = file.syntax.lower.ptrs.ast_to_hir(&SyntaxNodePtr::new(node));
+  Class { kind, declaration: false, def: idx.and_then(|idx| file.info.get_def(idx)) }
+}
+
+fn is_alphanumeric(s: &str) -> bool {
+  s.chars().next().map_or(false, |c| c.is_ascii_alphabetic() || c == '\'')
+}
@@ -0,0 +1,83 @@
+//! Stubbing out the items a structure is missing from its signature.
+
+use sml_syntax::ast::{self, AstNode as _};
+use sml_syntax::{SyntaxKind, SyntaxToken};
+use std::fmt::Write as _;
+use text_size_util::{TextRange, TextSize};
+
+/// Returns whether there are items to stub out for the offset, without computing the stubs.
+pub(crate) fn is_available(file: &mlb_statics::SourceFile, offset: TextSize) -> bool {
+  find(file, offset).is_some()
+}
+
+/// Returns where to insert stubs for the items missing from the innermost structure containing the
+/// offset that doesn't match its signature, and the text of the stubs.
+pub(crate) fn get(file: &mlb_statics::SourceFile, offset: TextSize) -> Option<(TextRange, String)> {
+  let (end, idx) = find(file, offset)?;
+  let indent = line_indent(&end);
+  let mut ret = String::new();
+  let starts_line = end.prev_token().map_or(true, |tok| starts_line(&tok));
+  let offset = if starts_line {
+    // insert at the start of the line with the `end`.
+    end.text_range().start() - TextSize::of(indent.as_str())
+  } else {
+    ret.push('\n');
+    end.text_range().start()
+  };
+  let stubs =
+    file.statics_errors.iter().filter(|err| err.idx() == idx).filter_map(|err| err.stub());
+  for line in stubs.flat_map(str::lines) {
+    writeln!(ret, "{indent}  {line}").unwrap();
+  }
+  if !starts_line {
+    ret.push_str(&indent);
+  }
+  Some((TextRange::empty(offset), ret))
+}
+
+/// returns the `end` of the innermost structure containing the offset that is missing items, and
+/// the index of the errors about the missing items.
+fn find(file: &mlb_statics::SourceFile, offset: TextSize) -> Option<(SyntaxToken, sml_hir::Idx)> {
+  let root = file.syntax.parse.root.syntax();
+  let (node, idx) = file
+    .statics_errors
+    .iter()
+    .filter(|err| err.stub().is_some())
+    .filter_map(|err| {
+      let node = file.syntax.lower.ptrs.hir_to_ast(err.idx())?.to_node(root);
+      node.text_range().contains_inclusive(offset).then_some((node, err.idx()))
+    })
+    .min_by_key(|(node, _)| node.text_range().len())?;
+  let mut str_exp = ast::StrExp::cast(node)?;
+  let struct_str_exp = loop {
+    match str_exp {
+      ast::StrExp::StructStrExp(x) => break x,
+      ast::StrExp::AscriptionStrExp(x) => str_exp = x.str_exp()?,
+      _ => return None,
+    }
+  };
+  let end = struct_str_exp
+    .syntax()
+    .children_with_tokens()
+    .filter_map(sml_syntax::rowan::NodeOrToken::into_token)
+    .find(|tok| tok.kind() == SyntaxKind::EndKw)?;
+  Some((end, idx))
+}
+
+fn starts_line(tok: &SyntaxToken) -> bool {
+  tok.kind() == SyntaxKind::Whitespace && tok.text().contains('\n')
+}
+
+/// returns the indentation of the line containing the token.
+fn line_indent(tok: &SyntaxToken) -> String {
+  let mut cur = tok.prev_token();
+  while let Some(tok) = cur {
+    if starts_line(&tok) {
+      let text = tok.text();
+      let indent = &text[text.rfind('\n').map_or(0, |x| x + 1)..];
+      return indent.to_owned();
+    }
+    cur = tok.prev_token();
+  }
+  String::new()
+}
@@ -0,0 +1,278 @@
+//! Symbols in documents, gotten from the syntax tree.
+
+use sml_syntax::ast::{self, AstNode as _};
+use sml_syntax::{SyntaxNode, SyntaxToken};
+use text_pos::{PositionDb, Range};
+
+/// A symbol in a document, like a structure or a value.
+#[derive(Debug)]
+pub struct DocumentSymbol {
+  /// The name.
+  pub name: String,
+  /// The kind.
+  pub kind: SymbolKind,
+  /// The range of the whole symbol, like the whole declaration.
+  pub range: Range,
+  /// The range of the name of the symbol. Contained in `range`.
+  pub selection_range: Range,
+  /// The symbols contained in this symbol, like the declarations in a structure.
+  pub children: Vec<DocumentSymbol>,
+}
+
+/// A kind of symbol.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+  /// A structure.
+  Structure,
+  /// A signature.
+  Signature,
+  /// A functor.
+  Functor,
+  /// A value that is a function.
+  Function,
+  /// A value that is not a function, or that we don't know is a function.
+  Value,
+  /// A type, like from `type` or `eqtype`.
+  Type,
+  /// A datatype.
+  Datatype,
+  /// A datatype constructor.
+  Constructor,
+  /// An exception.
+  Exception,
+}
+
+pub(crate) fn get(pos_db: &PositionDb, root: &ast::Root) -> Vec<DocumentSymbol> {
+  let mut ret = Vec::<DocumentSymbol>::new();
+  get_dec(pos_db, &mut ret, root.dec());
+  ret
+}
+
+fn get_dec(pos_db: &PositionDb, ac: &mut Vec<DocumentSymbol>, dec: Option<ast::Dec>) {
+  let dec = match dec {
+    Some(x) => x,
+    None => return,
+  };
+  for dwt_in_seq in dec.dec_with_tail_in_seqs() {
+    let dwt = match dwt_in_seq.dec_with_tail() {
+      Some(x) => x,
+      None => continue,
+    };
+    for dec in dwt.dec_in_seqs() {
+      if let Some(dec) = dec.dec_one() {
+        get_dec_one(pos_db, ac, dec);
+      }
+    }
+  }
+}
+
+fn get_dec_one(pos_db: &PositionDb, ac: &mut Vec<DocumentSymbol>, dec: ast::DecOne) {
+  match dec {
+    ast::DecOne::ValDec(dec) => {
+      for val_bind in dec.val_binds() {
+        let is_fn = matches!(val_bind.eq_exp().and_then(|x| x.exp()), Some(ast::Exp::FnExp(_)));
+        let kind = if is_fn { SymbolKind::Function } else { SymbolKind::Value };
+        let pat = match val_bind.pat() {
+          Some(x) => x,
+          None => continue,
+        };
+        for name in pat_names(&pat) {
+          ac.extend(symbol(pos_db, &name, kind, val_bind.syntax(), Vec::new()));
+        }
+      }
+    }
+    ast::DecOne::FunDec(dec) => {
+      for fun_bind in dec.fun_binds() {
+        let name = fun_bind.fun_bind_cases().find_map(|case| {
+          let name = match case.fun_bind_case_head()? {
+            ast::FunBindCaseHead::PrefixFunBindCaseHead(head) => head.name_star_eq(),
+            ast::FunBindCaseHead::InfixFunBindCaseHead(head) => head.name_star_eq(),
+          };
+          name.map(|x| x.token)
+        });
+        if let Some(name) = name {
+          ac.extend(symbol(pos_db, &name, SymbolKind::Function, fun_bind.syntax(), Vec::new()));
+        }
+      }
+    }
+    ast::DecOne::TyDec(dec) => get_ty_binds(pos_db, ac, dec.ty_binds()),
+    ast::DecOne::DatDec(dec) => {
+      get_dat_binds(pos_db, ac, dec.dat_binds());
+      get_ty_binds(pos_db, ac, dec.with_type().into_iter().flat_map(|x| x.ty_binds()));
+    }
+    ast::DecOne::DatCopyDec(dec) => {
+      if let Some(name) = dec.name() {
+        ac.extend(symbol(pos_db, &name, SymbolKind::Datatype, dec.syntax(), Vec::new()));
+      }
+    }
+    ast::DecOne::AbstypeDec(dec) => {
+      get_dat_binds(pos_db, ac, dec.dat_binds());
+      get_ty_binds(pos_db, ac, dec.with_type().into_iter().flat_map(|x| x.ty_binds()));
+      get_dec(pos_db, ac, dec.dec());
+    }
+    ast::DecOne::ExDec(dec) => {
+      for ex_bind in dec.ex_binds() {
+        if let Some(name) = ex_bind.name_star_eq() {
+          ac.extend(symbol(pos_db, &name.token, SymbolKind::Exception, ex_bind.syntax(), vec![]));
+        }
+      }
+    }
+    ast::DecOne::LocalDec(dec) => {
+      get_dec(pos_db, ac, dec.local_dec());
+      get_dec(pos_db, ac, dec.in_dec());
+    }
+    ast::DecOne::StructureDec(dec) => {
+      for str_bind in dec.str_binds() {
+        let name = match str_bind.name() {
+          Some(x) => x,
+          None => continue,
+        };
+        let mut children = Vec::<DocumentSymbol>::new();
+        match str_bind.eq_str_exp() {
+          Some(eq_str_exp) => get_str_exp(pos_db, &mut children, eq_str_exp.str_exp()),
+          // in a signature, a structure spec has only the ascription.
+          None => {
+            let sig_exp = str_bind.ascription_tail().and_then(|x| x.sig_exp());
+            get_sig_exp(pos_db, &mut children, sig_exp);
+          }
+        }
+        ac.extend(symbol(pos_db, &name, SymbolKind::Structure, str_bind.syntax(), children));
+      }
+    }
+    ast::DecOne::SignatureDec(dec) => {
+      for sig_bind in dec.sig_binds() {
+        let name = match sig_bind.name() {
+          Some(x) => x,
+          None => continue,
+        };
+        let mut children = Vec::<DocumentSymbol>::new();
+        get_sig_exp(pos_db, &mut children, sig_bind.sig_exp());
+        ac.extend(symbol(pos_db, &name, SymbolKind::Signature, sig_bind.syntax(), children));
+      }
+    }
+    ast::DecOne::FunctorDec(dec) => {
+      for functor_bind in dec.functor_binds() {
+        let name = match functor_bind.functor_name() {
+          Some(x) => x,
+          None => continue,
+        };
+        let mut children = Vec::<DocumentSymbol>::new();
+        match functor_bind.functor_arg() {
+          Some(ast::FunctorArg::FunctorArgNameSigExp(arg)) => {
+            if let Some(param_name) = arg.name() {
+              let mut param_children = Vec::<DocumentSymbol>::new();
+              get_sig_exp(pos_db, &mut param_children, arg.sig_exp());
+              children.extend(symbol(
+                pos_db,
+                &param_name,
+                SymbolKind::Structure,
+                arg.syntax(),
+                param_children,
+              ));
+            }
+          }
+          Some(ast::FunctorArg::Dec(arg)) => get_dec(pos_db, &mut children, Some(arg)),
+          None => {}
+        }
+        get_str_exp(pos_db, &mut children, functor_bind.body());
+        ac.extend(symbol(pos_db, &name, SymbolKind::Functor, functor_bind.syntax(), children));
+      }
+    }
+    ast::DecOne::HoleDec(_)
+    | ast::DecOne::OpenDec(_)
+    | ast::DecOne::InfixDec(_)
+    | ast::DecOne::InfixrDec(_)
+    | ast::DecOne::NonfixDec(_)
+    | ast::DecOne::DoDec(_)
+    | ast::DecOne::ExpDec(_)
+    | ast::DecOne::IncludeDec(_) => {}
+  }
+}
+
+fn get_str_exp(pos_db: &PositionDb, ac: &mut Vec<DocumentSymbol>, str_exp: Option<ast::StrExp>) {
+  match str_exp {
+    Some(ast::StrExp::StructStrExp(str_exp)) => get_dec(pos_db, ac, str_exp.dec()),
+    Some(ast::StrExp::AscriptionStrExp(str_exp)) => get_str_exp(pos_db, ac, str_exp.str_exp()),
+    Some(ast::StrExp::LetStrExp(str_exp)) => get_str_exp(pos_db, ac, str_exp.str_exp()),
+    Some(ast::StrExp::PathStrExp(_) | ast::StrExp::AppStrExp(_)) | None => {}
+  }
+}
+
+fn get_sig_exp(pos_db: &PositionDb, ac: &mut Vec<DocumentSymbol>, sig_exp: Option<ast::SigExp>) {
+  match sig_exp {
+    Some(ast::SigExp::SigSigExp(sig_exp)) => get_dec(pos_db, ac, sig_exp.dec()),
+    Some(ast::SigExp::WhereTypeSigExp(sig_exp)) => get_sig_exp(pos_db, ac, sig_exp.sig_exp()),
+    Some(ast::SigExp::WhereSigExp(sig_exp)) => get_sig_exp(pos_db, ac, sig_exp.sig_exp()),
+    Some(ast::SigExp::NameSigExp(_)) | None => {}
+  }
+}
+
+fn get_ty_binds<I>(pos_db: &PositionDb, ac: &mut Vec<DocumentSymbol>, iter: I)
+where
+  I: Iterator<Item = ast::TyBind>,
+{
+  for ty_bind in iter {
+    if let Some(name) = ty_bind.name() {
+      ac.extend(symbol(pos_db, &name, SymbolKind::Type, ty_bind.syntax(), Vec::new()));
+    }
+  }
+}
+
+fn get_dat_binds<I>(pos_db: &PositionDb, ac: &mut Vec<DocumentSymbol>, iter: I)
+where
+  I: Iterator<Item = ast::DatBind>,
+{
+  for dat_bind in iter {
+    let name = match dat_bind.name() {
+      Some(x) => x,
+      None => continue,
+    };
+    let children: Vec<_> = dat_bind
+      .eq_con_binds()
+      .into_iter()
+      .flat_map(|x| x.con_binds())
+      .filter_map(|con_bind| {
+        let name = con_bind.name_star_eq()?;
+        symbol(pos_db, &name.token, SymbolKind::Constructor, con_bind.syntax(), Vec::new())
+      })
+      .collect();
+    ac.extend(symbol(pos_db, &name, SymbolKind::Datatype, dat_bind.syntax(), children));
+  }
+}
+
+/// returns the names bound by the pattern, assuming that every name without an argument is a
+/// variable, not a constructor. we can't know for sure without statics.
+pub(crate) fn pat_names(pat: &ast::Pat) -> Vec<SyntaxToken> {
+  pat
+    .syntax()
+    .descendants()
+    .filter_map(|node| {
+      if let Some(con_pat) = ast::ConPat::cast(node.clone()) {
+        if con_pat.pat().is_some() {
+          return None;
+        }
+        let mut names = con_pat.path()?.name_star_eq_dots();
+        let name = names.next()?;
+        return names.next().is_none().then_some(name.name_star_eq()?.token);
+      }
+      let lab_pat_row = ast::LabPatRow::cast(node)?;
+      Some(lab_pat_row.name_star_eq()?.token)
+    })
+    .collect()
+}
+
+fn symbol(
+  pos_db: &PositionDb,
+  name: &SyntaxToken,
+  kind: SymbolKind,
+  node: &SyntaxNode,
+  children: Vec<DocumentSymbol>,
+) -> Option<DocumentSymbol> {
+  Some(DocumentSymbol {
+    name: name.text().to_owned(),
+    kind,
+    range: pos_db.range(node.text_range())?,
+    selection_range: pos_db.range(name.text_range())?,
+    children,
+  })
+}
@@ -0,0 +1,186 @@
+//! Fixing unused values.
+
+use sml_syntax::ast::{self, AstNode as _};
+use sml_syntax::{SyntaxKind, SyntaxNode, SyntaxToken};
+use text_size_util::{TextRange, TextSize};
+
+/// Returns whether [`ignore`] can stop binding the innermost unused value containing the offset,
+/// without computing the edit.
+pub(crate) fn can_ignore(file: &mlb_statics::SourceFile, offset: TextSize) -> bool {
+  let (node, name) = match get(file, offset) {
+    Some(x) => x,
+    None => return false,
+  };
+  match ast::Pat::cast(node) {
+    Some(ast::Pat::ConPat(_)) => true,
+    Some(ast::Pat::AsPat(pat)) => pat.as_pat_tail().and_then(|x| x.pat()).is_some(),
+    Some(ast::Pat::RecordPat(pat)) => ignorable_row(&pat, name).is_some(),
+    _ => false,
+  }
+}
+
+/// Returns where to edit to stop binding the innermost unused value containing the offset, and the
+/// text to replace that range with.
+pub(crate) fn ignore(
+  file: &mlb_statics::SourceFile,
+  offset: TextSize,
+) -> Option<(TextRange, String)> {
+  let (node, name) = get(file, offset)?;
+  match ast::Pat::cast(node)? {
+    // this includes `op`, if any.
+    ast::Pat::ConPat(pat) => Some((pat.syntax().text_range(), "_".to_owned())),
+    // `_ as p` is not allowed, so use just `p`.
+    ast::Pat::AsPat(pat) => {
+      let rhs = pat.as_pat_tail()?.pat()?;
+      let range =
+        TextRange::new(pat.syntax().text_range().start(), rhs.syntax().text_range().start());
+      Some((range, String::new()))
+    }
+    // a lone name in a record pattern is both a label and a variable, so keep the label.
+    ast::Pat::RecordPat(pat) => {
+      let (tok, tail) = ignorable_row(&pat, name)?;
+      match tail {
+        None => Some((TextRange::empty(tok.text_range().end()), " = _".to_owned())),
+        Some(tail) => {
+          let range =
+            TextRange::new(tok.text_range().end(), tail.pat()?.syntax().text_range().start());
+          Some((range, " = ".to_owned()))
+        }
+      }
+    }
+    _ => None,
+  }
+}
+
+/// returns the name token of the row in the record pattern that binds only the name, and its `as`
+/// tail, if any.
+fn ignorable_row(
+  pat: &ast::RecordPat,
+  name: &str,
+) -> Option<(SyntaxToken, Option<ast::AsPatTail>)> {
+  pat.pat_rows().find_map(|row| {
+    let row = match row.pat_row_inner()? {
+      ast::PatRowInner::LabPatRow(x) => x,
+      ast::PatRowInner::RestPatRow(_) | ast::PatRowInner::LabAndPatPatRow(_) => return None,
+    };
+    let tok = row.name_star_eq()?.token;
+    if tok.text() != name {
+      return None;
+    }
+    match (row.ty_annotation(), row.as_pat_tail()) {
+      (_, None) => Some((tok, None)),
+      (None, Some(tail)) => Some((tok, Some(tail))),
+      (Some(_), Some(_)) => None,
+    }
+  })
+}
+
+/// Returns whether [`delete_val`] can delete a declaration for the offset, without computing the
+/// range to delete.
+pub(crate) fn can_delete_val(file: &mlb_statics::SourceFile, offset: TextSize) -> bool {
+  deletable_dec(file, offset).is_some()
+}
+
+/// Returns the range of the `val` declaration binding only the innermost unused value containing
+/// the offset, if the right-hand side of the declaration is pure, so the whole declaration may be
+/// deleted.
+pub(crate) fn delete_val(file: &mlb_statics::SourceFile, offset: TextSize) -> Option<TextRange> {
+  let dec = deletable_dec(file, offset)?;
+  let range = dec.text_range();
+  // also delete the whitespace before it, back to the end of the previous line if there is one.
+  let start = match dec.first_token().and_then(|tok| tok.prev_token()) {
+    Some(tok) if tok.kind() == SyntaxKind::Whitespace => {
+      let text = tok.text();
+      let idx = text.rfind('\n').unwrap_or(0);
+      tok.text_range().start() + TextSize::try_from(idx).ok()?
+    }
+    _ => range.start(),
+  };
+  Some(TextRange::new(start, range.end()))
+}
+
+/// returns the declaration, including the `;` if any, of the `val` binding only the innermost unused
+/// value containing the offset, if the declaration may be deleted.
+fn deletable_dec(file: &mlb_statics::SourceFile, offset: TextSize) -> Option<SyntaxNode> {
+  let (node, _) = get(file, offset)?;
+  let val_bind = node.ancestors().find(|x| !ast::Pat::can_cast(x.kind()))?;
+  let val_bind = ast::ValBind::cast(val_bind)?;
+  if val_bind.rec_kw().is_some() || strip_pat(val_bind.pat()?) != node {
+    return None;
+  }
+  if !is_pure(val_bind.eq_exp()?.exp()?) {
+    return None;
+  }
+  let val_dec = ast::ValDec::cast(val_bind.syntax().parent()?)?;
+  if val_dec.val_binds().count() != 1 {
+    return None;
+  }
+  // include the `;`, if any.
+  val_dec.syntax().parent().filter(|x| x.kind() == SyntaxKind::DecInSeq)
+}
+
+/// returns the node for the innermost unused value containing the offset, and its name.
+fn get(file: &mlb_statics::SourceFile, offset: TextSize) -> Option<(SyntaxNode, &str)> {
+  let root = file.syntax.parse.root.syntax();
+  file
+    .statics_errors
+    .iter()
+    .filter_map(|err| {
+      let name = err.unused()?;
+      let node = file.syntax.lower.ptrs.hir_to_ast(err.idx())?.to_node(root);
+      node.text_range().contains_inclusive(offset).then_some((node, name))
+    })
+    .min_by_key(|(node, _)| node.text_range().len())
+}
+
+fn strip_pat(pat: ast::Pat) -> SyntaxNode {
+  match pat {
+    ast::Pat::ParenPat(pat) => pat.pat().map_or_else(|| pat.syntax().clone(), strip_pat),
+    ast::Pat::TypedPat(pat) => pat.pat().map_or_else(|| pat.syntax().clone(), strip_pat),
+    pat => pat.syntax().clone(),
+  }
+}
+
+/// not strictly "has no effects", since e.g. constructor applications are pure but we don't know
+/// which paths are constructors.
+fn is_pure(exp: ast::Exp) -> bool {
+  match exp {
+    ast::Exp::SConExp(_)
+    | ast::Exp::PathExp(_)
+    | ast::Exp::SelectorExp(_)
+    | ast::Exp::OpAndalsoExp(_)
+    | ast::Exp::OpOrelseExp(_)
+    | ast::Exp::FnExp(_) => true,
+    ast::Exp::RecordExp(exp) => {
+      exp.exp_rows().all(|row| row.eq_exp().map_or(true, |x| x.exp().map_or(false, is_pure)))
+    }
+    ast::Exp::ParenExp(exp) => exp.exp().map_or(false, is_pure),
+    ast::Exp::TupleExp(exp) => exp.exp_args().all(|x| x.exp().map_or(false, is_pure)),
+    ast::Exp::ListExp(exp) => exp.exp_args().all(|x| x.exp().map_or(false, is_pure)),
+    ast::Exp::VectorExp(exp) => {
+      exp.list_exp().map_or(false, |x| x.exp_args().all(|x| x.exp().map_or(false, is_pure)))
+    }
+    ast::Exp::TypedExp(exp) => exp.exp().map_or(false, is_pure),
+    ast::Exp::AndalsoExp(exp) => {
+      exp.lhs().map_or(false, is_pure) && exp.rhs().map_or(false, is_pure)
+    }
+    ast::Exp::OrelseExp(exp) => {
+      exp.lhs().map_or(false, is_pure) && exp.rhs().map_or(false, is_pure)
+    }
+    ast::Exp::IfExp(exp) => {
+      exp.cond().map_or(false, is_pure)
+        && exp.yes().map_or(false, is_pure)
+        && exp.no().map_or(false, is_pure)
+    }
+    ast::Exp::HoleExp(_)
+    | ast::Exp::WildcardExp(_)
+    | ast::Exp::SeqExp(_)
+    | ast::Exp::LetExp(_)
+    | ast::Exp::AppExp(_)
+    | ast::Exp::InfixExp(_)
+    | ast::Exp::HandleExp(_)
+    | ast::Exp::RaiseExp(_)
+    | ast::Exp::WhileExp(_)
+    | ast::Exp::CaseExp(_) => false,
+  }
+}
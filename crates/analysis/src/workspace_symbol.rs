@@ -0,0 +1,302 @@
+//! Symbols across the workspace, gotten from the HIR.
+
+use crate::symbol::SymbolKind;
+use text_pos::Range;
+
+/// A symbol somewhere in the workspace, like a structure or a value.
+#[derive(Debug, Clone)]
+pub struct WorkspaceSymbol {
+  /// The name.
+  pub name: String,
+  /// The kind.
+  pub kind: SymbolKind,
+  /// The name of the symbol containing this one, like the structure containing a value.
+  pub container_name: Option<String>,
+  /// The path of the file containing the symbol.
+  pub path: sml_statics::DefPath,
+  /// The range of the symbol in the file.
+  pub range: Range,
+}
+
+/// Returns the symbols declared in the HIR, not including those inside expressions.
+///
+/// `range` converts an HIR index to its range in the file.
+pub(crate) fn get<F>(
+  arenas: &sml_hir::Arenas,
+  root: sml_hir::StrDecIdx,
+  path: sml_statics::DefPath,
+  range: F,
+) -> Vec<WorkspaceSymbol>
+where
+  F: Fn(sml_hir::Idx) -> Option<Range>,
+{
+  let mut cx = Cx { arenas, path, range, ac: Vec::new() };
+  cx.str_dec(None, root);
+  cx.ac
+}
+
+/// Returns at most `limit` of the symbols that fuzzily match the query, best matches first.
+pub(crate) fn search<'a, I>(symbols: I, query: &str, limit: usize) -> Vec<WorkspaceSymbol>
+where
+  I: Iterator<Item = &'a WorkspaceSymbol>,
+{
+  let query = query.to_lowercase();
+  let mut ret: Vec<_> =
+    symbols.filter_map(|sym| Some((score(&query, &sym.name.to_lowercase())?, sym))).collect();
+  ret.sort_by(|&(s1, sym1), &(s2, sym2)| {
+    (s1, sym1.name.len(), &sym1.name).cmp(&(s2, sym2.name.len(), &sym2.name))
+  });
+  ret.into_iter().take(limit).map(|(_, sym)| sym.clone()).collect()
+}
+
+/// returns how well the name matches the query, lower is better, or `None` if it doesn't match at
+/// all. both should already be lowercase.
+fn score(query: &str, name: &str) -> Option<u8> {
+  if name == query {
+    return Some(0);
+  }
+  if name.starts_with(query) {
+    return Some(1);
+  }
+  if name.contains(query) {
+    return Some(2);
+  }
+  // the query is a subsequence of the name.
+  let mut name_chars = name.chars();
+  query.chars().all(|c| name_chars.any(|n| n == c)).then_some(3)
+}
+
+struct Cx<'a, F> {
+  arenas: &'a sml_hir::Arenas,
+  path: sml_statics::DefPath,
+  range: F,
+  ac: Vec<WorkspaceSymbol>,
+}
+
+impl<F> Cx<'_, F>
+where
+  F: Fn(sml_hir::Idx) -> Option<Range>,
+{
+  fn push<I>(&mut self, name: &str, kind: SymbolKind, container: Option<&str>, idx: I)
+  where
+    I: Into<sml_hir::Idx>,
+  {
+    let range = match (self.range)(idx.into()) {
+      Some(x) => x,
+      None => return,
+    };
+    self.ac.push(WorkspaceSymbol {
+      name: name.to_owned(),
+      kind,
+      container_name: container.map(ToOwned::to_owned),
+      path: self.path,
+      range,
+    });
+  }
+
+  fn str_dec(&mut self, container: Option<&str>, idx: sml_hir::StrDecIdx) {
+    let idx = match idx {
+      Some(x) => x,
+      None => return,
+    };
+    let arenas = self.arenas;
+    match &arenas.str_dec[idx] {
+      sml_hir::StrDec::Dec(dec) => self.dec(container, *dec),
+      sml_hir::StrDec::Structure(binds) => {
+        for bind in binds {
+          self.push(bind.name.as_str(), SymbolKind::Structure, container, idx);
+          self.str_exp(Some(bind.name.as_str()), bind.str_exp);
+        }
+      }
+      sml_hir::StrDec::Signature(binds) => {
+        for bind in binds {
+          self.push(bind.name.as_str(), SymbolKind::Signature, container, idx);
+          self.sig_exp(Some(bind.name.as_str()), bind.sig_exp);
+        }
+      }
+      sml_hir::StrDec::Functor(binds) => {
+        for bind in binds {
+          self.push(bind.functor_name.as_str(), SymbolKind::Functor, container, idx);
+        }
+      }
+      sml_hir::StrDec::Local(_, in_dec) => self.str_dec(container, *in_dec),
+      sml_hir::StrDec::Seq(str_decs) => {
+        for &str_dec in str_decs {
+          self.str_dec(container, str_dec);
+        }
+      }
+    }
+  }
+
+  fn str_exp(&mut self, container: Option<&str>, idx: sml_hir::StrExpIdx) {
+    let idx = match idx {
+      Some(x) => x,
+      None => return,
+    };
+    let arenas = self.arenas;
+    match &arenas.str_exp[idx] {
+      sml_hir::StrExp::Struct(str_dec) => self.str_dec(container, *str_dec),
+      sml_hir::StrExp::Ascription(str_exp, _, _) | sml_hir::StrExp::Let(_, str_exp) => {
+        self.str_exp(container, *str_exp);
+      }
+      sml_hir::StrExp::Path(_) | sml_hir::StrExp::App(_, _, _) => {}
+    }
+  }
+
+  fn sig_exp(&mut self, container: Option<&str>, idx: sml_hir::SigExpIdx) {
+    let idx = match idx {
+      Some(x) => x,
+      None => return,
+    };
+    let arenas = self.arenas;
+    match &arenas.sig_exp[idx] {
+      sml_hir::SigExp::Spec(spec) => self.spec(container, *spec),
+      sml_hir::SigExp::Where(sig_exp, _) => self.sig_exp(container, *sig_exp),
+      sml_hir::SigExp::Name(_) => {}
+    }
+  }
+
+  fn spec(&mut self, container: Option<&str>, idx: sml_hir::SpecIdx) {
+    let idx = match idx {
+      Some(x) => x,
+      None => return,
+    };
+    let arenas = self.arenas;
+    match &arenas.spec[idx] {
+      sml_hir::Spec::Val(_, descs) => {
+        for desc in descs {
+          let is_fn = matches!(desc.ty.map(|ty| &arenas.ty[ty]), Some(sml_hir::Ty::Fn(_, _)));
+          let kind = if is_fn { SymbolKind::Function } else { SymbolKind::Value };
+          self.push(desc.name.as_str(), kind, container, idx);
+        }
+      }
+      sml_hir::Spec::Ty(desc) | sml_hir::Spec::EqTy(desc) => {
+        self.push(desc.name.as_str(), SymbolKind::Type, container, idx);
+      }
+      sml_hir::Spec::Datatype(desc) => self.dat_binds(container, std::slice::from_ref(desc), idx),
+      sml_hir::Spec::DatatypeCopy(name, _) => {
+        self.push(name.as_str(), SymbolKind::Datatype, container, idx);
+      }
+      sml_hir::Spec::Exception(desc) => {
+        self.push(desc.name.as_str(), SymbolKind::Exception, container, idx);
+      }
+      sml_hir::Spec::Str(desc) => {
+        self.push(desc.name.as_str(), SymbolKind::Structure, container, idx);
+        self.sig_exp(Some(desc.name.as_str()), desc.sig_exp);
+      }
+      sml_hir::Spec::Include(_) => {}
+      sml_hir::Spec::Sharing(spec, _, _) => self.spec(container, *spec),
+      sml_hir::Spec::Seq(specs) => {
+        for &spec in specs {
+          self.spec(container, spec);
+        }
+      }
+    }
+  }
+
+  fn dec(&mut self, container: Option<&str>, idx: sml_hir::DecIdx) {
+    let idx = match idx {
+      Some(x) => x,
+      None => return,
+    };
+    let arenas = self.arenas;
+    match &arenas.dec[idx] {
+      sml_hir::Dec::Val(_, binds) => {
+        for bind in binds {
+          let is_fn = matches!(bind.exp.map(|exp| &arenas.exp[exp]), Some(sml_hir::Exp::Fn(_, _)));
+          let kind = if is_fn { SymbolKind::Function } else { SymbolKind::Value };
+          let mut names = Vec::<(&str, sml_hir::la_arena::Idx<sml_hir::Pat>)>::new();
+          pat_names(arenas, &mut names, bind.pat);
+          for (name, pat) in names {
+            self.push(name, kind, container, pat);
+          }
+        }
+      }
+      sml_hir::Dec::Ty(binds) => self.ty_binds(container, binds, idx),
+      sml_hir::Dec::Datatype(dat_binds, ty_binds) => {
+        self.dat_binds(container, dat_binds, idx);
+        self.ty_binds(container, ty_binds, idx);
+      }
+      sml_hir::Dec::DatatypeCopy(name, _) => {
+        self.push(name.as_str(), SymbolKind::Datatype, container, idx);
+      }
+      sml_hir::Dec::Abstype(dat_binds, ty_binds, dec) => {
+        self.dat_binds(container, dat_binds, idx);
+        self.ty_binds(container, ty_binds, idx);
+        self.dec(container, *dec);
+      }
+      sml_hir::Dec::Exception(binds) => {
+        for bind in binds {
+          let name = match bind {
+            sml_hir::ExBind::New(name, _) | sml_hir::ExBind::Copy(name, _) => name,
+          };
+          self.push(name.as_str(), SymbolKind::Exception, container, idx);
+        }
+      }
+      sml_hir::Dec::Local(_, in_dec) => self.dec(container, *in_dec),
+      sml_hir::Dec::Open(_) => {}
+      sml_hir::Dec::Seq(decs) => {
+        for &dec in decs {
+          self.dec(container, dec);
+        }
+      }
+    }
+  }
+
+  fn ty_binds<I>(&mut self, container: Option<&str>, binds: &[sml_hir::TyBind], idx: I)
+  where
+    I: Into<sml_hir::Idx> + Copy,
+  {
+    for bind in binds {
+      self.push(bind.name.as_str(), SymbolKind::Type, container, idx);
+    }
+  }
+
+  fn dat_binds<I>(&mut self, container: Option<&str>, binds: &[sml_hir::DatBind], idx: I)
+  where
+    I: Into<sml_hir::Idx> + Copy,
+  {
+    for bind in binds {
+      self.push(bind.name.as_str(), SymbolKind::Datatype, container, idx);
+      for con in &bind.cons {
+        self.push(con.name.as_str(), SymbolKind::Constructor, container, idx);
+      }
+    }
+  }
+}
+
+/// pushes the names bound by the pat, assuming that every name without an argument is a variable,
+/// not a constructor. we can't know for sure without statics.
+fn pat_names<'a>(
+  arenas: &'a sml_hir::Arenas,
+  ac: &mut Vec<(&'a str, sml_hir::la_arena::Idx<sml_hir::Pat>)>,
+  pat: sml_hir::PatIdx,
+) {
+  let pat = match pat {
+    Some(x) => x,
+    None => return,
+  };
+  match &arenas.pat[pat] {
+    sml_hir::Pat::Wild | sml_hir::Pat::SCon(_) => {}
+    sml_hir::Pat::Con(path, arg) => match arg {
+      Some(arg) => pat_names(arenas, ac, *arg),
+      None => {
+        if path.prefix().is_empty() {
+          ac.push((path.last().as_str(), pat));
+        }
+      }
+    },
+    sml_hir::Pat::Record { rows, .. } => {
+      for &(_, pat) in rows {
+        pat_names(arenas, ac, pat);
+      }
+    }
+    sml_hir::Pat::Typed(pat, _) => pat_names(arenas, ac, *pat),
+    sml_hir::Pat::As(name, inner) => {
+      ac.push((name.as_str(), pat));
+      pat_names(arenas, ac, *inner);
+    }
+    // every alternative binds the same names.
+    sml_hir::Pat::Or(or_pat) => pat_names(arenas, ac, or_pat.first),
+  }
+}
@@ -7,11 +7,15 @@ use text_size_util::WithRange;
 
 pub(crate) fn get(root: ParseRoot) -> Result<CmFile> {
   let mut paths = Vec::<WithRange<ParsedPath>>::new();
+  let mut std_basis_paths = Vec::<WithRange<String>>::new();
   for member in root.members {
     let cls = member.class();
     let path = match member.pathname.val {
       PathOrStdBasis::Path(p) => p,
-      PathOrStdBasis::StdBasis => continue,
+      PathOrStdBasis::StdBasis(s) => {
+        std_basis_paths.push(WithRange { val: s, range: member.pathname.range });
+        continue;
+      }
     };
     let kind = match cls {
       Some(class) => match class.val {
@@ -27,5 +31,5 @@ pub(crate) fn get(root: ParseRoot) -> Result<CmFile> {
     };
     paths.push(WithRange { val: ParsedPath { kind, path }, range: member.pathname.range });
   }
-  Ok(CmFile { export: root.export, paths })
+  Ok(CmFile { export: root.export, paths, std_basis_paths })
 }
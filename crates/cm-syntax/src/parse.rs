@@ -245,7 +245,7 @@ fn path_or_minus(p: &mut Parser<'_>) -> Result<PathOrMinus> {
       p.bump();
       match path(p, s)? {
         PathOrStdBasis::Path(x) => Ok(PathOrMinus::Path(x)),
-        PathOrStdBasis::StdBasis => p.err(ErrorKind::ExpectedPathOrMinus),
+        PathOrStdBasis::StdBasis(_) => p.err(ErrorKind::ExpectedPathOrMinus),
       }
     }
     _ => p.err(ErrorKind::ExpectedPathOrMinus),
@@ -258,7 +258,7 @@ fn path(p: &Parser<'_>, s: &str) -> Result<PathOrStdBasis> {
     Err(e) => {
       if let paths::slash_var_path::Error::Undefined(var) = &e {
         if matches!(var.as_str(), "" | "SMLNJ-LIB") {
-          return Ok(PathOrStdBasis::StdBasis);
+          return Ok(PathOrStdBasis::StdBasis(s.to_owned()));
         }
       }
       p.err(ErrorKind::SlashVarPathError(e))
@@ -105,6 +105,8 @@ pub struct CmFile {
   pub export: Export,
   /// The path.
   pub paths: Vec<WithRange<ParsedPath>>,
+  /// The std basis paths, like `$/basis.cm`, as written.
+  pub std_basis_paths: Vec<WithRange<String>>,
 }
 
 /// A kind of path.
@@ -163,8 +165,8 @@ pub enum Export {
 pub enum PathOrStdBasis {
   /// A path.
   Path(PathBuf),
-  /// A std basis path.
-  StdBasis,
+  /// A std basis path, as written.
+  StdBasis(String),
 }
 
 /// The "argument" to a source or group export.
@@ -195,7 +197,7 @@ impl Member {
   pub(crate) fn class(&self) -> Option<WithRange<Class>> {
     self.class.clone().or_else(|| match &self.pathname.val {
       PathOrStdBasis::Path(p) => Class::from_path(p.as_path()).map(|x| self.pathname.wrap(x)),
-      PathOrStdBasis::StdBasis => None,
+      PathOrStdBasis::StdBasis(_) => None,
     })
   }
 }
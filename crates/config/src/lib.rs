@@ -17,6 +17,8 @@ pub struct Root {
   pub version: u16,
   /// The workspace config.
   pub workspace: Option<Workspace>,
+  /// The language config.
+  pub language: Option<Language>,
   /// The diagnostics config.
   pub diagnostics: Option<FxHashMap<SmolStr, ErrorConfig>>,
 }
@@ -31,6 +33,44 @@ pub struct Workspace {
   pub path_vars: Option<FxHashMap<SmolStr, PathVar>>,
 }
 
+/// The language config.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct Language {
+  /// Whether every file may use the built-in libraries, even if its group does not ask for them.
+  pub implicit_std_basis: Option<bool>,
+  /// The dialect.
+  pub dialect: Option<Dialect>,
+}
+
+/// A dialect of SML, i.e. the SML implementation a project is written for.
+///
+/// This determines which built-in libraries and language extensions are available.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Dialect {
+  /// SML/NJ.
+  SmlNj,
+  /// MLton.
+  Mlton,
+  /// Poly/ML.
+  Poly,
+  /// Only what the Definition allows.
+  Standard,
+}
+
+impl Dialect {
+  /// Returns whether this allows the extensions to the Definition: or patterns, vector expressions
+  /// and patterns, `do` declarations, expression row punning, and `withtype` in specifications.
+  #[must_use]
+  pub fn allows_extensions(self) -> bool {
+    match self {
+      Dialect::SmlNj | Dialect::Mlton => true,
+      Dialect::Poly | Dialect::Standard => false,
+    }
+  }
+}
+
 /// A path var setting.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -81,26 +121,36 @@ pub enum DiagnosticsFilter {
   Syntax,
 }
 
-/// Optional settings for the server.
+/// Optional settings for the server. Any that are missing take their default value.
 #[derive(Debug, Deserialize)]
+#[serde(default)]
 #[allow(missing_docs)]
 #[allow(clippy::struct_excessive_bools)]
 pub struct Options {
   pub show_token_hover: bool,
+  pub inlay_hints_val_bind: bool,
+  pub inlay_hints_fun_param: bool,
+  pub inlay_hints_fn_param: bool,
   pub diagnostics_on_change: bool,
   pub diagnostics_filter: DiagnosticsFilter,
   pub diagnostics_more_info_hint: bool,
   pub format: bool,
+  /// Only used when there is no workspace root, since otherwise the config file sets the dialect.
+  pub language_dialect: Option<Dialect>,
 }
 
 impl Default for Options {
   fn default() -> Self {
     Self {
       show_token_hover: true,
+      inlay_hints_val_bind: true,
+      inlay_hints_fun_param: true,
+      inlay_hints_fn_param: true,
       diagnostics_on_change: false,
       diagnostics_filter: DiagnosticsFilter::Syntax,
       diagnostics_more_info_hint: true,
       format: false,
+      language_dialect: None,
     }
   }
 }
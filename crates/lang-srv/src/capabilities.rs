@@ -17,8 +17,70 @@ pub(crate) fn get() -> lsp_types::ServerCapabilities {
     hover_provider: Some(lsp_types::HoverProviderCapability::Simple(true)),
     definition_provider: Some(lsp_types::OneOf::Left(true)),
     type_definition_provider: Some(lsp_types::TypeDefinitionProviderCapability::Simple(true)),
-    code_action_provider: Some(lsp_types::CodeActionProviderCapability::Simple(true)),
+    implementation_provider: Some(lsp_types::ImplementationProviderCapability::Simple(true)),
+    completion_provider: Some(lsp_types::CompletionOptions {
+      trigger_characters: Some(vec![".".to_owned()]),
+      ..Default::default()
+    }),
+    signature_help_provider: Some(lsp_types::SignatureHelpOptions {
+      trigger_characters: Some(vec![" ".to_owned(), "(".to_owned(), ",".to_owned()]),
+      retrigger_characters: None,
+      work_done_progress_options: lsp_types::WorkDoneProgressOptions::default(),
+    }),
+    references_provider: Some(lsp_types::OneOf::Left(true)),
+    document_highlight_provider: Some(lsp_types::OneOf::Left(true)),
+    call_hierarchy_provider: Some(lsp_types::CallHierarchyServerCapability::Simple(true)),
+    rename_provider: Some(lsp_types::OneOf::Right(lsp_types::RenameOptions {
+      prepare_provider: Some(true),
+      work_done_progress_options: lsp_types::WorkDoneProgressOptions::default(),
+    })),
+    document_symbol_provider: Some(lsp_types::OneOf::Left(true)),
+    workspace_symbol_provider: Some(lsp_types::OneOf::Left(true)),
+    semantic_tokens_provider: Some(
+      lsp_types::SemanticTokensServerCapabilities::SemanticTokensOptions(
+        lsp_types::SemanticTokensOptions {
+          work_done_progress_options: lsp_types::WorkDoneProgressOptions::default(),
+          legend: lsp_types::SemanticTokensLegend {
+            token_types: SEMANTIC_TOKEN_TYPES.to_vec(),
+            token_modifiers: SEMANTIC_TOKEN_MODIFIERS.to_vec(),
+          },
+          range: Some(true),
+          full: Some(lsp_types::SemanticTokensFullOptions::Bool(true)),
+        },
+      ),
+    ),
+    inlay_hint_provider: Some(lsp_types::OneOf::Left(true)),
+    folding_range_provider: Some(lsp_types::FoldingRangeProviderCapability::Simple(true)),
+    selection_range_provider: Some(lsp_types::SelectionRangeProviderCapability::Simple(true)),
+    code_lens_provider: Some(lsp_types::CodeLensOptions { resolve_provider: Some(true) }),
+    code_action_provider: Some(lsp_types::CodeActionProviderCapability::Options(
+      lsp_types::CodeActionOptions {
+        code_action_kinds: None,
+        work_done_progress_options: lsp_types::WorkDoneProgressOptions::default(),
+        resolve_provider: Some(true),
+      },
+    )),
     document_formatting_provider: Some(lsp_types::OneOf::Left(true)),
     ..Default::default()
   }
 }
+
+/// the order matters: a token's type is an index into this.
+pub(crate) const SEMANTIC_TOKEN_TYPES: [lsp_types::SemanticTokenType; 10] = [
+  lsp_types::SemanticTokenType::ENUM_MEMBER,
+  lsp_types::SemanticTokenType::EVENT,
+  lsp_types::SemanticTokenType::VARIABLE,
+  lsp_types::SemanticTokenType::TYPE,
+  lsp_types::SemanticTokenType::NAMESPACE,
+  lsp_types::SemanticTokenType::INTERFACE,
+  lsp_types::SemanticTokenType::CLASS,
+  lsp_types::SemanticTokenType::TYPE_PARAMETER,
+  lsp_types::SemanticTokenType::PROPERTY,
+  lsp_types::SemanticTokenType::OPERATOR,
+];
+
+/// the order matters: a token's modifiers are a bitset of indices into this.
+pub(crate) const SEMANTIC_TOKEN_MODIFIERS: [lsp_types::SemanticTokenModifier; 2] = [
+  lsp_types::SemanticTokenModifier::DECLARATION,
+  lsp_types::SemanticTokenModifier::DEFAULT_LIBRARY,
+];
@@ -0,0 +1,61 @@
+//! Extensions to the language server protocol.
+
+use serde::{Deserialize, Serialize};
+
+/// Gets the contents of a built-in library file, given its `millet:` URI. Responds with `null` if
+/// there is no such file.
+#[derive(Debug)]
+pub(crate) enum BuiltinFile {}
+
+impl lsp_types::request::Request for BuiltinFile {
+  type Params = lsp_types::TextDocumentIdentifier;
+  type Result = Option<String>;
+  const METHOD: &'static str = "millet/builtinFile";
+}
+
+/// The data attached to a code lens, so we can find the item again when resolving it.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CodeLensData {
+  pub(crate) uri: lsp_types::Url,
+  pub(crate) kind: CodeLensKind,
+}
+
+/// What a code lens shows once resolved.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum CodeLensKind {
+  /// The number of references, which shows the references when clicked.
+  References,
+  /// The inferred type.
+  Type,
+}
+
+/// The data attached to a code action, so we can compute its edit when resolving it.
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CodeActionData {
+  pub(crate) uri: lsp_types::Url,
+  pub(crate) position: lsp_types::Position,
+  /// The version of the document when we offered the action, if it is open.
+  pub(crate) version: Option<i32>,
+  pub(crate) kind: CodeActionKind,
+}
+
+/// Which code action to compute the edit for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum CodeActionKind {
+  /// Fill a `case` with all the variants of the head's type.
+  FillCase,
+  /// Fill a hole with this suggestion for it.
+  FillHole(String),
+  /// Fill in the missing arms of a non-exhaustive match.
+  FillMissingArms,
+  /// Stub out the items a structure is missing from its signature.
+  StubMissingItems,
+  /// Replace an undefined name with a similar one.
+  FixUndefined,
+  /// Replace an unused value with `_`.
+  IgnoreUnused,
+  /// Delete the `val` binding an unused value.
+  DeleteUnusedVal,
+}
@@ -2,7 +2,7 @@
 
 mod helpers;
 
-use anyhow::{bail, Result};
+use anyhow::{bail, Context as _, Result};
 use crossbeam_channel::Sender;
 use diagnostic_util::Code;
 use fast_hash::{FxHashMap, FxHashSet};
@@ -38,9 +38,18 @@ impl State {
       options.diagnostics_filter,
       options.format,
     );
+    // if the client can't resolve the edits of code actions, we must compute them eagerly.
+    let resolve_code_action_edits = init
+      .capabilities
+      .text_document
+      .as_ref()
+      .and_then(|x| x.code_action.as_ref()?.resolve_support.as_ref())
+      .map_or(false, |x| x.properties.iter().any(|p| p == "edit"));
     let mut sp = SPState {
       options,
       registered_for_watched_files: false,
+      resolve_code_action_edits,
+      versions: FxHashMap::default(),
       store: paths::Store::new(),
       file_system: paths::RealFileSystem::default(),
       sender,
@@ -130,8 +139,8 @@ impl State {
     r = helpers::try_req::<lsp_types::request::GotoDefinition, _>(r, |id, params| {
       let params = params.text_document_position_params;
       let pos = helpers::text_doc_pos_params(&self.sp.file_system, &mut self.sp.store, params)?;
-      let res = self.analysis.get_def(pos).and_then(|range| {
-        helpers::lsp_location(&self.sp.store, range).map(lsp_types::GotoDefinitionResponse::Scalar)
+      let res = self.analysis.get_def(pos).and_then(|loc| {
+        helpers::def_location(&self.sp.store, loc).map(lsp_types::GotoDefinitionResponse::Scalar)
       });
       self.sp.send_response(Response::new_ok(id, res));
       Ok(())
@@ -144,24 +153,360 @@ impl State {
         .get_ty_defs(pos)
         .into_iter()
         .flatten()
-        .filter_map(|range| helpers::lsp_location(&self.sp.store, range))
+        .filter_map(|loc| helpers::def_location(&self.sp.store, loc))
         .collect();
       let res = (!locs.is_empty()).then_some(lsp_types::GotoDefinitionResponse::Array(locs));
       self.sp.send_response(Response::new_ok(id, res));
       Ok(())
     })?;
-    // TODO do CodeActionResolveRequest and lazily compute the edit
-    r = helpers::try_req::<lsp_types::request::CodeActionRequest, _>(r, |id, params| {
+    r = helpers::try_req::<lsp_types::request::GotoImplementation, _>(r, |id, params| {
+      let params = params.text_document_position_params;
+      let pos = helpers::text_doc_pos_params(&self.sp.file_system, &mut self.sp.store, params)?;
+      let res = self.analysis.get_impls(pos).map(|locs| {
+        let locs: Vec<_> =
+          locs.into_iter().filter_map(|loc| helpers::def_location(&self.sp.store, loc)).collect();
+        lsp_types::GotoDefinitionResponse::Array(locs)
+      });
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::References, _>(r, |id, params| {
+      let include_def = params.context.include_declaration;
+      let params = params.text_document_position;
+      let pos = helpers::text_doc_pos_params(&self.sp.file_system, &mut self.sp.store, params)?;
+      let res = self.analysis.get_refs(pos, include_def).map(|ranges| {
+        ranges
+          .into_iter()
+          .filter_map(|range| helpers::lsp_location(&self.sp.store, range))
+          .collect::<Vec<_>>()
+      });
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::DocumentHighlightRequest, _>(r, |id, params| {
+      let params = params.text_document_position_params;
+      let pos = helpers::text_doc_pos_params(&self.sp.file_system, &mut self.sp.store, params)?;
+      let res = self
+        .analysis
+        .document_highlights(pos)
+        .map(|hs| hs.into_iter().map(helpers::document_highlight).collect::<Vec<_>>());
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::CallHierarchyPrepare, _>(r, |id, params| {
+      let params = params.text_document_position_params;
+      let pos = helpers::text_doc_pos_params(&self.sp.file_system, &mut self.sp.store, params)?;
+      let res = self
+        .analysis
+        .prepare_call_hierarchy(pos)
+        .and_then(|item| helpers::call_hierarchy_item(&self.sp.store, item))
+        .map(|item| vec![item]);
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::CallHierarchyIncomingCalls, _>(r, |id, params| {
+      let params = helpers::call_hierarchy_item_pos(params.item);
+      let pos = helpers::text_doc_pos_params(&self.sp.file_system, &mut self.sp.store, params)?;
+      let res = self.analysis.incoming_calls(pos).map(|calls| {
+        calls
+          .into_iter()
+          .filter_map(|call| {
+            Some(lsp_types::CallHierarchyIncomingCall {
+              from: helpers::call_hierarchy_item(&self.sp.store, call.item)?,
+              from_ranges: call.ranges.into_iter().map(helpers::lsp_range).collect(),
+            })
+          })
+          .collect::<Vec<_>>()
+      });
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::CallHierarchyOutgoingCalls, _>(r, |id, params| {
+      let params = helpers::call_hierarchy_item_pos(params.item);
+      let pos = helpers::text_doc_pos_params(&self.sp.file_system, &mut self.sp.store, params)?;
+      let res = self.analysis.outgoing_calls(pos).map(|calls| {
+        calls
+          .into_iter()
+          .filter_map(|call| {
+            Some(lsp_types::CallHierarchyOutgoingCall {
+              to: helpers::call_hierarchy_item(&self.sp.store, call.item)?,
+              from_ranges: call.ranges.into_iter().map(helpers::lsp_range).collect(),
+            })
+          })
+          .collect::<Vec<_>>()
+      });
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::PrepareRenameRequest, _>(r, |id, params| {
+      let pos = helpers::text_doc_pos_params(&self.sp.file_system, &mut self.sp.store, params)?;
+      let res = self
+        .analysis
+        .prepare_rename(pos)
+        .map(|range| lsp_types::PrepareRenameResponse::Range(helpers::lsp_range(range)));
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::Rename, _>(r, |id, params| {
+      let new_name = params.new_name;
+      let params = params.text_document_position;
+      let pos = helpers::text_doc_pos_params(&self.sp.file_system, &mut self.sp.store, params)?;
+      let input = match &self.mode {
+        Mode::Root(root) => root.input.as_ref(),
+        Mode::NoRoot(_) => None,
+      };
+      let ranges = match input.map(|input| self.analysis.rename(input, pos, &new_name)) {
+        Some(Ok(x)) => x,
+        Some(Err(analysis::RenameError::InvalidName)) => {
+          let code = lsp_server::ErrorCode::InvalidParams as i32;
+          let msg = format!("not a valid name: {new_name}");
+          self.sp.send_response(Response::new_err(id, code, msg));
+          return Ok(());
+        }
+        Some(Err(analysis::RenameError::NoItem)) | None => {
+          self.sp.send_response(Response::new_ok(id, None::<lsp_types::WorkspaceEdit>));
+          return Ok(());
+        }
+      };
+      let mut changes = std::collections::HashMap::<Url, Vec<lsp_types::TextEdit>>::new();
+      for range in ranges {
+        let loc = match helpers::lsp_location(&self.sp.store, range) {
+          Some(x) => x,
+          None => continue,
+        };
+        let edit = lsp_types::TextEdit { range: loc.range, new_text: new_name.clone() };
+        changes.entry(loc.uri).or_default().push(edit);
+      }
+      let res = lsp_types::WorkspaceEdit { changes: Some(changes), ..Default::default() };
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::DocumentSymbolRequest, _>(r, |id, params| {
+      let url = params.text_document.uri;
+      let path = helpers::url_to_path_id(&self.sp.file_system, &mut self.sp.store, &url)?;
+      let res = self.analysis.document_symbols(path).map(|symbols| {
+        lsp_types::DocumentSymbolResponse::Nested(
+          symbols.into_iter().map(helpers::document_symbol).collect(),
+        )
+      });
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::FoldingRangeRequest, _>(r, |id, params| {
+      let url = params.text_document.uri;
+      let path = helpers::url_to_path_id(&self.sp.file_system, &mut self.sp.store, &url)?;
+      let res = self
+        .analysis
+        .folding_ranges(path)
+        .map(|ranges| ranges.into_iter().map(helpers::folding_range).collect::<Vec<_>>());
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::SelectionRangeRequest, _>(r, |id, params| {
+      let url = params.text_document.uri;
+      let path = helpers::url_to_path_id(&self.sp.file_system, &mut self.sp.store, &url)?;
+      let positions: Vec<_> =
+        params.positions.iter().copied().map(helpers::analysis_position).collect();
+      let res = self.analysis.selection_ranges(path, &positions).map(|all| {
+        let all = params.positions.into_iter().zip(all);
+        all.map(|(pos, ranges)| helpers::selection_range(pos, ranges)).collect::<Vec<_>>()
+      });
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::WorkspaceSymbol, _>(r, |id, params| {
+      let res: Vec<_> = self
+        .analysis
+        .workspace_symbols(&params.query)
+        .into_iter()
+        .filter_map(|symbol| helpers::symbol_information(&self.sp.store, symbol))
+        .collect();
+      self.sp.send_response(Response::new_ok(id, Some(res)));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::Completion, _>(r, |id, params| {
+      let pos = helpers::text_doc_pos_params(
+        &self.sp.file_system,
+        &mut self.sp.store,
+        params.text_document_position,
+      )?;
+      let res = self.analysis.completions(pos).map(|items| {
+        lsp_types::CompletionResponse::Array(
+          items.into_iter().map(helpers::completion_item).collect(),
+        )
+      });
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::SignatureHelpRequest, _>(r, |id, params| {
+      let pos = helpers::text_doc_pos_params(
+        &self.sp.file_system,
+        &mut self.sp.store,
+        params.text_document_position_params,
+      )?;
+      let res = self.analysis.signature_help(pos).map(helpers::signature_help);
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<crate::ext::BuiltinFile, _>(r, |id, params| {
+      let res = helpers::builtin_file_name(&params.uri)
+        .and_then(|name| self.analysis.builtin_file_contents(name))
+        .map(ToOwned::to_owned);
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::InlayHintRequest, _>(r, |id, params| {
+      let url = params.text_document.uri;
+      let path = helpers::url_to_path_id(&self.sp.file_system, &mut self.sp.store, &url)?;
+      let range = helpers::analysis_range(params.range);
+      let options = &self.sp.options;
+      let res = self.analysis.inlay_hints(path, range).map(|hints| {
+        hints
+          .into_iter()
+          .filter(|hint| match hint.kind {
+            analysis::InlayHintKind::ValBind => options.inlay_hints_val_bind,
+            analysis::InlayHintKind::FunParam => options.inlay_hints_fun_param,
+            analysis::InlayHintKind::FnParam => options.inlay_hints_fn_param,
+          })
+          .map(helpers::inlay_hint)
+          .collect::<Vec<_>>()
+      });
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::SemanticTokensFullRequest, _>(r, |id, params| {
+      let url = params.text_document.uri;
+      let path = helpers::url_to_path_id(&self.sp.file_system, &mut self.sp.store, &url)?;
+      let res = self
+        .analysis
+        .semantic_tokens(path, None)
+        .map(|tokens| lsp_types::SemanticTokensResult::Tokens(helpers::semantic_tokens(tokens)));
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::SemanticTokensRangeRequest, _>(r, |id, params| {
       let url = params.text_document.uri;
       let path = helpers::url_to_path_id(&self.sp.file_system, &mut self.sp.store, &url)?;
       let range = helpers::analysis_range(params.range);
-      let mut actions = Vec::<lsp_types::CodeActionOrCommand>::new();
-      if let Some((range, new_text)) = self.analysis.fill_case(path.wrap(range.start)) {
-        actions.push(helpers::quick_fix("Fill case".to_owned(), url, range, new_text));
+      let res = self.analysis.semantic_tokens(path, Some(range)).map(|tokens| {
+        lsp_types::SemanticTokensRangeResult::Tokens(helpers::semantic_tokens(tokens))
+      });
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::CodeLensRequest, _>(r, |id, params| {
+      let url = params.text_document.uri;
+      let path = helpers::url_to_path_id(&self.sp.file_system, &mut self.sp.store, &url)?;
+      let res = self
+        .analysis
+        .code_lenses(path)
+        .map(|lenses| lenses.into_iter().map(|x| helpers::code_lens(&url, x)).collect::<Vec<_>>());
+      self.sp.send_response(Response::new_ok(id, res));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::CodeLensResolve, _>(r, |id, mut lens| {
+      let data = lens.data.take().context("no data for code lens")?;
+      let data: crate::ext::CodeLensData = serde_json::from_value(data)?;
+      let params = lsp_types::TextDocumentPositionParams {
+        text_document: lsp_types::TextDocumentIdentifier { uri: data.uri.clone() },
+        position: lens.range.start,
+      };
+      let pos = helpers::text_doc_pos_params(&self.sp.file_system, &mut self.sp.store, params)?;
+      lens.command = match data.kind {
+        crate::ext::CodeLensKind::References => self.analysis.get_refs(pos, false).map(|ranges| {
+          let locations: Vec<_> = ranges
+            .into_iter()
+            .filter_map(|range| helpers::lsp_location(&self.sp.store, range))
+            .collect();
+          helpers::show_references_command(data.uri, lens.range.start, locations)
+        }),
+        // the lens is only informational, so clicking it does nothing.
+        crate::ext::CodeLensKind::Type => self.analysis.get_inferred_ty(pos).map(|title| {
+          lsp_types::Command { title, command: "millet.noop".to_owned(), arguments: None }
+        }),
+      };
+      self.sp.send_response(Response::new_ok(id, lens));
+      Ok(())
+    })?;
+    r = helpers::try_req::<lsp_types::request::CodeActionRequest, _>(r, |id, params| {
+      let url = params.text_document.uri;
+      let path = helpers::url_to_path_id(&self.sp.file_system, &mut self.sp.store, &url)?;
+      let position = params.range.start;
+      let pos = path.wrap(helpers::analysis_range(params.range).start);
+      let diagnostics = params.context.diagnostics;
+      // only find which actions are available here. the edits are computed when resolving.
+      let version = self.sp.versions.get(&url).copied();
+      let data = |kind| crate::ext::CodeActionData { uri: url.clone(), position, version, kind };
+      let mut actions = Vec::<lsp_types::CodeAction>::new();
+      if self.analysis.can_fill_case(pos) {
+        let title = "Fill case".to_owned();
+        let data = data(crate::ext::CodeActionKind::FillCase);
+        actions.push(helpers::quick_fix(title, &data, Vec::new()));
+      }
+      let holes = helpers::diagnostics_with_code(&diagnostics, Code::n(5026));
+      if !holes.is_empty() {
+        // the fills are the titles, so we need them now.
+        for new_text in self.analysis.get_hole_fills(pos).unwrap_or_default() {
+          let title = format!("Fill hole with `{new_text}`");
+          let data = data(crate::ext::CodeActionKind::FillHole(new_text));
+          actions.push(helpers::quick_fix(title, &data, holes.clone()));
+        }
+      }
+      let missing_arms = helpers::diagnostics_with_code(&diagnostics, Code::n(5011));
+      if !missing_arms.is_empty() && self.analysis.can_fill_missing_arms(pos) {
+        let title = "Fill in missing arms".to_owned();
+        let data = data(crate::ext::CodeActionKind::FillMissingArms);
+        actions.push(helpers::quick_fix(title, &data, missing_arms));
       }
+      let missing_items = helpers::diagnostics_with_code(&diagnostics, Code::n(5003));
+      if !missing_items.is_empty() && self.analysis.can_stub_missing_items(pos) {
+        let title = "Stub out missing items".to_owned();
+        let data = data(crate::ext::CodeActionKind::StubMissingItems);
+        actions.push(helpers::quick_fix(title, &data, missing_items));
+      }
+      let undefined = helpers::diagnostics_with_code(&diagnostics, Code::n(5001));
+      if !undefined.is_empty() {
+        if let Some(new_text) = self.analysis.get_similar_name(pos) {
+          let title = format!("Change to `{new_text}`");
+          let data = data(crate::ext::CodeActionKind::FixUndefined);
+          actions.push(helpers::quick_fix(title, &data, undefined));
+        }
+      }
+      let unused = helpers::diagnostics_with_code(&diagnostics, Code::n(5029));
+      if !unused.is_empty() {
+        if self.analysis.can_ignore_unused(pos) {
+          let title = "Replace unused value with `_`".to_owned();
+          let data = data(crate::ext::CodeActionKind::IgnoreUnused);
+          actions.push(helpers::quick_fix(title, &data, unused.clone()));
+        }
+        if self.analysis.can_delete_unused_val(pos) {
+          let title = "Delete unused `val`".to_owned();
+          let data = data(crate::ext::CodeActionKind::DeleteUnusedVal);
+          actions.push(helpers::quick_fix(title, &data, unused));
+        }
+      }
+      if !self.sp.resolve_code_action_edits {
+        for action in &mut actions {
+          // the document cannot have changed since we just offered the action.
+          self.resolve_code_action(action)?;
+        }
+      }
+      let actions: Vec<_> =
+        actions.into_iter().map(lsp_types::CodeActionOrCommand::CodeAction).collect();
       self.sp.send_response(Response::new_ok(id, actions));
       Ok(())
     })?;
+    r =
+      helpers::try_req::<lsp_types::request::CodeActionResolveRequest, _>(r, |id, mut action| {
+        if self.resolve_code_action(&mut action)? {
+          self.sp.send_response(Response::new_ok(id, action));
+        } else {
+          let code = lsp_server::ErrorCode::ContentModified as i32;
+          let msg = "the document changed since the code action was offered".to_owned();
+          self.sp.send_response(Response::new_err(id, code, msg));
+        }
+        Ok(())
+      })?;
     r = helpers::try_req::<lsp_types::request::Formatting, _>(r, |id, params| {
       let url = params.text_document.uri;
       let path = helpers::url_to_path_id(&self.sp.file_system, &mut self.sp.store, &url)?;
@@ -180,6 +525,38 @@ impl State {
     ControlFlow::Continue(r)
   }
 
+  /// computes the edit for the code action from its data. returns whether the document is still
+  /// the version for which the action was offered. if not, there is no edit.
+  fn resolve_code_action(&mut self, action: &mut lsp_types::CodeAction) -> Result<bool> {
+    let data = action.data.take().context("no data for code action")?;
+    let data: crate::ext::CodeActionData = serde_json::from_value(data)?;
+    let params = lsp_types::TextDocumentPositionParams {
+      text_document: lsp_types::TextDocumentIdentifier { uri: data.uri.clone() },
+      position: data.position,
+    };
+    let pos = helpers::text_doc_pos_params(&self.sp.file_system, &mut self.sp.store, params)?;
+    // the document changed since we offered the action, so its position may be out of date.
+    if self.sp.versions.get(&data.uri).copied() != data.version {
+      return Ok(false);
+    }
+    let edit = match data.kind {
+      crate::ext::CodeActionKind::FillCase => self.analysis.fill_case(pos),
+      crate::ext::CodeActionKind::FillHole(new_text) => {
+        self.analysis.get_hole_range(pos).map(|range| (range, new_text))
+      }
+      crate::ext::CodeActionKind::FillMissingArms => self.analysis.fill_missing_arms(pos),
+      crate::ext::CodeActionKind::StubMissingItems => self.analysis.stub_missing_items(pos),
+      crate::ext::CodeActionKind::FixUndefined => self.analysis.fix_undefined(pos),
+      crate::ext::CodeActionKind::IgnoreUnused => self.analysis.ignore_unused(pos),
+      crate::ext::CodeActionKind::DeleteUnusedVal => {
+        self.analysis.delete_unused_val(pos).map(|range| (range, String::new()))
+      }
+    };
+    action.edit =
+      edit.map(|(range, new_text)| helpers::text_edit(data.uri, data.version, range, new_text));
+    Ok(true)
+  }
+
   pub(crate) fn handle_response(&mut self, res: Response) {
     log::info!("got response: {res:?}");
     let data = match self.sp.req_queue.outgoing.complete(res.id.clone()) {
@@ -248,6 +625,7 @@ impl State {
     n = helpers::try_notif::<lsp_types::notification::DidChangeTextDocument, _>(n, |params| {
       let url = params.text_document.uri;
       let path = helpers::url_to_path_id(&self.sp.file_system, &mut self.sp.store, &url)?;
+      self.sp.versions.insert(url.clone(), params.text_document.version);
       match &mut self.mode {
         Mode::Root(root) => {
           let input = match &mut root.input {
@@ -272,7 +650,7 @@ impl State {
             helpers::apply_changes(text, params.content_changes);
             if self.sp.options.diagnostics_on_change {
               let ds = helpers::diagnostics(
-                self.analysis.get_one(text),
+                self.analysis.get_one(text, self.sp.options.language_dialect),
                 self.sp.options.diagnostics_more_info_hint,
               );
               self.sp.send_diagnostics(url, ds);
@@ -284,12 +662,13 @@ impl State {
       Ok(())
     })?;
     n = helpers::try_notif::<lsp_types::notification::DidOpenTextDocument, _>(n, |params| {
+      let url = params.text_document.uri;
+      self.sp.versions.insert(url.clone(), params.text_document.version);
       if let Mode::NoRoot(open_files) = &mut self.mode {
-        let url = params.text_document.uri;
         let text = params.text_document.text;
         let path = helpers::url_to_path_id(&self.sp.file_system, &mut self.sp.store, &url)?;
         let ds = helpers::diagnostics(
-          self.analysis.get_one(&text),
+          self.analysis.get_one(&text, self.sp.options.language_dialect),
           self.sp.options.diagnostics_more_info_hint,
         );
         self.sp.send_diagnostics(url, ds);
@@ -316,7 +695,7 @@ impl State {
           match open_files.get(&path) {
             Some(text) => {
               let ds = helpers::diagnostics(
-                self.analysis.get_one(text),
+                self.analysis.get_one(text, self.sp.options.language_dialect),
                 self.sp.options.diagnostics_more_info_hint,
               );
               self.sp.send_diagnostics(url, ds);
@@ -328,8 +707,9 @@ impl State {
       Ok(())
     })?;
     n = helpers::try_notif::<lsp_types::notification::DidCloseTextDocument, _>(n, |params| {
+      let url = params.text_document.uri;
+      self.sp.versions.remove(&url);
       if let Mode::NoRoot(open_files) = &mut self.mode {
-        let url = params.text_document.uri;
         let path = helpers::url_to_path_id(&self.sp.file_system, &mut self.sp.store, &url)?;
         open_files.remove(&path);
         self.sp.send_diagnostics(url, Vec::new());
@@ -398,6 +778,9 @@ struct Root {
 struct SPState {
   options: config::Options,
   registered_for_watched_files: bool,
+  resolve_code_action_edits: bool,
+  /// the versions of the open documents, so code actions resolved after an edit can be discarded.
+  versions: FxHashMap<Url, i32>,
   store: paths::Store,
   file_system: paths::RealFileSystem,
   sender: Sender<Message>,
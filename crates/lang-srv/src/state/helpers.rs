@@ -76,7 +76,9 @@ pub(crate) fn diagnostic(
     source: Some("Millet".to_owned()),
     message,
     related_information,
-    tags: None,
+    // unused values are a common case of code that may be deleted.
+    tags: (code == diagnostic_util::Code::n(5029))
+      .then(|| vec![lsp_types::DiagnosticTag::UNNECESSARY]),
     data: None,
   }
 }
@@ -103,7 +105,39 @@ pub(crate) fn lsp_location(
   Some(lsp_types::Location { uri, range: lsp_range(range.val) })
 }
 
-fn analysis_position(pos: lsp_types::Position) -> text_pos::Position {
+/// built-in library files have no path on disk, so we give them URLs with our own scheme.
+pub(crate) fn def_path_url(store: &paths::Store, path: analysis::DefPath) -> Option<Url> {
+  let res = match path {
+    analysis::DefPath::Regular(path) => file_url(store.get_path(path).as_path()),
+    analysis::DefPath::BuiltinLib(name) => Url::parse(&format!("millet:///{name}"))
+      .with_context(|| format!("couldn't parse built-in lib name into a URL: {name}")),
+  };
+  match res {
+    Ok(x) => Some(x),
+    Err(e) => {
+      log::error!("couldn't get def path as a url: {e:#}");
+      None
+    }
+  }
+}
+
+pub(crate) fn def_location(
+  store: &paths::Store,
+  (path, range): (analysis::DefPath, text_pos::Range),
+) -> Option<lsp_types::Location> {
+  Some(lsp_types::Location { uri: def_path_url(store, path)?, range: lsp_range(range) })
+}
+
+/// the inverse of [`def_path_url`] for built-in library files.
+pub(crate) fn builtin_file_name(url: &Url) -> Option<&str> {
+  if url.scheme() == "millet" {
+    url.path().strip_prefix('/')
+  } else {
+    None
+  }
+}
+
+pub(crate) fn analysis_position(pos: lsp_types::Position) -> text_pos::Position {
   text_pos::Position { line: pos.line, character: pos.character }
 }
 
@@ -147,34 +181,303 @@ where
   }
 }
 
+/// the diagnostics are those that the quick fix fixes, if any. the edit is computed from the data
+/// when resolving.
 pub(crate) fn quick_fix(
   title: String,
+  data: &crate::ext::CodeActionData,
+  diagnostics: Vec<lsp_types::Diagnostic>,
+) -> lsp_types::CodeAction {
+  lsp_types::CodeAction {
+    title,
+    kind: Some(lsp_types::CodeActionKind::QUICKFIX),
+    diagnostics: (!diagnostics.is_empty()).then_some(diagnostics),
+    data: Some(serde_json::to_value(data).unwrap()),
+    ..Default::default()
+  }
+}
+
+pub(crate) fn text_edit(
   url: Url,
+  version: Option<i32>,
   range: text_pos::Range,
   new_text: String,
-) -> lsp_types::CodeActionOrCommand {
-  lsp_types::CodeActionOrCommand::CodeAction(lsp_types::CodeAction {
-    title,
-    kind: Some(lsp_types::CodeActionKind::QUICKFIX),
-    edit: Some(lsp_types::WorkspaceEdit {
-      document_changes: Some(lsp_types::DocumentChanges::Edits(vec![
-        lsp_types::TextDocumentEdit {
-          text_document: lsp_types::OptionalVersionedTextDocumentIdentifier {
-            uri: url,
-            version: None,
-          },
-          edits: vec![lsp_types::OneOf::Left(lsp_types::TextEdit {
-            range: lsp_range(range),
-            new_text,
-          })],
-        },
-      ])),
-      ..Default::default()
-    }),
+) -> lsp_types::WorkspaceEdit {
+  lsp_types::WorkspaceEdit {
+    document_changes: Some(lsp_types::DocumentChanges::Edits(vec![lsp_types::TextDocumentEdit {
+      text_document: lsp_types::OptionalVersionedTextDocumentIdentifier { uri: url, version },
+      edits: vec![lsp_types::OneOf::Left(lsp_types::TextEdit {
+        range: lsp_range(range),
+        new_text,
+      })],
+    }])),
     ..Default::default()
+  }
+}
+
+pub(crate) fn call_hierarchy_item(
+  store: &paths::Store,
+  item: analysis::CallHierarchyItem,
+) -> Option<lsp_types::CallHierarchyItem> {
+  Some(lsp_types::CallHierarchyItem {
+    name: item.name,
+    kind: lsp_types::SymbolKind::FUNCTION,
+    tags: None,
+    detail: None,
+    uri: def_path_url(store, item.path)?,
+    range: lsp_range(item.range),
+    selection_range: lsp_range(item.selection_range),
+    data: None,
   })
 }
 
+/// we find the item again by its name.
+pub(crate) fn call_hierarchy_item_pos(
+  item: lsp_types::CallHierarchyItem,
+) -> lsp_types::TextDocumentPositionParams {
+  lsp_types::TextDocumentPositionParams {
+    text_document: lsp_types::TextDocumentIdentifier { uri: item.uri },
+    position: item.selection_range.start,
+  }
+}
+
+pub(crate) fn folding_range(range: analysis::FoldingRange) -> lsp_types::FoldingRange {
+  let kind = match range.kind {
+    analysis::FoldingRangeKind::Region => lsp_types::FoldingRangeKind::Region,
+    analysis::FoldingRangeKind::Comment => lsp_types::FoldingRangeKind::Comment,
+  };
+  lsp_types::FoldingRange {
+    start_line: range.range.start.line,
+    start_character: Some(range.range.start.character),
+    end_line: range.range.end.line,
+    end_character: Some(range.range.end.character),
+    kind: Some(kind),
+  }
+}
+
+/// the ranges are innermost first. if there are none, falls back to the empty range at the
+/// position, since the client expects a selection range for every position it asked about.
+pub(crate) fn selection_range(
+  pos: lsp_types::Position,
+  ranges: Vec<text_pos::Range>,
+) -> lsp_types::SelectionRange {
+  let ret = ranges.into_iter().rev().fold(None, |parent, range| {
+    Some(lsp_types::SelectionRange { range: lsp_range(range), parent: parent.map(Box::new) })
+  });
+  ret.unwrap_or(lsp_types::SelectionRange { range: lsp_types::Range::new(pos, pos), parent: None })
+}
+
+pub(crate) fn document_highlight(
+  highlight: analysis::DocumentHighlight,
+) -> lsp_types::DocumentHighlight {
+  let kind = match highlight.kind {
+    analysis::DocumentHighlightKind::Write => lsp_types::DocumentHighlightKind::WRITE,
+    analysis::DocumentHighlightKind::Read => lsp_types::DocumentHighlightKind::READ,
+    analysis::DocumentHighlightKind::Text => lsp_types::DocumentHighlightKind::TEXT,
+  };
+  lsp_types::DocumentHighlight { range: lsp_range(highlight.range), kind: Some(kind) }
+}
+
+// the `deprecated` field is deprecated, but we still have to set it.
+#[allow(deprecated)]
+pub(crate) fn document_symbol(symbol: analysis::DocumentSymbol) -> lsp_types::DocumentSymbol {
+  lsp_types::DocumentSymbol {
+    name: symbol.name,
+    detail: None,
+    kind: symbol_kind(symbol.kind),
+    tags: None,
+    deprecated: None,
+    range: lsp_range(symbol.range),
+    selection_range: lsp_range(symbol.selection_range),
+    children: Some(symbol.children.into_iter().map(document_symbol).collect()),
+  }
+}
+
+// the `deprecated` field is deprecated, but we still have to set it.
+#[allow(deprecated)]
+pub(crate) fn symbol_information(
+  store: &paths::Store,
+  symbol: analysis::WorkspaceSymbol,
+) -> Option<lsp_types::SymbolInformation> {
+  Some(lsp_types::SymbolInformation {
+    name: symbol.name,
+    kind: symbol_kind(symbol.kind),
+    tags: None,
+    deprecated: None,
+    location: lsp_types::Location {
+      uri: def_path_url(store, symbol.path)?,
+      range: lsp_range(symbol.range),
+    },
+    container_name: symbol.container_name,
+  })
+}
+
+fn symbol_kind(kind: analysis::SymbolKind) -> lsp_types::SymbolKind {
+  match kind {
+    analysis::SymbolKind::Structure => lsp_types::SymbolKind::MODULE,
+    analysis::SymbolKind::Signature => lsp_types::SymbolKind::INTERFACE,
+    analysis::SymbolKind::Functor => lsp_types::SymbolKind::CLASS,
+    analysis::SymbolKind::Function => lsp_types::SymbolKind::FUNCTION,
+    analysis::SymbolKind::Value => lsp_types::SymbolKind::VARIABLE,
+    analysis::SymbolKind::Type => lsp_types::SymbolKind::STRUCT,
+    analysis::SymbolKind::Datatype => lsp_types::SymbolKind::ENUM,
+    analysis::SymbolKind::Constructor => lsp_types::SymbolKind::ENUM_MEMBER,
+    analysis::SymbolKind::Exception => lsp_types::SymbolKind::EVENT,
+  }
+}
+
+pub(crate) fn completion_item(item: analysis::CompletionItem) -> lsp_types::CompletionItem {
+  let kind = match item.kind {
+    analysis::CompletionKind::Value => lsp_types::CompletionItemKind::VARIABLE,
+    analysis::CompletionKind::Function => lsp_types::CompletionItemKind::FUNCTION,
+    analysis::CompletionKind::Constructor => lsp_types::CompletionItemKind::ENUM_MEMBER,
+    analysis::CompletionKind::Exception => lsp_types::CompletionItemKind::EVENT,
+    analysis::CompletionKind::Type => lsp_types::CompletionItemKind::TYPE_PARAMETER,
+    analysis::CompletionKind::Structure => lsp_types::CompletionItemKind::MODULE,
+    analysis::CompletionKind::Signature => lsp_types::CompletionItemKind::INTERFACE,
+    analysis::CompletionKind::Functor => lsp_types::CompletionItemKind::CLASS,
+    analysis::CompletionKind::Label => lsp_types::CompletionItemKind::FIELD,
+  };
+  lsp_types::CompletionItem {
+    label: item.label,
+    kind: Some(kind),
+    detail: item.detail,
+    ..Default::default()
+  }
+}
+
+pub(crate) fn signature_help(help: analysis::SignatureHelp) -> lsp_types::SignatureHelp {
+  let active_parameter = help.active_param.and_then(|x| u32::try_from(x).ok());
+  let parameters = help
+    .params
+    .into_iter()
+    .filter_map(|range| {
+      // the offsets are in bytes, but LSP wants UTF-16 code units.
+      let utf16 = |idx: usize| u32::try_from(help.label.get(..idx)?.encode_utf16().count()).ok();
+      let start = utf16(range.start)?;
+      let end = utf16(range.end)?;
+      Some(lsp_types::ParameterInformation {
+        label: lsp_types::ParameterLabel::LabelOffsets([start, end]),
+        documentation: None,
+      })
+    })
+    .collect();
+  let documentation = help.doc.map(|value| {
+    lsp_types::Documentation::MarkupContent(lsp_types::MarkupContent {
+      kind: lsp_types::MarkupKind::Markdown,
+      value,
+    })
+  });
+  lsp_types::SignatureHelp {
+    signatures: vec![lsp_types::SignatureInformation {
+      label: help.label,
+      documentation,
+      parameters: Some(parameters),
+      active_parameter,
+    }],
+    active_signature: Some(0),
+    active_parameter,
+  }
+}
+
+pub(crate) fn inlay_hint(hint: analysis::InlayHint) -> lsp_types::InlayHint {
+  lsp_types::InlayHint {
+    position: lsp_position(hint.position),
+    label: lsp_types::InlayHintLabel::String(hint.label),
+    kind: Some(lsp_types::InlayHintKind::TYPE),
+    text_edits: None,
+    tooltip: None,
+    padding_left: Some(true),
+    padding_right: None,
+    data: None,
+  }
+}
+
+/// returns the diagnostics with the code, like those the client sent along with a code action
+/// request.
+pub(crate) fn diagnostics_with_code(
+  diagnostics: &[lsp_types::Diagnostic],
+  code: diagnostic_util::Code,
+) -> Vec<lsp_types::Diagnostic> {
+  let code = lsp_types::NumberOrString::Number(code.as_i32());
+  diagnostics.iter().filter(|d| d.code.as_ref() == Some(&code)).cloned().collect()
+}
+
+pub(crate) fn code_lens(url: &Url, lens: analysis::CodeLens) -> lsp_types::CodeLens {
+  let kind = match lens.kind {
+    analysis::CodeLensKind::References => crate::ext::CodeLensKind::References,
+    analysis::CodeLensKind::Type => crate::ext::CodeLensKind::Type,
+  };
+  let data = crate::ext::CodeLensData { uri: url.clone(), kind };
+  lsp_types::CodeLens {
+    range: lsp_range(lens.range),
+    // computed when resolving.
+    command: None,
+    data: Some(serde_json::to_value(data).unwrap()),
+  }
+}
+
+/// the client is expected to register this command, and show the locations as references to the
+/// position when it is run.
+pub(crate) fn show_references_command(
+  url: Url,
+  position: lsp_types::Position,
+  locations: Vec<lsp_types::Location>,
+) -> lsp_types::Command {
+  let title = match locations.len() {
+    1 => "1 reference".to_owned(),
+    n => format!("{n} references"),
+  };
+  lsp_types::Command {
+    title,
+    command: "millet.showReferences".to_owned(),
+    arguments: Some(vec![
+      serde_json::to_value(url).unwrap(),
+      serde_json::to_value(position).unwrap(),
+      serde_json::to_value(locations).unwrap(),
+    ]),
+  }
+}
+
+/// encodes the tokens, which must be in order, relative to each other. the token types and
+/// modifiers are indices into the legend in the capabilities.
+pub(crate) fn semantic_tokens(tokens: Vec<analysis::SemanticToken>) -> lsp_types::SemanticTokens {
+  let mut prev = text_pos::Position { line: 0, character: 0 };
+  let data = tokens
+    .into_iter()
+    .filter_map(|token| {
+      let start = token.range.start;
+      let length = token.range.end.character.checked_sub(start.character)?;
+      let delta_line = start.line - prev.line;
+      let delta_start =
+        if delta_line == 0 { start.character - prev.character } else { start.character };
+      prev = start;
+      let token_type = match token.kind {
+        analysis::SemanticTokenKind::Constructor => 0,
+        analysis::SemanticTokenKind::Exception => 1,
+        analysis::SemanticTokenKind::Value => 2,
+        analysis::SemanticTokenKind::Type => 3,
+        analysis::SemanticTokenKind::Structure => 4,
+        analysis::SemanticTokenKind::Signature => 5,
+        analysis::SemanticTokenKind::Functor => 6,
+        analysis::SemanticTokenKind::TypeVariable => 7,
+        analysis::SemanticTokenKind::Label => 8,
+        analysis::SemanticTokenKind::Operator => 9,
+      };
+      let token_modifiers_bitset =
+        u32::from(token.declaration) | (u32::from(token.default_library) << 1);
+      Some(lsp_types::SemanticToken {
+        delta_line,
+        delta_start,
+        length,
+        token_type,
+        token_modifiers_bitset,
+      })
+    })
+    .collect();
+  lsp_types::SemanticTokens { result_id: None, data }
+}
+
 pub(crate) fn try_req<R, F>(req: Request, f: F) -> ControlFlow<Result<()>, Request>
 where
   R: lsp_types::request::Request,
@@ -10,7 +10,7 @@ use sml_syntax::ast::AstNode;
 use std::fmt;
 use text_size_util::WithRange;
 
-pub use std_basis::StdBasis;
+pub use std_basis::{BuiltinFile, BuiltinLib, StdBasis};
 
 /// The result of analyzing MLB and source files.
 #[derive(Debug)]
@@ -23,6 +23,50 @@ pub struct MlbStatics {
   ///
   /// NOTE see comment in impl about having files analyzed more than once.
   pub sml: paths::PathMap<SourceFile>,
+  /// Information to pass to the next call to [`get`].
+  pub cache: Cache,
+}
+
+/// Information about how source files were analyzed, used to avoid re-analyzing source files whose
+/// inputs have not changed.
+#[derive(Debug, Default)]
+pub struct Cache {
+  /// the language the files were analyzed with. if it changed, nothing may be reused.
+  lang: Language,
+  files: paths::PathMap<CachedFile>,
+}
+
+impl Cache {
+  /// Returns the fixities in effect at the end of the source file at this path, as of the last
+  /// analysis.
+  #[must_use]
+  pub fn fix_env(&self, path: paths::PathId) -> Option<&sml_parse::parser::FixEnv> {
+    self.files.get(&path).map(|file| &file.fix_env_after)
+  }
+}
+
+/// Language settings for analysis.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Language {
+  /// Whether each group file starts with every built-in library, instead of an empty basis.
+  pub implicit_std_basis: bool,
+  /// The dialect. If there is none, every built-in library and language extension is available.
+  pub dialect: Option<config::Dialect>,
+}
+
+/// the inputs and outputs of analyzing a source file.
+#[derive(Debug)]
+struct CachedFile {
+  contents: String,
+  /// the fixities before and after parsing.
+  fix_env_before: sml_parse::parser::FixEnv,
+  fix_env_after: sml_parse::parser::FixEnv,
+  /// the basis the file was checked with, and the basis it exported.
+  basis_before: sml_statics::basis::Basis,
+  basis_after: sml_statics::basis::Basis,
+  /// the syms before checking the file, and the syms added by checking it.
+  syms_before: sml_statics::SymsLens,
+  syms_added: sml_statics::SymsAdded,
 }
 
 /// A source file.
@@ -99,6 +143,12 @@ struct Cx {
   cache: paths::PathMap<MBasis>,
   sml: paths::PathMap<SourceFile>,
   mlb_errors: Vec<Error>,
+  /// the results of the previous analysis, which we remove from as we reuse them.
+  prev_files: paths::PathMap<CachedFile>,
+  prev_sml: paths::PathMap<SourceFile>,
+  next_files: paths::PathMap<CachedFile>,
+  /// source files parsed ahead of time, which we remove from as we use them.
+  pre_parsed: paths::PathMap<Parsed>,
 }
 
 impl Cx {
@@ -116,7 +166,12 @@ impl Cx {
 struct Files<'a> {
   sml: &'a paths::PathMap<String>,
   mlb: &'a paths::PathMap<&'a BasDec>,
-  std_basis: &'a MBasis,
+  std_basis: &'a StdBasis,
+  dialect: Option<config::Dialect>,
+  /// how many threads to parse source files with.
+  threads: usize,
+  /// the basis that each group file starts with.
+  init: &'a MBasis,
 }
 
 #[derive(Debug, Default, Clone)]
@@ -124,6 +179,9 @@ struct MBasis {
   fix_env: sml_parse::parser::FixEnv,
   bas_env: FxHashMap<str_util::Name, MBasis>,
   basis: sml_statics::basis::Basis,
+  /// whether a source file this came from may have changed the syms from what they were in the
+  /// previous analysis. if so, the files that use this can't reuse their previous results.
+  syms_changed: bool,
 }
 
 impl MBasis {
@@ -132,34 +190,55 @@ impl MBasis {
     self.fix_env.extend(other.fix_env);
     self.bas_env.extend(other.bas_env);
     self.basis.append(other.basis);
+    self.syms_changed |= other.syms_changed;
   }
 }
 
 /// Runs analysis.
+///
+/// Each group file starts with an empty basis, and must ask for the built-in libraries it uses.
+/// But if `lang.implicit_std_basis` is set, each group file starts with every built-in library
+/// available in the dialect.
+///
+/// The `cache` and `prev_sml` should be from the previous call to this, if any. A source file is
+/// only re-analyzed if its contents or the basis it is analyzed in changed since then, other than
+/// where the items in that basis are defined.
+///
+/// Source files are parsed with up to `threads` threads. The results do not depend on `threads`.
 #[must_use]
+#[allow(clippy::too_many_arguments)]
 pub fn get(
-  syms: sml_statics::Syms,
-  basis: &sml_statics::basis::Basis,
+  std_basis: &StdBasis,
+  lang: Language,
+  threads: usize,
   sml: &paths::PathMap<String>,
   mlb: &paths::PathMap<&BasDec>,
   root_group_paths: &[paths::PathId],
+  cache: Cache,
+  prev_sml: paths::PathMap<SourceFile>,
 ) -> MlbStatics {
   let mut cx = Cx {
-    syms,
+    syms: std_basis.syms().clone(),
     cache: paths::PathMap::default(),
     sml: paths::PathMap::default(),
     mlb_errors: Vec::new(),
+    prev_files: if cache.lang == lang { cache.files } else { paths::PathMap::default() },
+    prev_sml,
+    next_files: paths::PathMap::default(),
+    pre_parsed: paths::PathMap::default(),
   };
+  let mut init = MBasis::default();
+  if lang.implicit_std_basis {
+    for lib in BuiltinLib::ALL {
+      init.append(builtin_lib_m_basis(std_basis, lang.dialect, lib));
+    }
+  }
+  let files = Files { sml, mlb, std_basis, dialect: lang.dialect, threads, init: &init };
   for &path in root_group_paths {
-    let std_basis = MBasis {
-      fix_env: sml_parse::parser::STD_BASIS.clone(),
-      bas_env: FxHashMap::default(),
-      basis: basis.clone(),
-    };
-    let files = Files { sml, mlb, std_basis: &std_basis };
     get_group_file(&mut cx, files, &mut MBasis::default(), path);
   }
-  MlbStatics { mlb_errors: cx.mlb_errors, syms: cx.syms, sml: cx.sml }
+  let cache = Cache { lang, files: cx.next_files };
+  MlbStatics { mlb_errors: cx.mlb_errors, syms: cx.syms, sml: cx.sml, cache }
 }
 
 fn get_bas_exp(
@@ -214,6 +293,7 @@ fn get_bas_dec(
     // NOTE this doesn't do any of the stuff with the side conditions with the ty names and whatnot.
     // those might be necessary.
     BasDec::Export(ns, lhs, rhs) => {
+      ac.syms_changed |= scope.syms_changed;
       if !ac.basis.add(*ns, lhs.val.clone(), &scope.basis, &rhs.val) {
         let item = match ns {
           sml_statics::basis::Namespace::Structure => Item::Structure,
@@ -224,6 +304,27 @@ fn get_bas_dec(
       }
     }
     BasDec::Seq(decs) => {
+      // fixity declarations flow from each file into the next, but most files have none. so we
+      // parse the source files directly in the sequence in parallel, as if each starts with the
+      // fixities at the start of the sequence. when we get to a file, we use that parse only if
+      // the file really does start with those fixities, and parse it again otherwise.
+      if files.threads > 1 {
+        let to_parse: Vec<_> = decs
+          .iter()
+          .filter_map(|dec| match dec {
+            BasDec::Path(path, PathKind::Source) => {
+              let contents = files.sml.get(path).expect("no sml file for path id");
+              (!can_reuse_prev(cx, *path, &scope.fix_env, contents))
+                .then_some((*path, contents.as_str()))
+            }
+            _ => None,
+          })
+          .collect();
+        if to_parse.len() > 1 {
+          let parsed = get_parsed_parallel(files, &scope.fix_env, to_parse);
+          cx.pre_parsed.extend(parsed);
+        }
+      }
       let mut scope = scope.clone();
       for dec in decs {
         let mut one_m_basis = MBasis::default();
@@ -235,28 +336,33 @@ fn get_bas_dec(
     BasDec::Path(path, kind) => match kind {
       PathKind::Source => {
         let contents = files.sml.get(path).expect("no sml file for path id");
-        let mut fix_env = scope.fix_env.clone();
-        let syntax = SourceFileSyntax::new(&mut fix_env, contents);
-        get_source_file(cx, *path, scope, ac, fix_env, syntax);
+        let parsed = get_parsed(cx, files.dialect, *path, &scope.fix_env, contents);
+        get_source_file(cx, *path, scope, ac, parsed);
       }
       PathKind::Group => match cx.cache.get(path) {
         Some(mb) => ac.append(mb.clone()),
         None => get_group_file(cx, files, ac, *path),
       },
     },
+    BasDec::Builtin(lib) => ac.append(builtin_lib_m_basis(files.std_basis, files.dialect, *lib)),
     BasDec::SourcePathSet(paths) => {
-      let mut syntaxes: paths::PathMap<_> = paths
-        .iter()
-        .map(|path| {
-          let mut fix_env = scope.fix_env.clone();
-          let contents = files.sml.get(path).expect("no sml file for path id");
-          let syntax = SourceFileSyntax::new(&mut fix_env, contents);
-          (*path, (fix_env, syntax))
-        })
-        .collect();
+      // every file starts with the same fixities, so the ones we can't reuse may be parsed in
+      // parallel.
+      let mut syntaxes = paths::PathMap::<Parsed>::default();
+      let mut to_parse = Vec::<(paths::PathId, &str)>::new();
+      for &path in paths {
+        let contents = files.sml.get(&path).expect("no sml file for path id");
+        match get_prev_parsed(cx, path, &scope.fix_env, contents) {
+          Some(parsed) => {
+            syntaxes.insert(path, parsed);
+          }
+          None => to_parse.push((path, contents.as_str())),
+        }
+      }
+      syntaxes.extend(get_parsed_parallel(files, &scope.fix_env, to_parse));
       let hir_roots: paths::PathMap<_> = syntaxes
         .iter()
-        .map(|(&path, (_, syntax))| (path, (&syntax.lower.arenas, syntax.lower.root)))
+        .map(|(&path, parsed)| (path, (&parsed.syntax.lower.arenas, parsed.syntax.lower.root)))
         .collect();
       assert_eq!(syntaxes.len(), hir_roots.len());
       let order = sml_statics::path_order::get(cx.syms.clone(), scope.basis.clone(), hir_roots);
@@ -267,8 +373,8 @@ fn get_bas_dec(
       let mut scope = scope.clone();
       for path in order {
         let mut one_m_basis = MBasis::default();
-        let (fix_env, syntax) = syntaxes.remove(&path).expect("path from order is in syntaxes");
-        get_source_file(cx, path, &scope, &mut one_m_basis, fix_env, syntax);
+        let parsed = syntaxes.remove(&path).expect("path from order is in syntaxes");
+        get_source_file(cx, path, &scope, &mut one_m_basis, parsed);
         scope.append(one_m_basis.clone());
         ac.append(one_m_basis);
       }
@@ -276,21 +382,250 @@ fn get_bas_dec(
   }
 }
 
+/// a source file that was parsed, or whose syntax was reused from the previous analysis.
+struct Parsed {
+  contents: String,
+  fix_env_before: sml_parse::parser::FixEnv,
+  fix_env_after: sml_parse::parser::FixEnv,
+  syntax: SourceFileSyntax,
+  /// the previous results of checking the file, if its syntax was reused.
+  prev: Option<(CachedFile, Vec<sml_statics::Error>, sml_statics::Info)>,
+}
+
+fn get_parsed(
+  cx: &mut Cx,
+  dialect: Option<config::Dialect>,
+  path: paths::PathId,
+  fix_env: &sml_parse::parser::FixEnv,
+  contents: &str,
+) -> Parsed {
+  if let Some(parsed) = get_prev_parsed(cx, path, fix_env, contents) {
+    return parsed;
+  }
+  if let Some(parsed) = cx.pre_parsed.remove(&path) {
+    if parsed.fix_env_before == *fix_env {
+      return parsed;
+    }
+  }
+  let mut fix_env_after = fix_env.clone();
+  let syntax = SourceFileSyntax::new(&mut fix_env_after, dialect, contents);
+  Parsed {
+    contents: contents.to_owned(),
+    fix_env_before: fix_env.clone(),
+    fix_env_after,
+    syntax,
+    prev: None,
+  }
+}
+
+/// parses the contents in parallel, each starting with the fixities.
+fn get_parsed_parallel(
+  files: Files<'_>,
+  fix_env: &sml_parse::parser::FixEnv,
+  to_parse: Vec<(paths::PathId, &str)>,
+) -> Vec<(paths::PathId, Parsed)> {
+  let contents: Vec<_> = to_parse.iter().map(|&(_, contents)| contents).collect();
+  let syntaxes = get_syntaxes_parallel(fix_env, files.dialect, files.threads, &contents);
+  to_parse
+    .into_iter()
+    .zip(syntaxes)
+    .map(|((path, contents), (fix_env_after, syntax))| {
+      let parsed = Parsed {
+        contents: contents.to_owned(),
+        fix_env_before: fix_env.clone(),
+        fix_env_after,
+        syntax,
+        prev: None,
+      };
+      (path, parsed)
+    })
+    .collect()
+}
+
+/// returns whether [`get_prev_parsed`] would return the syntax of the file from the previous
+/// analysis, without taking it.
+fn can_reuse_prev(
+  cx: &Cx,
+  path: paths::PathId,
+  fix_env: &sml_parse::parser::FixEnv,
+  contents: &str,
+) -> bool {
+  cx.prev_sml.contains_key(&path)
+    && cx
+      .prev_files
+      .get(&path)
+      .map_or(false, |entry| entry.contents == contents && entry.fix_env_before == *fix_env)
+}
+
+/// returns the syntax of the file from the previous analysis, if it would parse the same now.
+fn get_prev_parsed(
+  cx: &mut Cx,
+  path: paths::PathId,
+  fix_env: &sml_parse::parser::FixEnv,
+  contents: &str,
+) -> Option<Parsed> {
+  let (entry, file) = cx.prev_files.remove(&path).zip(cx.prev_sml.remove(&path))?;
+  if entry.contents != contents || entry.fix_env_before != *fix_env {
+    return None;
+  }
+  Some(Parsed {
+    contents: contents.to_owned(),
+    fix_env_before: fix_env.clone(),
+    fix_env_after: entry.fix_env_after.clone(),
+    syntax: file.syntax,
+    prev: Some((entry, file.statics_errors, file.info)),
+  })
+}
+
+/// lexes, parses, and lowers each of the contents in parallel with up to `threads` threads, each
+/// starting with the fixity environment. returns the results in the same order as the contents.
+fn get_syntaxes_parallel(
+  fix_env: &sml_parse::parser::FixEnv,
+  dialect: Option<config::Dialect>,
+  threads: usize,
+  contents: &[&str],
+) -> Vec<(sml_parse::parser::FixEnv, SourceFileSyntax)> {
+  if threads <= 1 || contents.len() <= 1 {
+    return contents
+      .iter()
+      .map(|contents| {
+        let mut fix_env = fix_env.clone();
+        let syntax = SourceFileSyntax::new(&mut fix_env, dialect, contents);
+        (fix_env, syntax)
+      })
+      .collect();
+  }
+  let chunk_size = (contents.len() + threads - 1) / threads;
+  std::thread::scope(|s| {
+    let handles: Vec<_> = contents
+      .chunks(chunk_size)
+      .map(|chunk| {
+        s.spawn(move || {
+          chunk
+            .iter()
+            .map(|contents| {
+              let mut fix_env = fix_env.clone();
+              let parts = SyntaxParts::new(&mut fix_env, dialect, contents);
+              (fix_env, parts)
+            })
+            .collect::<Vec<_>>()
+        })
+      })
+      .collect();
+    // join in the order we spawned, so the results are in the same order as the contents.
+    handles
+      .into_iter()
+      .flat_map(|handle| handle.join().expect("syntax thread panicked"))
+      .map(|(fix_env, parts)| (fix_env, parts.into_syntax()))
+      .collect()
+  })
+}
+
+/// the parts of a [`SourceFileSyntax`], but with the syntax tree as a green node, so they may be
+/// sent across threads.
+struct SyntaxParts {
+  pos_db: text_pos::PositionDb,
+  lex_errors: Vec<sml_lex::Error>,
+  green: sml_syntax::rowan::GreenNode,
+  parse_errors: Vec<sml_parse::parser::Error>,
+  lower: sml_lower::Lower,
+}
+
+impl SyntaxParts {
+  fn new(
+    fix_env: &mut sml_parse::parser::FixEnv,
+    dialect: Option<config::Dialect>,
+    contents: &str,
+  ) -> Self {
+    let syntax = SourceFileSyntax::new(fix_env, dialect, contents);
+    Self {
+      pos_db: syntax.pos_db,
+      lex_errors: syntax.lex_errors,
+      green: syntax.parse.root.syntax().green().into_owned(),
+      parse_errors: syntax.parse.errors,
+      lower: syntax.lower,
+    }
+  }
+
+  fn into_syntax(self) -> SourceFileSyntax {
+    let root = sml_syntax::SyntaxNode::new_root(self.green);
+    let root = sml_syntax::ast::Root::cast(root).expect("should be a root");
+    SourceFileSyntax {
+      pos_db: self.pos_db,
+      lex_errors: self.lex_errors,
+      parse: sml_parse::Parse { root, errors: self.parse_errors },
+      lower: self.lower,
+    }
+  }
+}
+
 fn get_source_file(
   cx: &mut Cx,
   path: paths::PathId,
   scope: &MBasis,
   ac: &mut MBasis,
-  fix_env: sml_parse::parser::FixEnv,
-  syntax: SourceFileSyntax,
+  parsed: Parsed,
 ) {
-  let mode = sml_statics::Mode::Regular(Some(path));
-  let checked =
-    sml_statics::get(&mut cx.syms, &scope.basis, mode, &syntax.lower.arenas, syntax.lower.root);
-  let mut info = checked.info;
-  add_all_doc_comments(syntax.parse.root.syntax(), &syntax.lower, &mut info);
-  let file = SourceFile { syntax, statics_errors: checked.errors, info };
-  ac.append(MBasis { fix_env, bas_env: FxHashMap::default(), basis: checked.basis });
+  let syntax = parsed.syntax;
+  let syms_before = cx.syms.lens();
+  let prev = parsed.prev.map(|(entry, statics_errors, info)| {
+    let def_map = if scope.syms_changed || entry.syms_before != syms_before {
+      None
+    } else {
+      sml_statics::DefMap::between_bases(&entry.basis_before, &scope.basis)
+    };
+    (entry, statics_errors, info, def_map)
+  });
+  let (file, basis_after, syms_added, syms_changed) = match prev {
+    // the file and everything it depends on is the same as before, except maybe where the things
+    // it depends on are defined.
+    Some((entry, statics_errors, mut info, Some(def_map))) => {
+      let mut basis_after = entry.basis_after;
+      let mut syms_added = entry.syms_added;
+      if !def_map.is_empty() {
+        info.remap_defs(&def_map);
+        basis_after.remap_defs(&def_map);
+        syms_added.remap_defs(&def_map);
+      }
+      cx.syms.add(syms_added.clone());
+      let file = SourceFile { syntax, statics_errors, info };
+      (file, basis_after, syms_added, false)
+    }
+    prev => {
+      let mode = sml_statics::Mode::Regular(Some(path));
+      let checked =
+        sml_statics::get(&mut cx.syms, &scope.basis, mode, &syntax.lower.arenas, syntax.lower.root);
+      let mut info = checked.info;
+      add_all_doc_comments(syntax.parse.root.syntax(), &syntax.lower, &mut info);
+      let syms_added = cx.syms.added_since(syms_before);
+      // files that use the syms get their defs from the syms, not from their previous results, so
+      // the defs may differ.
+      let same_syms = prev.map_or(false, |(entry, _, _, _)| {
+        entry.syms_before == syms_before
+          && sml_statics::DefMap::between_syms_added(&entry.syms_added, &syms_added).is_some()
+      });
+      // the syms the file exports may contain the changed syms from the files it uses.
+      let syms_changed = scope.syms_changed || (!same_syms && !syms_added.is_empty());
+      let file = SourceFile { syntax, statics_errors: checked.errors, info };
+      (file, checked.basis, syms_added, syms_changed)
+    }
+  };
+  ac.append(MBasis {
+    fix_env: parsed.fix_env_after.clone(),
+    bas_env: FxHashMap::default(),
+    basis: basis_after.clone(),
+    syms_changed,
+  });
+  let entry = CachedFile {
+    contents: parsed.contents,
+    fix_env_before: parsed.fix_env_before,
+    fix_env_after: parsed.fix_env_after,
+    basis_before: scope.basis.clone(),
+    basis_after,
+    syms_before,
+    syms_added,
+  };
+  cx.next_files.insert(path, entry);
   // NOTE: we would like to assert that the insert returns None, but actually it may not
   // always.
   //
@@ -317,10 +652,14 @@ pub struct SourceFileSyntax {
 }
 
 impl SourceFileSyntax {
-  /// Starts processing a single source file.
-  pub fn new(fix_env: &mut sml_parse::parser::FixEnv, contents: &str) -> Self {
+  /// Starts processing a single source file, written in the dialect, if any.
+  pub fn new(
+    fix_env: &mut sml_parse::parser::FixEnv,
+    dialect: Option<config::Dialect>,
+    contents: &str,
+  ) -> Self {
     let (lex_errors, parse) = Self::lex_and_parse(fix_env, contents);
-    let mut lower = sml_lower::get(&parse.root);
+    let mut lower = sml_lower::get(dialect, &parse.root);
     sml_ty_var_scope::get(&mut lower.arenas, lower.root);
     Self { pos_db: text_pos::PositionDb::new(contents), lex_errors, parse, lower }
   }
@@ -336,11 +675,30 @@ impl SourceFileSyntax {
   }
 }
 
+/// returns the basis for the built-in library, which is empty if it is not in the dialect.
+fn builtin_lib_m_basis(
+  std_basis: &StdBasis,
+  dialect: Option<config::Dialect>,
+  lib: BuiltinLib,
+) -> MBasis {
+  if !dialect.map_or(true, |d| lib.in_dialect(d)) {
+    return MBasis::default();
+  }
+  let fix_env = match lib {
+    BuiltinLib::StdBasis => sml_parse::parser::STD_BASIS.clone(),
+    BuiltinLib::SmlnjLib | BuiltinLib::SmlOfNj | BuiltinLib::Mlton => {
+      sml_parse::parser::FixEnv::default()
+    }
+  };
+  let basis = std_basis.lib_basis(lib).cloned().unwrap_or_default();
+  MBasis { fix_env, bas_env: FxHashMap::default(), basis, syms_changed: false }
+}
+
 /// Processes a single group file.
 fn get_group_file(cx: &mut Cx, files: Files<'_>, ac: &mut MBasis, path: paths::PathId) {
   let dec = files.mlb.get(&path).expect("no mlb file for path id");
   let mut path_ac = MBasis::default();
-  get_bas_dec(cx, files, path, files.std_basis, &mut path_ac, dec);
+  get_bas_dec(cx, files, path, files.init, &mut path_ac, dec);
   cx.cache.insert(path, path_ac.clone());
   ac.append(path_ac);
 }
@@ -376,6 +734,7 @@ pub enum BasDec {
   Path(paths::PathId, PathKind),
   /// Used by CM.
   SourcePathSet(FxHashSet<paths::PathId>),
+  Builtin(BuiltinLib),
 }
 
 impl BasDec {
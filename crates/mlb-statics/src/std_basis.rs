@@ -1,18 +1,66 @@
 //! Representing the standard basis libraries and all other "built-in" libraries by parsing and
-//! analyzing them at runtime at when starting up.
+//! analyzing them.
+//!
+//! The results of analyzing the full standard basis may be serialized ahead of time, so that when
+//! starting up, we need only deserialize them and parse the files.
 
-use crate::{add_all_doc_comments, SourceFileSyntax};
+use crate::{add_all_doc_comments, get_syntaxes_parallel, SourceFileSyntax};
 use fast_hash::FxHashMap;
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use sml_statics::{basis, Info, Syms};
 use sml_syntax::ast::AstNode as _;
+use std::borrow::Cow;
+use std::sync::Arc;
 
 /// A standard basis.
 #[derive(Debug, Clone)]
 pub struct StdBasis {
   syms: Syms,
   basis: basis::Basis,
-  info: FxHashMap<&'static str, Info>,
+  libs: FxHashMap<BuiltinLib, basis::Basis>,
+  files: FxHashMap<&'static str, Arc<BuiltinFile>>,
+}
+
+/// A built-in library, which a group may ask for with a well-known path, like `$/basis.cm`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BuiltinLib {
+  /// The standard basis library.
+  StdBasis,
+  /// The SML/NJ library.
+  SmlnjLib,
+  /// The SML/NJ extensions, like `SMLofNJ`.
+  SmlOfNj,
+  /// The MLton extensions, like `MLton`.
+  Mlton,
+}
+
+impl BuiltinLib {
+  /// All the built-in libraries, in the order they are analyzed.
+  pub(crate) const ALL: [Self; 4] = [Self::StdBasis, Self::SmlnjLib, Self::SmlOfNj, Self::Mlton];
+
+  /// Returns whether this library is available in the dialect.
+  pub(crate) fn in_dialect(self, dialect: config::Dialect) -> bool {
+    match self {
+      BuiltinLib::StdBasis => true,
+      BuiltinLib::SmlnjLib => matches!(dialect, config::Dialect::SmlNj | config::Dialect::Mlton),
+      BuiltinLib::SmlOfNj => matches!(dialect, config::Dialect::SmlNj),
+      BuiltinLib::Mlton => matches!(dialect, config::Dialect::Mlton),
+    }
+  }
+}
+
+/// A built-in library file in a standard basis.
+///
+/// We don't keep the syntax tree, since it can't be shared across threads.
+#[derive(Debug)]
+#[allow(missing_docs)]
+pub struct BuiltinFile {
+  /// Not always exactly the contents in `sml_libs`, since we patch some files before analyzing.
+  pub contents: Cow<'static, str>,
+  pub pos_db: text_pos::PositionDb,
+  pub lower: sml_lower::Lower,
+  pub info: Info,
 }
 
 impl StdBasis {
@@ -41,27 +89,128 @@ impl StdBasis {
     &self.basis
   }
 
+  /// Returns the basis for the built-in libraries available in the dialect. If there is no
+  /// dialect, this is the same as [`Self::basis`].
+  #[must_use]
+  pub fn dialect_basis(&self, dialect: Option<config::Dialect>) -> basis::Basis {
+    let dialect = match dialect {
+      Some(x) => x,
+      None => return self.basis.clone(),
+    };
+    let mut ret = basis::Basis::default();
+    for lib in BuiltinLib::ALL {
+      if !lib.in_dialect(dialect) {
+        continue;
+      }
+      if let Some(basis) = self.libs.get(&lib) {
+        ret.append(basis.clone());
+      }
+    }
+    ret
+  }
+
+  /// Returns the basis for the library, if this has it.
+  pub(crate) fn lib_basis(&self, lib: BuiltinLib) -> Option<&basis::Basis> {
+    self.libs.get(&lib)
+  }
+
   /// Look up a std basis file's info.
   #[must_use]
   pub fn get_info(&self, s: &str) -> Option<&Info> {
-    self.info.get(s)
+    self.get_file(s).map(|x| &x.info)
   }
+
+  /// Look up a std basis file.
+  #[must_use]
+  pub fn get_file(&self, s: &str) -> Option<&BuiltinFile> {
+    self.files.get(s).map(AsRef::as_ref)
+  }
+
+  /// Returns an iterator over the built-in library files, with their names.
+  pub fn files(&self) -> impl Iterator<Item = (&'static str, &BuiltinFile)> + '_ {
+    self.files.iter().map(|(&name, file)| (name, file.as_ref()))
+  }
+
+  /// Returns the results of analyzing this as bytes, to later pass to [`Self::full_from_bytes`].
+  ///
+  /// # Panics
+  ///
+  /// If serialization failed (an internal error).
+  #[must_use]
+  pub fn to_bytes(&self) -> Vec<u8> {
+    let computed = ComputedRef {
+      syms: &self.syms,
+      basis: &self.basis,
+      libs: &self.libs,
+      infos: self.files.iter().map(|(&name, file)| (name, &file.info)).collect(),
+    };
+    sml_statics::with_serde_cx(&[], || bincode::serialize(&computed))
+      .expect("couldn't serialize std basis")
+  }
+
+  /// The full standard basis, from the bytes from calling [`Self::to_bytes`] on [`Self::full`].
+  ///
+  /// This only lexes, parses, and lowers the files, which is much faster than also analyzing them
+  /// like `full` does.
+  ///
+  /// # Panics
+  ///
+  /// If the bytes were not from the full standard basis.
+  #[must_use]
+  pub fn full_from_bytes(bytes: &[u8]) -> Self {
+    let names: Vec<_> = full_files().map(|(_, name, _)| name).collect();
+    let computed: Computed = sml_statics::with_serde_cx(&names, || bincode::deserialize(bytes))
+      .expect("couldn't deserialize std basis");
+    let mut infos = computed.infos;
+    let files: FxHashMap<_, _> =
+      get_syntaxes(full_files().map(|(_, name, contents)| (name, contents)))
+        .into_iter()
+        .map(|(name, contents, syntax)| {
+          let info = infos.remove(name).expect("no info for std basis file");
+          let file = BuiltinFile { contents, pos_db: syntax.pos_db, lower: syntax.lower, info };
+          (name, Arc::new(file))
+        })
+        .collect();
+    assert!(infos.is_empty(), "info for a file not in the std basis");
+    Self { syms: computed.syms, basis: computed.basis, libs: computed.libs, files }
+  }
+}
+
+/// the results of analyzing a std basis.
+#[derive(Deserialize)]
+struct Computed {
+  syms: Syms,
+  basis: basis::Basis,
+  libs: FxHashMap<BuiltinLib, basis::Basis>,
+  infos: FxHashMap<String, Info>,
+}
+
+/// like [`Computed`], but borrowed. serializes the same way.
+#[derive(Serialize)]
+struct ComputedRef<'a> {
+  syms: &'a Syms,
+  basis: &'a basis::Basis,
+  libs: &'a FxHashMap<BuiltinLib, basis::Basis>,
+  infos: FxHashMap<&'static str, &'a Info>,
 }
 
 static MINIMAL: Lazy<StdBasis> = Lazy::new(|| get_std_basis(std::iter::empty()));
 
-static FULL: Lazy<StdBasis> = Lazy::new(|| {
-  get_std_basis(
-    std::iter::empty()
-      .chain(sml_libs::primitive::FILES)
-      .chain(sml_libs::std_basis::FILES)
-      .chain(sml_libs::std_basis_extra::FILES)
-      .chain(sml_libs::smlnj_lib::FILES)
-      .chain(sml_libs::sml_of_nj::FILES)
-      .chain(sml_libs::mlton::FILES)
-      .copied(),
-  )
-});
+static FULL: Lazy<StdBasis> = Lazy::new(|| get_std_basis(full_files()));
+
+fn full_files() -> impl Iterator<Item = (BuiltinLib, &'static str, &'static str)> {
+  let libs: [(BuiltinLib, &'static [(&'static str, &'static str)]); 6] = [
+    (BuiltinLib::StdBasis, sml_libs::primitive::FILES),
+    (BuiltinLib::StdBasis, sml_libs::std_basis::FILES),
+    (BuiltinLib::StdBasis, sml_libs::std_basis_extra::FILES),
+    (BuiltinLib::SmlnjLib, sml_libs::smlnj_lib::FILES),
+    (BuiltinLib::SmlOfNj, sml_libs::sml_of_nj::FILES),
+    (BuiltinLib::Mlton, sml_libs::mlton::FILES),
+  ];
+  libs
+    .into_iter()
+    .flat_map(|(lib, files)| files.iter().map(move |&(name, contents)| (lib, name, contents)))
+}
 
 const STREAM_IO_REGULAR: &str = "  structure StreamIO : STREAM_IO";
 const STREAM_IO_TEXT: &str = r#"  structure StreamIO : TEXT_STREAM_IO
@@ -73,12 +222,43 @@ const INCLUDE_IMPERATIVE_IO_HACK: &str = "  include IMPERATIVE_IO_HACK";
 
 fn get_std_basis<I>(files: I) -> StdBasis
 where
-  I: Iterator<Item = (&'static str, &'static str)>,
+  I: Iterator<Item = (BuiltinLib, &'static str, &'static str)>,
 {
   let (mut syms, mut basis) = basis::minimal();
+  // the minimal basis has the std basis's most fundamental items.
+  let mut libs = FxHashMap::<BuiltinLib, basis::Basis>::default();
+  libs.insert(BuiltinLib::StdBasis, basis.clone());
+  let (file_libs, files): (Vec<_>, Vec<_>) =
+    files.map(|(lib, name, contents)| (lib, (name, contents))).unzip();
+  let files: FxHashMap<_, _> = get_syntaxes(files.into_iter())
+    .into_iter()
+    .zip(file_libs)
+    .map(|((name, contents, started), lib)| {
+      let mode = sml_statics::Mode::BuiltinLib(name);
+      let low = started.lower;
+      let checked = sml_statics::get(&mut syms, &basis, mode, &low.arenas, low.root);
+      libs.entry(lib).or_default().append(checked.basis.clone());
+      basis.append(checked.basis);
+      if let Some(e) = checked.errors.first() {
+        let e = e.display(&syms, checked.info.meta_vars(), config::ErrorLines::One);
+        panic!("{name}: statics error: {e}");
+      }
+      let mut info = checked.info;
+      add_all_doc_comments(started.parse.root.syntax(), &low, &mut info);
+      (name, Arc::new(BuiltinFile { contents, pos_db: started.pos_db, lower: low, info }))
+    })
+    .collect();
+  StdBasis { syms, basis, libs, files }
+}
+
+/// patches, lexes, parses, and lowers the files. returns them in the same order.
+fn get_syntaxes<I>(files: I) -> Vec<(&'static str, Cow<'static, str>, SourceFileSyntax)>
+where
+  I: Iterator<Item = (&'static str, &'static str)>,
+{
   let mut imperative_io_hack = None::<String>;
-  let info: FxHashMap<_, _> = files
-    .map(|(name, mut contents)| {
+  let files: Vec<_> = files
+    .map(|(name, contents)| {
       if name == "std_basis/imperative-io.sml" {
         let mut lines: Vec<_> = contents
           .lines()
@@ -88,8 +268,7 @@ where
         assert_eq!(lines.pop().unwrap(), "end");
         imperative_io_hack = Some(lines.join("\n"));
       }
-      let owned_contents: String;
-      if name == "std_basis/text-io.sml" {
+      let contents = if name == "std_basis/text-io.sml" {
         let lines: Vec<_> = contents
           .lines()
           .map(|line| {
@@ -100,11 +279,20 @@ where
             }
           })
           .collect();
-        owned_contents = lines.join("\n");
-        contents = &owned_contents;
-      }
-      let mut fix_env = sml_parse::parser::STD_BASIS.clone();
-      let started = SourceFileSyntax::new(&mut fix_env, contents);
+        Cow::Owned(lines.join("\n"))
+      } else {
+        Cow::Borrowed(contents)
+      };
+      (name, contents)
+    })
+    .collect();
+  let contents: Vec<_> = files.iter().map(|(_, contents)| contents.as_ref()).collect();
+  let threads = std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get);
+  let syntaxes = get_syntaxes_parallel(&sml_parse::parser::STD_BASIS, None, threads, &contents);
+  files
+    .into_iter()
+    .zip(syntaxes)
+    .map(|((name, contents), (_, started))| {
       if let Some(e) = started.lex_errors.first() {
         panic!("{name}: lex error: {}", e.display());
       }
@@ -114,18 +302,7 @@ where
       if let Some(e) = started.lower.errors.first() {
         panic!("{name}: lower error: {}", e.display());
       }
-      let mode = sml_statics::Mode::BuiltinLib(name);
-      let low = started.lower;
-      let checked = sml_statics::get(&mut syms, &basis, mode, &low.arenas, low.root);
-      basis.append(checked.basis);
-      if let Some(e) = checked.errors.first() {
-        let e = e.display(&syms, checked.info.meta_vars(), config::ErrorLines::One);
-        panic!("{name}: statics error: {e}");
-      }
-      let mut info = checked.info;
-      add_all_doc_comments(started.parse.root.syntax(), &low, &mut info);
-      (name, info)
+      (name, contents, started)
     })
-    .collect();
-  StdBasis { syms, basis, info }
+    .collect()
 }
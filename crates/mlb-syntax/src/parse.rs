@@ -80,31 +80,23 @@ fn bas_dec(p: &mut Parser<'_>) -> Result<BasDec> {
   let mut ac = Vec::<BasDec>::new();
   loop {
     let bd = match bas_dec_one(p)? {
-      BasDecOne::NoStartTok => break,
-      BasDecOne::StdBasisPath => None,
-      BasDecOne::Ok(bd) => Some(bd),
+      Some(x) => x,
+      None => break,
     };
     if p.cur() == Some(Token::Semicolon) {
       p.bump();
     }
-    if let Some(bd) = bd {
-      ac.push(bd);
-    }
+    ac.push(bd);
   }
   let ret = if ac.len() == 1 { ac.pop().unwrap() } else { BasDec::Seq(ac) };
   Ok(ret)
 }
 
-enum BasDecOne {
-  NoStartTok,
-  StdBasisPath,
-  Ok(BasDec),
-}
-
-fn bas_dec_one(p: &mut Parser<'_>) -> Result<BasDecOne> {
+/// returns `None` if there was no basis declaration start token.
+fn bas_dec_one(p: &mut Parser<'_>) -> Result<Option<BasDec>> {
   let tok = match p.cur_tok() {
     Some(x) => x,
-    None => return Ok(BasDecOne::NoStartTok),
+    None => return Ok(None),
   };
   let ret = match tok.val {
     Token::Basis => {
@@ -150,23 +142,24 @@ fn bas_dec_one(p: &mut Parser<'_>) -> Result<BasDecOne> {
     // TODO allow string paths as well
     Token::BarePath(path) => {
       p.bump();
-      let path = match paths::slash_var_path::get(path, p.env) {
+      let parsed = match paths::slash_var_path::get(path, p.env) {
         Ok(x) => x,
         Err(e) => {
           if let paths::slash_var_path::Error::Undefined(var) = &e {
-            // ignore the sml lib paths (http://mlton.org/MLBasisPathMap) since they're baked in.
+            // keep the sml lib paths (http://mlton.org/MLBasisPathMap) as written, since the
+            // libraries there are baked in.
             if var == "SML_LIB" {
-              return Ok(BasDecOne::StdBasisPath);
+              return Ok(Some(BasDec::StdBasisPath(tok.wrap(path.to_owned()))));
             }
           }
           return p.err(ErrorKind::SlashVarPathError(e));
         }
       };
-      let kind = match path_kind(path.as_path()) {
+      let kind = match path_kind(parsed.as_path()) {
         Some(x) => x,
         None => return p.err(ErrorKind::PathNotSmlOrMlb),
       };
-      BasDec::Path(tok.wrap(ParsedPath { kind, path }))
+      BasDec::Path(tok.wrap(ParsedPath { kind, path: parsed }))
     }
     Token::Ann => {
       p.bump();
@@ -178,9 +171,9 @@ fn bas_dec_one(p: &mut Parser<'_>) -> Result<BasDecOne> {
       p.eat(Token::End)?;
       BasDec::Ann(s, bd.into())
     }
-    _ => return Ok(BasDecOne::NoStartTok),
+    _ => return Ok(None),
   };
-  Ok(BasDecOne::Ok(ret))
+  Ok(Some(ret))
 }
 
 fn path_kind(path: &Path) -> Option<PathKind> {
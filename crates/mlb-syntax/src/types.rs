@@ -106,6 +106,8 @@ pub enum BasDec {
   Local(Box<BasDec>, Box<BasDec>),
   Export(Namespace, NamesSeq),
   Path(WithRange<ParsedPath>),
+  /// A path using `$(SML_LIB)`, which is left as written, since the libraries there are built in.
+  StdBasisPath(WithRange<String>),
   Ann(WithRange<String>, Box<BasDec>),
   Seq(Vec<BasDec>),
 }
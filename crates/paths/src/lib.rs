@@ -8,6 +8,7 @@ pub mod slash_var_path;
 pub use glob::{GlobError, PatternError};
 
 use fast_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
 /// A store of paths.
@@ -45,7 +46,7 @@ impl Store {
 }
 
 /// A path identifier. Cheap to copy and compare.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct PathId(idx::Idx);
 
 impl PathId {
@@ -5,6 +5,7 @@
 use std::fmt;
 
 use la_arena::Arena;
+use serde::{Deserialize, Serialize};
 use str_util::{Name, SmolStr};
 
 pub use la_arena;
@@ -37,6 +38,36 @@ macro_rules! mk_idx {
         }
       }
     )*
+
+    /// an [`Idx`] with the raw index, since arena indices can't be (de)serialized.
+    #[derive(Serialize, Deserialize)]
+    enum SerdeIdx {
+      $($name(u32),)*
+    }
+
+    impl Serialize for Idx {
+      fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+      where
+        S: serde::Serializer,
+      {
+        let idx = match *self {
+          $(Self::$name(idx) => SerdeIdx::$name(idx.into_raw().into()),)*
+        };
+        idx.serialize(serializer)
+      }
+    }
+
+    impl<'de> Deserialize<'de> for Idx {
+      fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+      where
+        D: serde::Deserializer<'de>,
+      {
+        let idx = match SerdeIdx::deserialize(deserializer)? {
+          $(SerdeIdx::$name(idx) => Self::$name(la_arena::Idx::from_raw(idx.into())),)*
+        };
+        Ok(idx)
+      }
+    }
   };
 }
 
@@ -50,7 +81,7 @@ pub type OptIdx<T> = Option<la_arena::Idx<T>>;
 ///
 /// An implementation MUST NOT depend on this for semantics, e.g. type check differently based on
 /// the flavor. It MUST only be used for "niceties", e.g. to emit better error messages.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Flavor {
   /// It used sugar.
   Sugared,
@@ -318,7 +349,7 @@ pub enum Ty {
   Fn(TyIdx, TyIdx),
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum Lab {
   Name(Name),
   Num(usize),
@@ -364,7 +395,7 @@ impl fmt::Display for Int {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Path {
   prefix: Vec<Name>,
   last: Name,
@@ -414,7 +445,7 @@ impl fmt::Display for Path {
   }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct TyVar(Name);
 
 impl TyVar {
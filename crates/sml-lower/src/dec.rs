@@ -337,7 +337,7 @@ fn get_spec_one(cx: &mut Cx, dec: Option<ast::DecOne>) -> Vec<sml_hir::SpecIdx>
       if let Some(with_type) = dec.with_type() {
         cx.err(
           with_type.syntax().text_range(),
-          ErrorKind::Unsupported("`withtype` in specifications"),
+          cx.unsupported_extension("`withtype` in specifications"),
         );
       }
       // need to collect to end the exclusive borrow on `cx`
@@ -644,7 +644,7 @@ fn get_one(cx: &mut Cx, dec: ast::DecOne) -> sml_hir::DecIdx {
     }
     ast::DecOne::DoDec(ref inner) => {
       // emit an error, but lower anyway.
-      cx.err(dec.syntax().text_range(), ErrorKind::Unsupported("`do` declarations"));
+      cx.err(dec.syntax().text_range(), cx.unsupported_extension("`do` declarations"));
       sml_hir::Dec::Val(
         Vec::new(),
         vec![sml_hir::ValBind {
@@ -28,14 +28,14 @@ pub(crate) fn get(cx: &mut Cx, exp: Option<ast::Exp>) -> sml_hir::ExpIdx {
             Some(eq_exp) => get(cx, eq_exp.exp()),
             None => match &lab {
               sml_hir::Lab::Name(name) => {
-                cx.err(lab_tr, ErrorKind::Unsupported("expression row punning"));
+                cx.err(lab_tr, cx.unsupported_extension("expression row punning"));
                 cx.exp(sml_hir::Exp::Path(sml_hir::Path::one(name.clone())), ptr.clone())
               }
               sml_hir::Lab::Num(_) => {
                 // NOTE: we explicitly duplicate the `err` call in both branches, to remind us that
                 // if we ever actually accepted expression row punning, we should add a separate
                 // error here rejecting the attempt to pun with a int label.
-                cx.err(lab_tr, ErrorKind::Unsupported("expression row punning"));
+                cx.err(lab_tr, cx.unsupported_extension("expression row punning"));
                 None
               }
             },
@@ -73,7 +73,7 @@ pub(crate) fn get(cx: &mut Cx, exp: Option<ast::Exp>) -> sml_hir::ExpIdx {
       })
     }
     ast::Exp::VectorExp(exp) => {
-      cx.err(exp.syntax().text_range(), ErrorKind::Unsupported("vector expressions"));
+      cx.err(exp.syntax().text_range(), cx.unsupported_extension("vector expressions"));
       return None;
     }
     ast::Exp::SeqExp(exp) => return exps_in_seq(cx, exp.exps_in_seq(), &ptr),
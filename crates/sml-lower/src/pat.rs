@@ -16,6 +16,9 @@ pub(crate) fn get(
   if or_pat.rest.is_empty() {
     or_pat.first
   } else {
+    if !cx.allows_extensions() {
+      cx.err(ptr.text_range(), ErrorKind::NotInDialect("or patterns"));
+    }
     cx.pat(sml_hir::Pat::Or(or_pat), ptr)
   }
 }
@@ -103,7 +106,7 @@ fn get_or(cx: &mut Cx, flavor: Option<MatcherFlavor>, pat: ast::Pat) -> Option<s
       })
     }
     ast::Pat::VectorPat(pat) => {
-      cx.err(pat.syntax().text_range(), ErrorKind::Unsupported("vector patterns"));
+      cx.err(pat.syntax().text_range(), cx.unsupported_extension("vector patterns"));
       return None;
     }
     ast::Pat::InfixPat(pat) => {
@@ -4,9 +4,12 @@ use crate::util::{Cx, Lower};
 use sml_syntax::ast;
 
 /// Does the conversion.
+///
+/// Language extensions not in the `dialect` are errors. If there is no `dialect`, every extension
+/// is allowed.
 #[must_use]
-pub fn get(root: &ast::Root) -> Lower {
-  let mut cx = Cx::default();
+pub fn get(dialect: Option<config::Dialect>, root: &ast::Root) -> Lower {
+  let mut cx = Cx::new(dialect);
   let idx = crate::dec::get_top_dec(&mut cx, root.dec());
   cx.finish(idx)
 }
@@ -65,6 +65,7 @@ pub(crate) enum ErrorKind {
   AsPatLhsNotName,
   PatNameIsNameOfContainingFun(MatcherFlavor),
   EmptyFun,
+  NotInDialect(&'static str),
 }
 
 impl fmt::Display for ErrorKind {
@@ -108,6 +109,7 @@ impl fmt::Display for ErrorKind {
         write!(f, "name bound in pattern inside a `{flavor}` matches name of a `fun` that contains the `{flavor}`")
       }
       ErrorKind::EmptyFun => f.write_str("`fun` with no parameters"),
+      ErrorKind::NotInDialect(s) => write!(f, "not allowed in this dialect: {s}"),
     }
   }
 }
@@ -180,6 +182,7 @@ impl Error {
       ErrorKind::AsPatLhsNotName => Code::n(4024),
       ErrorKind::PatNameIsNameOfContainingFun(_) => Code::n(4025),
       ErrorKind::EmptyFun => Code::n(4026),
+      ErrorKind::NotInDialect(_) => Code::n(4027),
     }
   }
 
@@ -218,10 +221,29 @@ pub(crate) struct Cx {
   arenas: sml_hir::Arenas,
   ptrs: Ptrs,
   fun_names: Vec<str_util::Name>,
+  dialect: Option<config::Dialect>,
 }
 
 #[allow(clippy::unnecessary_wraps)]
 impl Cx {
+  pub(crate) fn new(dialect: Option<config::Dialect>) -> Self {
+    Self { dialect, ..Self::default() }
+  }
+
+  /// Returns whether extensions to the Definition are allowed.
+  pub(crate) fn allows_extensions(&self) -> bool {
+    self.dialect.map_or(true, config::Dialect::allows_extensions)
+  }
+
+  /// Returns the error for an extension `s` that is unsupported, or not allowed in this dialect.
+  pub(crate) fn unsupported_extension(&self, s: &'static str) -> ErrorKind {
+    if self.allows_extensions() {
+      ErrorKind::Unsupported(s)
+    } else {
+      ErrorKind::NotInDialect(s)
+    }
+  }
+
   /// Returns a `Name` that is both:
   /// - not writeable in user code, and will thus not collide with any identifiers in user code;
   /// - distinct from all other `Name`s returned from self thus far, and will thus not collide
@@ -125,7 +125,7 @@ impl<'a> Parser<'a> {
 // sml-specific types //
 
 /// Information about an infix name.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Infix {
   /// The precedence.
   pub prec: u16,
@@ -1,14 +1,16 @@
 //! Bases. (The plural of "basis".)
 
+use crate::def_map::DefMap;
 use crate::types::{
   BasicOverload, Bs, CompositeOverload, Def, Env, EnvLike as _, EnvStack, FunEnv, IdStatus,
   Overload, RecordTy, SigEnv, StrEnv, Sym, Syms, Ty, TyEnv, TyInfo, TyScheme, TyVarKind, ValEnv,
   ValInfo,
 };
 use fast_hash::map;
+use serde::{Deserialize, Serialize};
 
 /// A basis.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Basis {
   pub(crate) inner: Bs,
 }
@@ -19,6 +21,11 @@ impl Basis {
     self.inner.append(other.inner);
   }
 
+  /// Changes the defs in this by the map.
+  pub fn remap_defs(&mut self, map: &DefMap) {
+    map.apply_bs(&mut self.inner);
+  }
+
   /// Adds the item named `other_name` from `other` into `self` with the name `name`, or
   /// returns `false` if this was not possible.
   pub fn add(
@@ -58,7 +65,7 @@ impl Basis {
 }
 
 /// A namespace for an export.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 #[allow(missing_docs)]
 pub enum Namespace {
   Structure,
@@ -2,11 +2,11 @@
 
 use crate::config::Cfg;
 use crate::error::{ErrorKind, Item};
-use crate::get_env::{get_env_from_str_path, get_ty_info, get_val_info};
+use crate::get_env::{get_env_from_str_path, get_ty_info, get_val_info, undefined_val};
 use crate::pat_match::Pat;
 use crate::st::St;
 use crate::types::{
-  generalize, generalize_fixed, Cx, Env, EnvLike as _, FixedTyVars, Generalizable,
+  generalize, generalize_fixed, Cx, Def, Env, EnvLike as _, FixedTyVars, Generalizable,
   HasRecordMetaVars, IdStatus, StartedSym, Ty, TyEnv, TyInfo, TyScheme, TyVarSrc, ValEnv, ValInfo,
 };
 use crate::unify::unify;
@@ -26,6 +26,7 @@ pub(crate) fn get(
     Some(x) => x,
     None => return,
   };
+  st.record_env(dec, &cx.env);
   match &ars.dec[dec] {
     // @def(15)
     sml_hir::Dec::Val(ty_vars, val_binds) => {
@@ -96,6 +97,9 @@ pub(crate) fn get(
         }
         let mv_g = st.meta_gen.generalizer();
         let g = generalize(mv_g, &st.subst, fixed.clone(), &mut val_info.ty_scheme);
+        if let Some(Def::Path(_, idx)) = val_info.def {
+          st.info.insert_bind_ty_scheme(idx, val_info.ty_scheme.clone());
+        }
         if expansive(&cx, ars, exp) && !val_info.ty_scheme.bound_vars.is_empty() {
           st.err(
             exp.map_or(sml_hir::Idx::Dec(dec), sml_hir::Idx::Exp),
@@ -168,7 +172,7 @@ pub(crate) fn get(
               }
               _ => st.err(dec, ErrorKind::ExnCopyNotExnIdStatus(path.clone())),
             },
-            Ok(None) => st.err(dec, ErrorKind::Undefined(Item::Val, path.last().clone())),
+            Ok(None) => st.err(dec, undefined_val(&cx.env, path)),
             Err(e) => st.err(dec, e),
           },
         }
@@ -0,0 +1,195 @@
+//! Mapping the definition sites in some items to those in other items that are otherwise equal.
+
+use crate::basis::Basis;
+use crate::types::{Bs, Def, Env, EnvStack, FunSig, Sig, SymsAdded, TyInfo, ValInfo};
+use fast_hash::FxHashMap;
+use std::collections::hash_map::Entry;
+use std::sync::Arc;
+
+/// A mapping from old definition sites to new ones.
+///
+/// An edit to a file may change where the items it defines are defined, without changing the items
+/// otherwise. Then the results of checking the files that use those items may be reused, with their
+/// definition sites changed by this.
+#[derive(Debug, Default)]
+pub struct DefMap(FxHashMap<Def, Def>);
+
+impl DefMap {
+  /// Returns the map from the defs in `old` to those in `new`, or `None` if they differ in more than
+  /// their defs.
+  #[must_use]
+  pub fn between_bases(old: &Basis, new: &Basis) -> Option<Self> {
+    let mut ret = Self::default();
+    ret.bs(&old.inner, &new.inner)?;
+    Some(ret)
+  }
+
+  /// Like [`Self::between_bases`], but for the items added to some syms.
+  #[must_use]
+  pub fn between_syms_added(old: &SymsAdded, new: &SymsAdded) -> Option<Self> {
+    let mut ret = Self::default();
+    if old.store.len() != new.store.len() || old.exns != new.exns {
+      return None;
+    }
+    for ((old_path, old), (new_path, new)) in old.store.iter().zip(new.store.iter()) {
+      if old_path != new_path {
+        return None;
+      }
+      ret.ty_info(old, new)?;
+    }
+    Some(ret)
+  }
+
+  /// Returns whether this maps no defs.
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  fn bs(&mut self, old: &Bs, new: &Bs) -> Option<()> {
+    self.env_stack(&old.env, &new.env)?;
+    if !Arc::ptr_eq(&old.sig_env, &new.sig_env) {
+      self.all(&old.sig_env, &new.sig_env, Self::sig)?;
+    }
+    if !Arc::ptr_eq(&old.fun_env, &new.fun_env) {
+      self.all(&old.fun_env, &new.fun_env, Self::fun_sig)?;
+    }
+    Some(())
+  }
+
+  fn env_stack(&mut self, old: &EnvStack, new: &EnvStack) -> Option<()> {
+    let mut old = old.iter();
+    let mut new = new.iter();
+    loop {
+      match (old.next(), new.next()) {
+        (None, None) => return Some(()),
+        (Some(old), Some(new)) => self.env(old, new)?,
+        (None, Some(_)) | (Some(_), None) => return None,
+      }
+    }
+  }
+
+  fn sig(&mut self, old: &Sig, new: &Sig) -> Option<()> {
+    if old.ty_names != new.ty_names {
+      return None;
+    }
+    self.env(&old.env, &new.env)
+  }
+
+  fn fun_sig(&mut self, old: &FunSig, new: &FunSig) -> Option<()> {
+    if old.body_ty_names != new.body_ty_names || old.flavor != new.flavor {
+      return None;
+    }
+    self.sig(&old.param, &new.param)?;
+    self.env(&old.body_env, &new.body_env)
+  }
+
+  fn env(&mut self, old: &Env, new: &Env) -> Option<()> {
+    // envs are often shared, and then they are certainly the same.
+    if std::ptr::eq(old, new) {
+      return Some(());
+    }
+    self.all(&old.str_env, &new.str_env, Self::env)?;
+    self.all(&old.ty_env, &new.ty_env, Self::ty_info)?;
+    self.all(&old.val_env, &new.val_env, Self::val_info)?;
+    self.def(old.def, new.def)
+  }
+
+  fn ty_info(&mut self, old: &TyInfo, new: &TyInfo) -> Option<()> {
+    if old.ty_scheme != new.ty_scheme {
+      return None;
+    }
+    self.all(&old.val_env, &new.val_env, Self::val_info)?;
+    self.def(old.def, new.def)
+  }
+
+  fn val_info(&mut self, old: &ValInfo, new: &ValInfo) -> Option<()> {
+    if old.ty_scheme != new.ty_scheme || old.id_status != new.id_status {
+      return None;
+    }
+    self.def(old.def, new.def)
+  }
+
+  /// `old` and `new` must have the same names, mapping to things that differ only in their defs.
+  fn all<T, F>(
+    &mut self,
+    old: &FxHashMap<str_util::Name, T>,
+    new: &FxHashMap<str_util::Name, T>,
+    mut f: F,
+  ) -> Option<()>
+  where
+    F: FnMut(&mut Self, &T, &T) -> Option<()>,
+  {
+    if old.len() != new.len() {
+      return None;
+    }
+    for (name, old) in old {
+      f(self, old, new.get(name)?)?;
+    }
+    Some(())
+  }
+
+  fn def(&mut self, old: Option<Def>, new: Option<Def>) -> Option<()> {
+    match (old, new) {
+      (None, None) => Some(()),
+      (Some(old), Some(new)) => {
+        if old == new {
+          return Some(());
+        }
+        match self.0.entry(old) {
+          Entry::Vacant(entry) => {
+            entry.insert(new);
+            Some(())
+          }
+          Entry::Occupied(entry) => (*entry.get() == new).then_some(()),
+        }
+      }
+      (None, Some(_)) | (Some(_), None) => None,
+    }
+  }
+
+  pub(crate) fn apply(&self, def: &mut Def) {
+    if let Some(&new) = self.0.get(def) {
+      *def = new;
+    }
+  }
+
+  pub(crate) fn apply_opt(&self, def: &mut Option<Def>) {
+    if let Some(def) = def {
+      self.apply(def);
+    }
+  }
+
+  pub(crate) fn apply_bs(&self, bs: &mut Bs) {
+    for env in bs.env.iter_mut() {
+      self.apply_env(env);
+    }
+    for sig in bs.as_mut_sig_env().values_mut() {
+      self.apply_env(&mut sig.env);
+    }
+    for fun_sig in bs.as_mut_fun_env().values_mut() {
+      self.apply_env(&mut fun_sig.param.env);
+      self.apply_env(&mut fun_sig.body_env);
+    }
+  }
+
+  fn apply_env(&self, env: &mut Env) {
+    for env in env.str_env.values_mut() {
+      self.apply_env(env);
+    }
+    for ty_info in env.ty_env.values_mut() {
+      self.apply_ty_info(ty_info);
+    }
+    for val_info in env.val_env.values_mut() {
+      self.apply_opt(&mut val_info.def);
+    }
+    self.apply_opt(&mut env.def);
+  }
+
+  pub(crate) fn apply_ty_info(&self, ty_info: &mut TyInfo) {
+    for val_info in ty_info.val_env.values_mut() {
+      self.apply_opt(&mut val_info.def);
+    }
+    self.apply_opt(&mut ty_info.def);
+  }
+}
@@ -16,9 +16,11 @@ use std::fmt;
 pub(crate) enum ErrorKind {
   /// must be first here, but have the highest error code
   Unsupported(&'static str),
-  Undefined(Item, str_util::Name),
+  /// the last is a similar name in scope, if there is one.
+  Undefined(Item, str_util::Name, Option<str_util::Name>),
   Duplicate(Item, str_util::Name),
-  Missing(Item, str_util::Name),
+  /// the last is a stub declaration for the missing item, if we could make one.
+  Missing(Item, str_util::Name, Option<String>),
   Extra(Item, str_util::Name),
   Circularity(MetaTyVar, Ty),
   MismatchedTypes(MismatchedTypesFlavor, Ty, Ty),
@@ -55,6 +57,17 @@ pub(crate) enum ErrorKind {
   AppFn,
 }
 
+impl ErrorKind {
+  /// returns an error for the undefined name, suggesting the most similar of the candidates.
+  pub(crate) fn undefined<'a, I>(item: Item, name: &str_util::Name, candidates: I) -> Self
+  where
+    I: IntoIterator<Item = &'a str_util::Name>,
+  {
+    let similar = suggestion::similar(name.as_str(), candidates).cloned();
+    Self::Undefined(item, name.clone(), similar)
+  }
+}
+
 struct ErrorKindDisplay<'a> {
   kind: &'a ErrorKind,
   syms: &'a Syms,
@@ -66,15 +79,19 @@ impl fmt::Display for ErrorKindDisplay<'_> {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     match self.kind {
       ErrorKind::Unsupported(s) => write!(f, "unsupported: {s}"),
-      ErrorKind::Undefined(item, name) => {
+      ErrorKind::Undefined(item, name, similar) => {
         write!(f, "undefined {item}: {name}")?;
-        if let Some(sug) = suggestion::get(name.as_str()) {
+        let sug =
+          suggestion::get(name.as_str()).or_else(|| similar.as_ref().map(str_util::Name::as_str));
+        if let Some(sug) = sug {
           write!(f, " (did you mean `{sug}`?)")?;
         }
         Ok(())
       }
       ErrorKind::Duplicate(item, name) => write!(f, "duplicate {item}: {name}"),
-      ErrorKind::Missing(item, name) => write!(f, "missing {item} required by signature: {name}"),
+      ErrorKind::Missing(item, name, _) => {
+        write!(f, "missing {item} required by signature: {name}")
+      }
       ErrorKind::Extra(item, name) => write!(f, "extra {item} not present in signature: {name}"),
       ErrorKind::Circularity(mv, ty) => {
         let mut mvs = MetaVarNames::new(self.mv_info);
@@ -343,6 +360,47 @@ impl Error {
     self.idx
   }
 
+  /// If this is an error about an item missing from a structure, returns a declaration stubbing
+  /// out the item, if there is one.
+  #[must_use]
+  pub fn stub(&self) -> Option<&str> {
+    match &self.kind {
+      ErrorKind::Missing(_, _, stub) => stub.as_deref(),
+      _ => None,
+    }
+  }
+
+  /// If this is an error about an undefined name, and there is a similar name in scope, returns the
+  /// undefined name and the similar name.
+  #[must_use]
+  pub fn similar(&self) -> Option<(&str, &str)> {
+    match &self.kind {
+      ErrorKind::Undefined(_, name, Some(similar)) => Some((name.as_str(), similar.as_str())),
+      _ => None,
+    }
+  }
+
+  /// If this is an error about an unused value, returns its name.
+  #[must_use]
+  pub fn unused(&self) -> Option<&str> {
+    match &self.kind {
+      ErrorKind::Unused(name) => Some(name.as_str()),
+      _ => None,
+    }
+  }
+
+  /// If this is an error about a non-exhaustive case, returns the missing patterns as SML source.
+  ///
+  /// If `curried` is `Some(n)`, the case is for the `n` curried parameters of a `fun`, and each
+  /// pattern is written as `n` atomic patterns separated by spaces.
+  #[must_use]
+  pub fn missing_pats(&self, syms: &Syms, curried: Option<usize>) -> Option<Vec<String>> {
+    match &self.kind {
+      ErrorKind::NonExhaustiveCase(pats) => Some(non_exhaustive::source(syms, pats, curried)),
+      _ => None,
+    }
+  }
+
   /// Returns a value that displays the message.
   #[must_use]
   pub fn display<'a>(
@@ -359,9 +417,9 @@ impl Error {
   pub fn code(&self) -> Code {
     match self.kind {
       ErrorKind::Unsupported(_) => Code::n(5999),
-      ErrorKind::Undefined(_, _) => Code::n(5001),
+      ErrorKind::Undefined(_, _, _) => Code::n(5001),
       ErrorKind::Duplicate(_, _) => Code::n(5002),
-      ErrorKind::Missing(_, _) => Code::n(5003),
+      ErrorKind::Missing(_, _, _) => Code::n(5003),
       ErrorKind::Extra(_, _) => Code::n(5004),
       ErrorKind::Circularity(_, _) => Code::n(5005),
       ErrorKind::MismatchedTypes(_, _, _) => Code::n(5006),
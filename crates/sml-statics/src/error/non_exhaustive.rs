@@ -25,6 +25,26 @@ pub(crate) fn get(
   Ok(())
 }
 
+/// Returns the pats as SML source. If `curried` is `Some(n)`, each pat is for the `n` curried
+/// parameters of a `fun`, and is written as `n` atomic pats separated by spaces.
+pub(crate) fn source(syms: &Syms, pats: &[Pat], curried: Option<usize>) -> Vec<String> {
+  pats
+    .iter()
+    .map(|pat| match (curried, &pat.raw) {
+      (None, _) => PatDisplay { pat, syms, prec: PatPrec::Min }.to_string(),
+      (Some(n), RawPat::Con(Con::Any, _)) => vec!["_"; n].join(" "),
+      (Some(n), RawPat::Con(Con::Record { allows_other: false, .. }, args))
+        if n > 1 && args.len() == n =>
+      {
+        let args: Vec<_> =
+          args.iter().map(|pat| PatDisplay { pat, syms, prec: PatPrec::App }.to_string()).collect();
+        args.join(" ")
+      }
+      (Some(_), _) => PatDisplay { pat, syms, prec: PatPrec::App }.to_string(),
+    })
+    .collect()
+}
+
 struct PatDisplay<'a> {
   pat: &'a Pat,
   syms: &'a Syms,
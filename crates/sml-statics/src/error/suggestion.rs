@@ -1,4 +1,4 @@
-//! See [`get`].
+//! Suggestions for undefined names.
 
 /// Suggests a bit of syntax that may be similar to the input.
 pub(crate) fn get(s: &str) -> Option<&'static str> {
@@ -36,3 +36,49 @@ pub(crate) fn get(s: &str) -> Option<&'static str> {
   };
   Some(ret)
 }
+
+/// Returns the name among the candidates most similar to the input, if any is similar enough.
+///
+/// A candidate equal to the input ignoring case is most similar. Otherwise, we use the edit distance
+/// counting transpositions, and allow about one edit for every three characters.
+pub(crate) fn similar<'a, I>(s: &str, candidates: I) -> Option<&'a str_util::Name>
+where
+  I: IntoIterator<Item = &'a str_util::Name>,
+{
+  let max = s.chars().count() / 3;
+  candidates
+    .into_iter()
+    .filter_map(|name| {
+      let dist =
+        if name.as_str().eq_ignore_ascii_case(s) { 0 } else { edit_distance(s, name.as_str()) };
+      (dist <= max).then_some((dist, name))
+    })
+    .min_by(|(d1, n1), (d2, n2)| d1.cmp(d2).then_with(|| n1.as_str().cmp(n2.as_str())))
+    .map(|(_, name)| name)
+}
+
+/// the optimal string alignment distance, i.e. the Levenshtein distance but also allowing swapping
+/// adjacent characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+  let a: Vec<_> = a.chars().collect();
+  let b: Vec<_> = b.chars().collect();
+  // `dp[i][j]` is the distance between the first `i` chars of `a` and the first `j` chars of `b`.
+  let mut dp = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+  for (i, row) in dp.iter_mut().enumerate() {
+    row[0] = i;
+  }
+  for (j, cell) in dp[0].iter_mut().enumerate() {
+    *cell = j;
+  }
+  for i in 1..=a.len() {
+    for j in 1..=b.len() {
+      let cost = usize::from(a[i - 1] != b[j - 1]);
+      let mut d = (dp[i - 1][j] + 1).min(dp[i][j - 1] + 1).min(dp[i - 1][j - 1] + cost);
+      if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+        d = d.min(dp[i - 2][j - 2] + 1);
+      }
+      dp[i][j] = d;
+    }
+  }
+  dp[a.len()][b.len()]
+}
@@ -1,13 +1,14 @@
 //! Checking expressions.
 
 use crate::config::Cfg;
-use crate::error::{AppendArg, ErrorKind, Item};
-use crate::get_env::get_val_info;
+use crate::error::{AppendArg, ErrorKind};
+use crate::get_env::{get_val_info, undefined_val};
 use crate::info::TyEntry;
 use crate::pat_match::Pat;
 use crate::st::St;
 use crate::types::{
-  Cx, Def, DefPath, Env, EnvLike as _, Generalizable, Sym, SymsMarker, Ty, TyScheme, ValEnv,
+  Cx, Def, DefPath, Env, EnvLike as _, Generalizable, IdStatus, Sym, SymsMarker, Ty, TyScheme,
+  ValEnv,
 };
 use crate::unify::unify;
 use crate::util::{apply, get_scon, instantiate, record};
@@ -36,8 +37,11 @@ fn get(st: &mut St, cfg: Cfg, cx: &Cx, ars: &sml_hir::Arenas, exp: sml_hir::ExpI
   // NOTE: do not early return, since we add to the Info at the bottom.
   let mut ty_scheme = None::<TyScheme>;
   let mut definition = None::<Def>;
+  let mut id_status = None::<IdStatus>;
   let ret = match &ars.exp[exp] {
     sml_hir::Exp::Hole => {
+      // for the fills of the hole.
+      st.record_env(exp, &cx.env);
       let mv = st.meta_gen.gen(Generalizable::Always);
       st.insert_hole(mv, exp.into());
       Ty::MetaVar(mv)
@@ -49,13 +53,14 @@ fn get(st: &mut St, cfg: Cfg, cx: &Cx, ars: &sml_hir::Arenas, exp: sml_hir::ExpI
       Ok(Some(val_info)) => {
         ty_scheme = Some(val_info.ty_scheme.clone());
         definition = val_info.def;
+        id_status = Some(val_info.id_status);
         if let Some(Def::Path(_, idx)) = val_info.def {
           st.mark_used(idx);
         }
         instantiate(st, Generalizable::Always, val_info.ty_scheme.clone())
       }
       Ok(None) => {
-        st.err(exp, ErrorKind::Undefined(Item::Val, path.last().clone()));
+        st.err(exp, undefined_val(&cx.env, path));
         Ty::None
       }
       Err(e) => {
@@ -74,6 +79,9 @@ fn get(st: &mut St, cfg: Cfg, cx: &Cx, ars: &sml_hir::Arenas, exp: sml_hir::ExpI
       dec::get(st, cfg, cx, ars, &mut let_env, *dec);
       let mut cx = cx.clone();
       cx.env.append(&mut let_env);
+      if let Some(inner) = *inner {
+        st.record_env(inner, &cx.env);
+      }
       get(st, cfg, &cx, ars, *inner)
     }
     // @def(8)
@@ -153,6 +161,9 @@ fn get(st: &mut St, cfg: Cfg, cx: &Cx, ars: &sml_hir::Arenas, exp: sml_hir::ExpI
   };
   let ty_entry = TyEntry { ty: ret.clone(), ty_scheme };
   st.info.insert(exp.into(), Some(ty_entry), definition);
+  if let Some(id_status) = id_status {
+    st.info.insert_val_kind(exp.into(), id_status);
+  }
   ret
 }
 
@@ -268,6 +279,9 @@ fn get_matcher(
     let (pm_pat, pat_ty) = pat::get(st, cfg, ars, cx, &mut ve, pat);
     let mut cx = cx.clone();
     cx.env.push(Env { val_env: ve, ..Default::default() });
+    if let Some(exp) = exp {
+      st.record_env(exp, &cx.env);
+    }
     let exp_ty = get(st, cfg.cfg, &cx, ars, exp);
     let pi = pat.map_or(idx, Into::into);
     unify(st, param_ty.clone(), pat_ty, pi);
@@ -18,18 +18,31 @@ where
     Some(x) => x,
   };
   let mut env = match env.get_str(name) {
-    None => return Err(ErrorKind::Undefined(Item::Struct, name.clone())),
+    None => {
+      let names = env.all_envs().into_iter().flat_map(|x| x.str_env.keys());
+      return Err(ErrorKind::undefined(Item::Struct, name, names));
+    }
     Some(x) => x,
   };
   for name in names {
     match env.str_env.get(name) {
-      None => return Err(ErrorKind::Undefined(Item::Struct, name.clone())),
+      None => return Err(ErrorKind::undefined(Item::Struct, name, env.str_env.keys())),
       Some(x) => env = x,
     }
   }
   Ok(Some(env))
 }
 
+/// returns the env reached by traversing the `names`, or `None` if any name was unbound or there
+/// were no names.
+pub(crate) fn get_env_raw<'e, 'n, I, E>(env: &'e E, names: I) -> Option<&'e Env>
+where
+  I: IntoIterator<Item = &'n str_util::Name>,
+  E: EnvLike,
+{
+  get_env(env, names).ok().flatten()
+}
+
 pub(crate) fn get_env_from_str_path<'e, E>(
   env: &'e E,
   path: &sml_hir::Path,
@@ -63,7 +76,10 @@ where
     Some(got_env) => got_env.ty_env.get(last),
     None => env.get_ty(last),
   };
-  ty_info.ok_or_else(|| ErrorKind::Undefined(Item::Ty, last.clone()))
+  ty_info.ok_or_else(|| {
+    let envs = got_env.map_or_else(|| env.all_envs(), |x| vec![x]);
+    ErrorKind::undefined(Item::Ty, last, envs.into_iter().flat_map(|x| x.ty_env.keys()))
+  })
 }
 
 pub(crate) fn get_val_info<'e, E>(
@@ -78,3 +94,16 @@ where
     None => Ok(env.get_val(path.last())),
   }
 }
+
+/// returns an error for the value at the end of the `path` being undefined in the `env` reached by
+/// the prefix of the `path`.
+pub(crate) fn undefined_val<E>(env: &E, path: &sml_hir::Path) -> ErrorKind
+where
+  E: EnvLike,
+{
+  let envs = match get_env(env, path.prefix()) {
+    Ok(Some(x)) => vec![x],
+    Ok(None) | Err(_) => env.all_envs(),
+  };
+  ErrorKind::undefined(Item::Val, path.last(), envs.into_iter().flat_map(|x| x.val_env.keys()))
+}
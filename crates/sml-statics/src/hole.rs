@@ -0,0 +1,151 @@
+//! Suggestions for what to fill an expression hole with.
+
+use crate::types::{
+  BoundTyVars, Env, Generalizable, MetaTyVarGen, Subst, SubstEntry, Syms, Ty, TyScheme,
+};
+use crate::unify::unify_on;
+use crate::util::{apply, apply_bv};
+use fast_hash::FxHashSet;
+
+/// A value that may fill a hole.
+#[derive(Debug)]
+pub struct HoleFill {
+  /// The name, qualified with the structures it is in, if any.
+  pub name: String,
+  /// How many curried arguments to apply the value to so that it has the type of the hole.
+  pub args: usize,
+}
+
+/// The state after checking, with which to unify the type of the hole with the candidates.
+#[derive(Clone, Copy)]
+pub(crate) struct Cx<'a> {
+  pub(crate) syms: &'a Syms,
+  pub(crate) subst: &'a Subst,
+  pub(crate) meta_gen: &'a MetaTyVarGen,
+}
+
+/// Returns the values in the envs, which should be innermost first, that can be instantiated to
+/// have the wanted type, possibly after applying them to some arguments. The most specific are
+/// first: those that need the fewest arguments, then those with the fewest type variables.
+pub(crate) fn get<'a, I>(cx: Cx<'_>, want: &Ty, envs: I) -> Vec<HoleFill>
+where
+  I: Iterator<Item = &'a Env>,
+{
+  let mut want = want.clone();
+  apply(cx.subst, &mut want);
+  // anything could fill a hole with an unconstrained type, so don't suggest everything in scope.
+  match &want {
+    Ty::None => return Vec::new(),
+    Ty::MetaVar(mv) if cx.subst.get(*mv).is_none() => return Vec::new(),
+    _ => {}
+  }
+  let mut search = Search { cx, want, seen: FxHashSet::default(), ac: Vec::new() };
+  let mut prefix = Vec::<&str>::new();
+  for env in envs {
+    search.env(&mut prefix, env);
+  }
+  search.ac.sort_unstable_by(|a, b| {
+    (a.fill.args, a.bound_vars, &a.fill.name).cmp(&(b.fill.args, b.bound_vars, &b.fill.name))
+  });
+  search.ac.into_iter().map(|x| x.fill).collect()
+}
+
+struct Candidate {
+  fill: HoleFill,
+  bound_vars: usize,
+}
+
+struct Search<'a> {
+  cx: Cx<'a>,
+  want: Ty,
+  /// the names already seen, to skip shadowed ones.
+  seen: FxHashSet<String>,
+  ac: Vec<Candidate>,
+}
+
+impl Search<'_> {
+  fn env<'e>(&mut self, prefix: &mut Vec<&'e str>, env: &'e Env) {
+    for (name, val_info) in &env.val_env {
+      let name = if prefix.is_empty() {
+        name.as_str().to_owned()
+      } else {
+        format!("{}.{}", prefix.join("."), name.as_str())
+      };
+      if !self.seen.insert(name.clone()) {
+        continue;
+      }
+      if let Some(args) = self.args(&val_info.ty_scheme) {
+        let bound_vars = val_info.ty_scheme.bound_vars.len();
+        self.ac.push(Candidate { fill: HoleFill { name, args }, bound_vars });
+      }
+    }
+    for (name, env) in &env.str_env {
+      prefix.push(name.as_str());
+      self.env(prefix, env);
+      prefix.pop();
+    }
+  }
+
+  /// returns the fewest curried args to apply a value of the ty scheme to so that it unifies with
+  /// `want`.
+  fn args(&self, ty_scheme: &TyScheme) -> Option<usize> {
+    let mut ty = ty_scheme.ty.clone();
+    apply(self.cx.subst, &mut ty);
+    let mut ret = 0usize;
+    loop {
+      // only bother unifying, which needs a throwaway copy of the subst, if it might work.
+      if may_unify(&self.want, &ty) && self.unifies(&ty_scheme.bound_vars, &ty) {
+        return Some(ret);
+      }
+      match ty {
+        Ty::Fn(_, res) => {
+          ty = *res;
+          ret += 1;
+        }
+        _ => return None,
+      }
+    }
+  }
+
+  /// returns whether the ty, with the bound vars instantiated, unifies with `want`.
+  fn unifies(&self, bound_vars: &BoundTyVars, ty: &Ty) -> bool {
+    let mut subst = self.cx.subst.clone();
+    let mut meta_gen = self.cx.meta_gen.clone();
+    let bvs: Vec<_> = bound_vars
+      .kinds()
+      .map(|kind| {
+        let mv = meta_gen.gen(Generalizable::Always);
+        if let Some(kind) = kind {
+          subst.insert(mv, SubstEntry::Kind(kind.clone()));
+        }
+        Ty::MetaVar(mv)
+      })
+      .collect();
+    let mut ty = ty.clone();
+    apply_bv(&bvs, &mut ty);
+    unify_on(&mut subst, &mut meta_gen, self.cx.syms, self.want.clone(), ty)
+  }
+}
+
+/// returns false only if the tys certainly don't unify. quick, and doesn't look at the subst.
+fn may_unify(lhs: &Ty, rhs: &Ty) -> bool {
+  match (lhs, rhs) {
+    (Ty::None | Ty::BoundVar(_) | Ty::MetaVar(_), _)
+    | (_, Ty::None | Ty::BoundVar(_) | Ty::MetaVar(_)) => true,
+    (Ty::FixedVar(lhs), Ty::FixedVar(rhs)) => lhs == rhs,
+    (Ty::Record(lhs), Ty::Record(rhs)) => {
+      lhs.len() == rhs.len()
+        && lhs
+          .iter()
+          .zip(rhs)
+          .all(|((l_lab, l_ty), (r_lab, r_ty))| l_lab == r_lab && may_unify(l_ty, r_ty))
+    }
+    (Ty::Con(l_args, l_sym), Ty::Con(r_args, r_sym)) => {
+      l_sym == r_sym && l_args.iter().zip(r_args).all(|(l, r)| may_unify(l, r))
+    }
+    (Ty::Fn(l_param, l_res), Ty::Fn(r_param, r_res)) => {
+      may_unify(l_param, r_param) && may_unify(l_res, r_res)
+    }
+    (Ty::FixedVar(_) | Ty::Record(_) | Ty::Con(_, _) | Ty::Fn(_, _), _) => false,
+  }
+}
@@ -1,34 +1,117 @@
 //! See [`Info`].
 
-use crate::types::{Def, MetaVarInfo, MetaVarNames, Syms, Ty, TyScheme};
-use crate::util::ty_syms;
-use fast_hash::FxHashMap;
+use crate::def_map::DefMap;
+use crate::get_env::get_env_raw;
+use crate::hole::{self, HoleFill};
+use crate::serde_cx::de_builtin_lib_name;
+use crate::types::{
+  Bs, Def, Env, EnvStack, IdStatus, MetaTyVarGen, MetaVarInfo, MetaVarNames, Subst, Syms, Ty,
+  TyScheme,
+};
+use crate::util::{apply, ty_syms};
+use fast_hash::{FxHashMap, FxHashSet};
+use serde::{Deserialize, Serialize};
 use std::fmt::Write as _;
 
 /// Information about HIR indices.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Info {
   mode: Mode,
   store: FxHashMap<sml_hir::Idx, InfoEntry>,
+  /// pairs of (signature spec, implementation) definition sites, from signature matching.
+  impls: Vec<(Def, Def)>,
+  /// the most general types of `val` bindings, once generalized. only for code lenses, since hover
+  /// shows the type at the binding instead.
+  bind_ty_schemes: FxHashMap<sml_hir::Idx, TyScheme>,
+  /// the environments in scope at some indices, for completions. only recorded in regular mode.
+  scopes: FxHashMap<sml_hir::Idx, Scope>,
   pub(crate) meta_vars: MetaVarInfo,
+  /// for solving the meta vars in the types in `scopes`.
+  pub(crate) subst: Subst,
+  /// for making meta vars distinct from those in `subst`.
+  pub(crate) meta_gen: MetaTyVarGen,
 }
 
-#[derive(Debug, Clone)]
+/// The environment in scope at an index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) enum Scope {
+  /// Only the structures, types, and values.
+  Env(EnvStack),
+  /// Also the signatures and functors.
+  Bs(Bs),
+}
+
+/// An item that may complete a name.
+#[derive(Debug)]
+pub struct CompletionItem {
+  /// The name.
+  pub label: String,
+  /// The kind.
+  pub kind: CompletionKind,
+  /// Details about the item, like its type.
+  pub detail: Option<String>,
+}
+
+/// A kind of completion item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+  /// A value that is not a function, or that we don't know is a function.
+  Value,
+  /// A value that is a function.
+  Function,
+  /// A datatype constructor.
+  Constructor,
+  /// An exception.
+  Exception,
+  /// A type.
+  Type,
+  /// A structure.
+  Structure,
+  /// A signature.
+  Signature,
+  /// A functor.
+  Functor,
+  /// A record label.
+  Label,
+}
+
+/// What kind of value a value identifier refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ValKind {
+  /// A regular value, like a variable or function.
+  Val,
+  /// A datatype constructor.
+  Con,
+  /// An exception constructor.
+  Exn,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct TyEntry {
   pub(crate) ty: Ty,
   pub(crate) ty_scheme: Option<TyScheme>,
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 struct InfoEntry {
   ty_entry: Option<TyEntry>,
   def: Option<Def>,
   doc: Option<String>,
+  val_kind: Option<ValKind>,
 }
 
 impl Info {
   pub(crate) fn new(mode: Mode) -> Self {
-    Self { mode, store: FxHashMap::default(), meta_vars: MetaVarInfo::default() }
+    Self {
+      mode,
+      store: FxHashMap::default(),
+      impls: Vec::new(),
+      bind_ty_schemes: FxHashMap::default(),
+      scopes: FxHashMap::default(),
+      meta_vars: MetaVarInfo::default(),
+      subst: Subst::default(),
+      meta_gen: MetaTyVarGen::default(),
+    }
   }
 
   pub(crate) fn insert(&mut self, idx: sml_hir::Idx, ty_entry: Option<TyEntry>, def: Option<Def>) {
@@ -41,6 +124,7 @@ impl Info {
       }),
       def,
       doc: None,
+      val_kind: None,
     };
     assert!(self.store.insert(idx, entry).is_none());
   }
@@ -50,6 +134,32 @@ impl Info {
     self.store.entry(idx).or_default().doc.replace(doc)
   }
 
+  /// sets the most general type of the binding at the idx, once it has been generalized.
+  pub(crate) fn insert_bind_ty_scheme(&mut self, idx: sml_hir::Idx, ty_scheme: TyScheme) {
+    if !ty_scheme.bound_vars.is_empty() {
+      self.bind_ty_schemes.insert(idx, ty_scheme);
+    }
+  }
+
+  pub(crate) fn insert_val_kind(&mut self, idx: sml_hir::Idx, id_status: IdStatus) {
+    let val_kind = match id_status {
+      IdStatus::Val => ValKind::Val,
+      IdStatus::Con => ValKind::Con,
+      IdStatus::Exn(_) => ValKind::Exn,
+    };
+    self.store.entry(idx).or_default().val_kind = Some(val_kind);
+  }
+
+  pub(crate) fn add_impl(&mut self, spec: Def, def: Def) {
+    if spec != def {
+      self.impls.push((spec, def));
+    }
+  }
+
+  pub(crate) fn insert_scope(&mut self, idx: sml_hir::Idx, scope: Scope) {
+    self.scopes.insert(idx, scope);
+  }
+
   pub(crate) fn tys_mut(&mut self) -> impl Iterator<Item = &mut Ty> {
     self.store.values_mut().filter_map(|entry| entry.ty_entry.as_mut().map(|x| &mut x.ty))
   }
@@ -90,6 +200,50 @@ impl Info {
     Some(())
   }
 
+  /// Returns the type of this index on one line, without Markdown.
+  #[must_use]
+  pub fn get_ty(&self, syms: &Syms, idx: sml_hir::Idx) -> Option<String> {
+    let ty_entry = self.store.get(&idx)?.ty_entry.as_ref()?;
+    let mut mvs = MetaVarNames::new(&self.meta_vars);
+    mvs.extend_for(&ty_entry.ty);
+    Some(ty_entry.ty.display(&mvs, syms).to_string())
+  }
+
+  /// Like [`Self::get_ty`], but uses the most general type if this index is a generalized `val`
+  /// binding.
+  #[must_use]
+  pub fn get_bind_ty(&self, syms: &Syms, idx: sml_hir::Idx) -> Option<String> {
+    let ty_scheme = match self.bind_ty_schemes.get(&idx) {
+      Some(x) => x,
+      None => return self.get_ty(syms, idx),
+    };
+    let mut mvs = MetaVarNames::new(&self.meta_vars);
+    mvs.extend_for(&ty_scheme.ty);
+    Some(ty_scheme.display(&mvs, syms).to_string())
+  }
+
+  /// Writes the type of this index, which is usually a function, to `buf`. Returns the ranges in
+  /// `buf` of each of the curried parameters, with a range for each component of a tuple
+  /// parameter.
+  ///
+  /// Uses the most general type if there is one, since that's usually what people think of as the
+  /// "signature" of a function.
+  pub fn get_fn_params(
+    &self,
+    syms: &Syms,
+    idx: sml_hir::Idx,
+    buf: &mut String,
+  ) -> Option<Vec<Vec<std::ops::Range<usize>>>> {
+    let ty_entry = self.store.get(&idx)?.ty_entry.as_ref()?;
+    let ty_scheme = match &ty_entry.ty_scheme {
+      Some(x) => x.clone(),
+      None => TyScheme::zero(ty_entry.ty.clone()),
+    };
+    let mut mvs = MetaVarNames::new(&self.meta_vars);
+    mvs.extend_for(&ty_scheme.ty);
+    Some(ty_scheme.write_params(&mvs, syms, buf))
+  }
+
   /// Returns documentation for this index.
   #[must_use]
   pub fn get_doc(&self, idx: sml_hir::Idx) -> Option<&str> {
@@ -102,6 +256,204 @@ impl Info {
     self.store.get(&idx)?.def
   }
 
+  /// Returns what kind of value the value identifier at the idx refers to.
+  #[must_use]
+  pub fn get_val_kind(&self, idx: sml_hir::Idx) -> Option<ValKind> {
+    self.store.get(&idx)?.val_kind
+  }
+
+  /// Returns an iterator over all the indices that have a definition site, with that site.
+  pub fn defs(&self) -> impl Iterator<Item = (sml_hir::Idx, Def)> + '_ {
+    self.store.iter().filter_map(|(&idx, entry)| entry.def.map(|def| (idx, def)))
+  }
+
+  /// Returns pairs of (signature spec, implementation) definition sites, discovered when matching
+  /// structures against signatures.
+  #[must_use]
+  pub fn impls(&self) -> &[(Def, Def)] {
+    &self.impls
+  }
+
+  /// Changes the defs in this by the map.
+  ///
+  /// Doesn't change the defs in the scopes, since completions don't use them.
+  pub fn remap_defs(&mut self, map: &DefMap) {
+    for entry in self.store.values_mut() {
+      map.apply_opt(&mut entry.def);
+    }
+    for (spec, def) in &mut self.impls {
+      map.apply(spec);
+      map.apply(def);
+    }
+  }
+
+  /// Returns the items in scope that may complete a name.
+  ///
+  /// `scopes` are the indices enclosing the name, innermost first. `prefix` is the structure path
+  /// before the name, like `Foo.Bar` in `Foo.Bar.x`. If it is non-empty, only the items in that
+  /// structure are returned.
+  #[must_use]
+  pub fn get_completions(
+    &self,
+    syms: &Syms,
+    scopes: &[sml_hir::Idx],
+    prefix: &[str_util::Name],
+  ) -> Vec<CompletionItem> {
+    let mut env = None::<&EnvStack>;
+    let mut bs = None::<&Bs>;
+    for idx in scopes {
+      match self.scopes.get(idx) {
+        None => {}
+        Some(Scope::Env(e)) => {
+          env = env.or(Some(e));
+        }
+        Some(Scope::Bs(b)) => {
+          env = env.or(Some(&b.env));
+          bs = Some(b);
+          break;
+        }
+      }
+    }
+    let mut ret = Vec::<CompletionItem>::new();
+    let env = match env {
+      Some(x) => x,
+      None => return ret,
+    };
+    if !prefix.is_empty() {
+      if let Some(env) = get_env_raw(env, prefix) {
+        self.env_completions(&mut ret, syms, std::iter::once(env));
+      }
+      return ret;
+    }
+    self.env_completions(&mut ret, syms, env.iter());
+    if let Some(bs) = bs {
+      ret.extend(bs.sig_env.keys().map(|name| CompletionItem {
+        label: name.as_str().to_owned(),
+        kind: CompletionKind::Signature,
+        detail: None,
+      }));
+      ret.extend(bs.fun_env.keys().map(|name| CompletionItem {
+        label: name.as_str().to_owned(),
+        kind: CompletionKind::Functor,
+        detail: None,
+      }));
+    }
+    ret
+  }
+
+  /// Returns the values in scope at the idx, which should be an expression hole, that may fill the
+  /// hole, most specific first. Returns none if nothing is known about the type of the hole, since
+  /// then every value could fill it.
+  #[must_use]
+  pub fn get_hole_fills(&self, syms: &Syms, idx: sml_hir::Idx) -> Vec<HoleFill> {
+    let want = match self.store.get(&idx).and_then(|x| x.ty_entry.as_ref()) {
+      Some(x) => &x.ty,
+      None => return Vec::new(),
+    };
+    let cx = hole::Cx { syms, subst: &self.subst, meta_gen: &self.meta_gen };
+    match self.scopes.get(&idx) {
+      Some(Scope::Env(env)) => hole::get(cx, want, env.iter()),
+      Some(Scope::Bs(bs)) => hole::get(cx, want, bs.env.iter()),
+      None => Vec::new(),
+    }
+  }
+
+  /// the envs should be innermost first, so we skip the shadowed names in the outer envs.
+  fn env_completions<'a, I>(&self, ac: &mut Vec<CompletionItem>, syms: &Syms, envs: I)
+  where
+    I: Iterator<Item = &'a Env>,
+  {
+    let mut strs = FxHashSet::<&str_util::Name>::default();
+    let mut tys = FxHashSet::<&str_util::Name>::default();
+    let mut vals = FxHashSet::<&str_util::Name>::default();
+    for env in envs {
+      for name in env.str_env.keys() {
+        if strs.insert(name) {
+          ac.push(CompletionItem {
+            label: name.as_str().to_owned(),
+            kind: CompletionKind::Structure,
+            detail: None,
+          });
+        }
+      }
+      for (name, ty_info) in &env.ty_env {
+        if tys.insert(name) {
+          ac.push(CompletionItem {
+            label: name.as_str().to_owned(),
+            kind: CompletionKind::Type,
+            detail: Some(self.ty_scheme_string(syms, &ty_info.ty_scheme)),
+          });
+        }
+      }
+      for (name, val_info) in &env.val_env {
+        if vals.insert(name) {
+          let kind = match val_info.id_status {
+            IdStatus::Con => CompletionKind::Constructor,
+            IdStatus::Exn(_) => CompletionKind::Exception,
+            IdStatus::Val => match val_info.ty_scheme.ty {
+              Ty::Fn(_, _) => CompletionKind::Function,
+              _ => CompletionKind::Value,
+            },
+          };
+          ac.push(CompletionItem {
+            label: name.as_str().to_owned(),
+            kind,
+            detail: Some(self.ty_scheme_string(syms, &val_info.ty_scheme)),
+          });
+        }
+      }
+    }
+  }
+
+  fn ty_scheme_string(&self, syms: &Syms, ty_scheme: &TyScheme) -> String {
+    let mut ty_scheme = ty_scheme.clone();
+    apply(&self.subst, &mut ty_scheme.ty);
+    let mut mvs = MetaVarNames::new(&self.meta_vars);
+    mvs.extend_for(&ty_scheme.ty);
+    ty_scheme.display(&mvs, syms).to_string()
+  }
+
+  /// Returns the labels of the record type of the idx, with their types.
+  ///
+  /// If `fn_param` is true, the idx should instead have a function type, and we return the labels
+  /// of the record type of its parameter. This is useful for a record expression that is the
+  /// argument of a function application, since the record expression's own type has only the
+  /// labels written so far.
+  #[must_use]
+  pub fn get_record_labels(
+    &self,
+    syms: &Syms,
+    idx: sml_hir::Idx,
+    fn_param: bool,
+  ) -> Option<Vec<CompletionItem>> {
+    let mut ty = &self.store.get(&idx)?.ty_entry.as_ref()?.ty;
+    if fn_param {
+      ty = match ty {
+        Ty::Fn(param, _) => param.as_ref(),
+        _ => return None,
+      };
+    }
+    let rows = match ty {
+      Ty::Record(rows) => rows,
+      _ => return None,
+    };
+    let mut mvs = MetaVarNames::new(&self.meta_vars);
+    mvs.extend_for(ty);
+    let ret = rows
+      .iter()
+      .filter_map(|(lab, ty)| match lab {
+        sml_hir::Lab::Name(name) => Some(CompletionItem {
+          label: name.as_str().to_owned(),
+          kind: CompletionKind::Label,
+          detail: Some(ty.display(&mvs, syms).to_string()),
+        }),
+        // tuples have only numeric labels, and nobody wants to write those.
+        sml_hir::Lab::Num(_) => None,
+      })
+      .collect();
+    Some(ret)
+  }
+
   /// Returns the definition site of the type for the idx.
   #[must_use]
   pub fn get_ty_defs(&self, syms: &Syms, idx: sml_hir::Idx) -> Option<Vec<Def>> {
@@ -144,7 +496,7 @@ impl Info {
 }
 
 /// The mode for checking.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, Serialize)]
 pub enum Mode {
   /// Regular checking. The default.
   Regular(Option<paths::PathId>),
@@ -161,6 +513,28 @@ pub enum Mode {
   PathOrder,
 }
 
+/// like [`Mode`], but owning the name of the built-in library, so it may be deserialized.
+#[derive(Deserialize)]
+enum SerdeMode {
+  Regular(Option<paths::PathId>),
+  BuiltinLib(String),
+  PathOrder,
+}
+
+impl<'de> Deserialize<'de> for Mode {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    let ret = match SerdeMode::deserialize(deserializer)? {
+      SerdeMode::Regular(path) => Self::Regular(path),
+      SerdeMode::BuiltinLib(name) => Self::BuiltinLib(de_builtin_lib_name::<D::Error>(&name)?),
+      SerdeMode::PathOrder => Self::PathOrder,
+    };
+    Ok(ret)
+  }
+}
+
 impl Mode {
   #[must_use]
   pub(crate) fn is_path_order(&self) -> bool {
@@ -10,15 +10,19 @@
 mod compatible;
 mod config;
 mod dec;
+mod def_map;
 mod equality;
 mod error;
 mod exp;
 mod fmt_util;
 mod get_env;
+mod hole;
 mod info;
 mod pat;
 mod pat_match;
+mod serde_cx;
 mod st;
+mod stub;
 mod top_dec;
 mod ty;
 mod types;
@@ -28,10 +32,13 @@ mod util;
 pub mod basis;
 pub mod path_order;
 
+pub use def_map::DefMap;
 pub use equality::ENABLED as EQUALITY_CHECKS_ENABLED;
 pub use error::Error;
-pub use info::{Info, Mode};
-pub use types::{Def, DefPath, MetaVarInfo, Syms};
+pub use hole::HoleFill;
+pub use info::{CompletionItem, CompletionKind, Info, Mode, ValKind};
+pub use serde_cx::with_serde_cx;
+pub use types::{Def, DefPath, MetaVarInfo, Syms, SymsAdded, SymsLens};
 
 /// The result of statics.
 #[derive(Debug)]
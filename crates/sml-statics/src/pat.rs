@@ -3,7 +3,7 @@
 use crate::compatible::eq_ty_scheme;
 use crate::config;
 use crate::error::{ErrorKind, Item};
-use crate::get_env::get_val_info;
+use crate::get_env::{get_val_info, undefined_val};
 use crate::info::{Mode, TyEntry};
 use crate::pat_match::{Con, Pat, VariantName};
 use crate::st::St;
@@ -42,10 +42,14 @@ pub(crate) fn get(
       ty: Ty::MetaVar(st.meta_gen.gen(cfg.gen)),
       ty_scheme: None,
       def: None,
+      id_status: None,
     },
   };
   let ty_entry = TyEntry { ty: ret.ty.clone(), ty_scheme: ret.ty_scheme };
   st.info.insert(pat_.into(), Some(ty_entry), ret.def);
+  if let Some(id_status) = ret.id_status {
+    st.info.insert_val_kind(pat_.into(), id_status);
+  }
   (ret.pm_pat, ret.ty)
 }
 
@@ -54,6 +58,7 @@ struct PatRet {
   ty: Ty,
   ty_scheme: Option<TyScheme>,
   def: Option<Def>,
+  id_status: Option<IdStatus>,
 }
 
 fn get_(
@@ -67,6 +72,7 @@ fn get_(
   let pat = Some(pat_idx);
   let mut ty_scheme = None::<TyScheme>;
   let mut def = None::<Def>;
+  let mut id_status = None::<IdStatus>;
   let (pm_pat, ty) = match &ars.pat[pat_idx] {
     // @def(32)
     sml_hir::Pat::Wild => (Pat::zero(Con::Any, pat), Ty::MetaVar(st.meta_gen.gen(cfg.gen))),
@@ -101,15 +107,17 @@ fn get_(
       if is_var {
         let ty = Ty::MetaVar(st.meta_gen.gen(cfg.gen));
         insert_name(st, cfg.cfg, ve, path.last().clone(), ty.clone(), pat_idx.into());
-        return Some(PatRet { pm_pat: Pat::zero(Con::Any, pat), ty, ty_scheme, def });
+        let pm_pat = Pat::zero(Con::Any, pat);
+        return Some(PatRet { pm_pat, ty, ty_scheme, def, id_status: Some(IdStatus::Val) });
       }
       let val_info = match maybe_val_info {
         Some(x) => x,
         None => {
-          st.err(pat_idx, ErrorKind::Undefined(Item::Val, path.last().clone()));
+          st.err(pat_idx, undefined_val(&cx.env, path));
           return None;
         }
       };
+      id_status = Some(val_info.id_status);
       let variant_name = match &val_info.id_status {
         IdStatus::Val => {
           st.err(pat_idx, ErrorKind::PatValIdStatus);
@@ -237,7 +245,7 @@ fn get_(
       (Pat::or(pm_pats, pat), ty)
     }
   };
-  Some(PatRet { pm_pat, ty, ty_scheme, def })
+  Some(PatRet { pm_pat, ty, ty_scheme, def, id_status })
 }
 
 fn ok_val_info(vi: Option<&ValInfo>) -> bool {
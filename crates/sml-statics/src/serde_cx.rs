@@ -0,0 +1,107 @@
+//! Context for serializing and deserializing the results of statics.
+//!
+//! Some things in the results can't be serialized as-is:
+//!
+//! - Fixed type variables have ids which are only unique within one run of statics, and which we
+//!   can't write out directly. We write out a small number for each distinct id instead, and give
+//!   each distinct number a fresh id when reading back, so fixed vars that were equal stay equal.
+//! - Built-in library file names are `&'static str`s. We read back only names the caller knows
+//!   about, and hand out the caller's `&'static str` for them.
+
+use crate::types::{FixedTyVar, FixedTyVarGen, TyVarSrc};
+use fast_hash::{FxHashMap, FxHashSet};
+use std::cell::RefCell;
+use uniq::Uniq;
+
+/// Runs `f`, which should do one serialization or deserialization of the results of statics.
+///
+/// When deserializing, every name of a built-in library file must be in `builtin_lib_names`.
+///
+/// # Panics
+///
+/// If called while already in `f`.
+pub fn with_serde_cx<F, T>(builtin_lib_names: &[&'static str], f: F) -> T
+where
+  F: FnOnce() -> T,
+{
+  CX.with(|cx| {
+    let mut cx = cx.borrow_mut();
+    assert!(cx.is_none(), "nested serde cx");
+    *cx = Some(SerdeCx {
+      builtin_lib_names: builtin_lib_names.iter().copied().collect(),
+      ..SerdeCx::default()
+    });
+  });
+  // reset even if `f` panics, so the next call may proceed.
+  let _reset = Reset;
+  f()
+}
+
+thread_local! {
+  static CX: RefCell<Option<SerdeCx>> = RefCell::new(None);
+}
+
+#[derive(Default)]
+struct SerdeCx {
+  builtin_lib_names: FxHashSet<&'static str>,
+  ser_ids: FxHashMap<Uniq, u32>,
+  de_ids: FxHashMap<u32, FixedTyVar>,
+  de_gen: FixedTyVarGen,
+}
+
+struct Reset;
+
+impl Drop for Reset {
+  fn drop(&mut self) {
+    CX.with(|cx| *cx.borrow_mut() = None);
+  }
+}
+
+fn with_cx<F, T>(f: F) -> Option<T>
+where
+  F: FnOnce(&mut SerdeCx) -> T,
+{
+  CX.with(|cx| cx.borrow_mut().as_mut().map(f))
+}
+
+const NO_CX: &str = "not in a serde cx";
+
+/// returns the number to write out for the fixed var with this id.
+pub(crate) fn ser_fixed_id<E>(id: Uniq) -> Result<u32, E>
+where
+  E: serde::ser::Error,
+{
+  let ret = with_cx(|cx| {
+    let len = u32::try_from(cx.ser_ids.len()).expect("too many fixed vars");
+    *cx.ser_ids.entry(id).or_insert(len)
+  });
+  ret.ok_or_else(|| E::custom(NO_CX))
+}
+
+/// returns the fixed var for the number that was written out for it.
+pub(crate) fn de_fixed_var<E>(
+  n: u32,
+  ty_var: sml_hir::TyVar,
+  src: TyVarSrc,
+) -> Result<FixedTyVar, E>
+where
+  E: serde::de::Error,
+{
+  let ret = with_cx(|cx| {
+    let SerdeCx { de_ids, de_gen, .. } = cx;
+    de_ids.entry(n).or_insert_with(|| de_gen.gen(ty_var, src)).clone()
+  });
+  ret.ok_or_else(|| E::custom(NO_CX))
+}
+
+/// returns the caller's static str equal to the name of a built-in library file.
+pub(crate) fn de_builtin_lib_name<E>(name: &str) -> Result<&'static str, E>
+where
+  E: serde::de::Error,
+{
+  match with_cx(|cx| cx.builtin_lib_names.get(name).copied()) {
+    Some(Some(name)) => Ok(name),
+    Some(None) => Err(E::custom(format!("unknown built-in library file: {name}"))),
+    None => Err(E::custom(NO_CX)),
+  }
+}
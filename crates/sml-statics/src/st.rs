@@ -1,9 +1,11 @@
 //! See [`St`].
 
 use crate::error::{Error, ErrorKind, Item};
-use crate::info::{Info, Mode};
+use crate::info::{Info, Mode, Scope};
 use crate::pat_match::{Lang, Pat};
-use crate::types::{Def, DefPath, FixedTyVarGen, MetaTyVar, MetaTyVarGen, Subst, Syms, Ty};
+use crate::types::{
+  Bs, Def, DefPath, EnvStack, FixedTyVarGen, MetaTyVar, MetaTyVarGen, Subst, Syms, Ty,
+};
 use crate::util::apply;
 use fast_hash::FxHashSet;
 
@@ -59,7 +61,7 @@ impl St {
     I: Into<sml_hir::Idx>,
   {
     match (self.info.mode(), &kind) {
-      (Mode::PathOrder, ErrorKind::Undefined(Item::Struct | Item::Sig | Item::Functor, _))
+      (Mode::PathOrder, ErrorKind::Undefined(Item::Struct | Item::Sig | Item::Functor, ..))
       | (Mode::Regular(_) | Mode::BuiltinLib(_), _) => {
         self.errors.push(Error { idx: idx.into(), kind });
       }
@@ -106,6 +108,30 @@ impl St {
     self.used.insert(idx);
   }
 
+  /// records the env in scope at the idx, for completions.
+  ///
+  /// completions use the env of the innermost enclosing idx that has one, so we only record envs
+  /// where they may change: at declarations, and at the expressions in the scope of a `let` or a
+  /// matcher's patterns. we also record them at holes, for their fills.
+  pub(crate) fn record_env<I>(&mut self, idx: I, env: &EnvStack)
+  where
+    I: Into<sml_hir::Idx>,
+  {
+    if let Mode::Regular(_) = self.info.mode() {
+      self.info.insert_scope(idx.into(), Scope::Env(env.clone()));
+    }
+  }
+
+  /// like `record_env`, but also records the signatures and functors in scope.
+  pub(crate) fn record_bs<I>(&mut self, idx: I, bs: &Bs)
+  where
+    I: Into<sml_hir::Idx>,
+  {
+    if let Mode::Regular(_) = self.info.mode() {
+      self.info.insert_scope(idx.into(), Scope::Bs(bs.clone()));
+    }
+  }
+
   pub(crate) fn push_prefix(&mut self, name: str_util::Name) {
     if self.info.mode().is_path_order() {
       return;
@@ -160,7 +186,13 @@ impl St {
     for ty in self.info.tys_mut() {
       apply(&self.subst, ty);
     }
-    self.info.meta_vars = self.subst.into_meta_var_info();
+    self.info.meta_vars = self.subst.meta_var_info().clone();
+    // the recorded scopes may have types with meta vars solved later, so keep the subst, and the
+    // generator to make fresh meta vars when unifying with those types later.
+    if let Mode::Regular(_) = self.info.mode() {
+      self.info.subst = self.subst;
+      self.info.meta_gen = self.meta_gen;
+    }
     (lang.syms, errors, self.info)
   }
 }
@@ -0,0 +1,123 @@
+//! Stub declarations for the items a structure is missing from its signature.
+
+use crate::fmt_util::ty_var_name;
+use crate::types::{
+  BoundTyVars, Env, IdStatus, MetaVarInfo, MetaVarNames, Syms, Ty, TyInfo, TyNameSet, TyVarKind,
+  ValInfo,
+};
+use std::fmt::Write as _;
+
+const BODY: &str = "raise Fail \"unimplemented\"";
+
+pub(crate) struct Cx<'a> {
+  pub(crate) syms: &'a Syms,
+  pub(crate) mv_info: &'a MetaVarInfo,
+  /// the ty names bound by the signature, which the stubs will define, so we write them with only
+  /// their last name.
+  pub(crate) short: &'a TyNameSet,
+}
+
+impl Cx<'_> {
+  pub(crate) fn str(&self, name: &str_util::Name, env: &Env) -> String {
+    let mut ret = format!("structure {name} = struct\n");
+    for line in self.env(env).iter().flat_map(|x| x.lines()) {
+      writeln!(ret, "  {line}").unwrap();
+    }
+    ret.push_str("end");
+    ret
+  }
+
+  pub(crate) fn ty(&self, name: &str_util::Name, ty_info: &TyInfo) -> String {
+    let ty_scheme = &ty_info.ty_scheme;
+    let params = TyVarParams(&ty_scheme.bound_vars);
+    let mut mvs = MetaVarNames::new(self.mv_info);
+    if ty_info.val_env.is_empty() {
+      mvs.extend_for(&ty_scheme.ty);
+      // an abstract ty is one the signature binds with this name. other tys, like `type t = Foo.t`
+      // or `type u = t`, are abbreviations.
+      let is_abstract = match &ty_scheme.ty {
+        Ty::Con(_, sym) => {
+          self.short.contains(sym)
+            && self.syms.get(*sym).map_or(false, |(path, _)| path.last() == name)
+        }
+        _ => false,
+      };
+      if is_abstract {
+        return format!("type {params}{name} = unit");
+      }
+      let ty = ty_scheme.display_short(&ty_scheme.ty, &mvs, self.syms, self.short);
+      return format!("type {params}{name} = {ty}");
+    }
+    let mut ret = format!("datatype {params}{name} =");
+    let mut cons: Vec<_> = ty_info.val_env.iter().collect();
+    cons.sort_unstable_by_key(|&(name, _)| name);
+    for (idx, (con_name, val_info)) in cons.into_iter().enumerate() {
+      let sep = if idx == 0 { " " } else { " | " };
+      write!(ret, "{sep}{con_name}").unwrap();
+      if let Ty::Fn(arg, _) = &val_info.ty_scheme.ty {
+        mvs.extend_for(arg);
+        let arg = val_info.ty_scheme.display_short(arg, &mvs, self.syms, self.short);
+        write!(ret, " of {arg}").unwrap();
+      }
+    }
+    ret
+  }
+
+  /// constructors are stubbed with their datatype instead.
+  pub(crate) fn val(&self, name: &str_util::Name, val_info: &ValInfo) -> Option<String> {
+    let ty_scheme = &val_info.ty_scheme;
+    let mut mvs = MetaVarNames::new(self.mv_info);
+    mvs.extend_for(&ty_scheme.ty);
+    let ret = match val_info.id_status {
+      IdStatus::Con => return None,
+      IdStatus::Exn(_) => match &ty_scheme.ty {
+        Ty::Fn(arg, _) => {
+          let arg = ty_scheme.display_short(arg, &mvs, self.syms, self.short);
+          format!("exception {name} of {arg}")
+        }
+        _ => format!("exception {name}"),
+      },
+      IdStatus::Val => {
+        let ty = ty_scheme.display_short(&ty_scheme.ty, &mvs, self.syms, self.short);
+        // a `fn` is not expansive, so this may be polymorphic.
+        match &ty_scheme.ty {
+          Ty::Fn(_, _) => format!("val {name} : {ty} = fn _ => {BODY}"),
+          _ => format!("val {name} : {ty} = {BODY}"),
+        }
+      }
+    };
+    Some(ret)
+  }
+
+  fn env(&self, env: &Env) -> Vec<String> {
+    let mut strs: Vec<_> = env.str_env.iter().collect();
+    strs.sort_unstable_by_key(|&(name, _)| name);
+    let mut tys: Vec<_> = env.ty_env.iter().collect();
+    tys.sort_unstable_by_key(|&(name, _)| name);
+    let mut vals: Vec<_> = env.val_env.iter().collect();
+    vals.sort_unstable_by_key(|&(name, _)| name);
+    let strs = strs.into_iter().map(|(name, env)| self.str(name, env));
+    let tys = tys.into_iter().map(|(name, ty_info)| self.ty(name, ty_info));
+    let vals = vals.into_iter().filter_map(|(name, val_info)| self.val(name, val_info));
+    strs.chain(tys).chain(vals).collect()
+  }
+}
+
+/// the ty var params of a type, like `('a, ''b) `, including the trailing space if non-empty.
+struct TyVarParams<'a>(&'a BoundTyVars);
+
+impl std::fmt::Display for TyVarParams<'_> {
+  fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    let names: Vec<_> = self
+      .0
+      .kinds()
+      .enumerate()
+      .map(|(idx, kind)| ty_var_name(matches!(kind, Some(TyVarKind::Equality)), idx).to_string())
+      .collect();
+    match names.as_slice() {
+      [] => Ok(()),
+      [name] => write!(f, "{name} "),
+      _ => write!(f, "({}) ", names.join(", ")),
+    }
+  }
+}
@@ -7,12 +7,12 @@ use crate::get_env::{get_env_from_str_path, get_ty_info, get_ty_info_raw};
 use crate::info::Mode;
 use crate::st::St;
 use crate::types::{
-  generalize, generalize_fixed, BasicOverload, Bs, Env, EnvLike, EnvStack, FunEnv, FunSig,
+  generalize, generalize_fixed, BasicOverload, Bs, Def, Env, EnvLike, EnvStack, FunEnv, FunSig,
   HasRecordMetaVars, IdStatus, Sig, SigEnv, StartedSym, StrEnv, Sym, SymsMarker, Ty, TyEnv, TyInfo,
   TyNameSet, TyScheme, TyVarKind, TyVarSrc, ValEnv, ValInfo,
 };
 use crate::util::{apply_bv, ins_check_name, ins_no_dupe, ty_syms};
-use crate::{dec, ty};
+use crate::{dec, stub, ty};
 use fast_hash::{map, FxHashMap, FxHashSet};
 
 pub(crate) fn get(st: &mut St, bs: &Bs, ars: &sml_hir::Arenas, top_dec: sml_hir::StrDecIdx) -> Bs {
@@ -46,6 +46,7 @@ fn get_str_dec(
     Some(x) => x,
     None => return,
   };
+  st.record_bs(str_dec, bs);
   match &ars.str_dec[str_dec] {
     // @def(56)
     sml_hir::StrDec::Dec(dec) => {
@@ -188,6 +189,7 @@ fn get_str_exp(
     Some(x) => x,
     None => return,
   };
+  st.record_bs(str_exp, bs);
   match &ars.str_exp[str_exp] {
     // @def(50)
     sml_hir::StrExp::Struct(str_dec) => get_str_dec(st, bs, ars, StrDecAc::Env(ac), *str_dec),
@@ -213,7 +215,7 @@ fn get_str_exp(
         Mode::Regular(_) => {
           env_instance_sig(st, &mut subst, &str_exp_env, &sig, str_exp.into());
           env_realize(&subst, &mut to_add);
-          env_enrich(st, &str_exp_env, &to_add, str_exp.into());
+          env_enrich(st, &str_exp_env, &to_add, str_exp.into(), Some(&sig.ty_names));
         }
         Mode::BuiltinLib(_) | Mode::PathOrder => {}
       }
@@ -257,7 +259,7 @@ fn get_str_exp(
         env_realize(&subst, &mut to_add);
         let mut param_env = fun_sig.param.env.clone();
         env_realize(&subst, &mut param_env);
-        env_enrich(st, &arg_env, &param_env, arg_idx);
+        env_enrich(st, &arg_env, &param_env, arg_idx, Some(&fun_sig.param.ty_names));
         let def = st.def(str_exp.into());
         for env in to_add.str_env.values_mut() {
           env.def = def;
@@ -271,7 +273,7 @@ fn get_str_exp(
         st.info.insert(str_exp.into(), None, fun_sig.body_env.def);
         ac.append(&mut to_add);
       }
-      None => st.err(str_exp, ErrorKind::Undefined(Item::Functor, fun_name.clone())),
+      None => st.err(str_exp, ErrorKind::undefined(Item::Functor, fun_name, bs.fun_env.keys())),
     },
     // @def(55)
     sml_hir::StrExp::Let(str_dec, str_exp) => {
@@ -295,6 +297,7 @@ fn get_sig_exp(
     Some(x) => x,
     None => return None,
   };
+  st.record_bs(sig_exp, bs);
   match &ars.sig_exp[sig_exp] {
     // @def(62)
     sml_hir::SigExp::Spec(spec) => {
@@ -321,7 +324,7 @@ fn get_sig_exp(
         }
       }
       None => {
-        st.err(sig_exp, ErrorKind::Undefined(Item::Sig, name.clone()));
+        st.err(sig_exp, ErrorKind::undefined(Item::Sig, name, bs.sig_env.keys()));
         None
       }
     },
@@ -459,6 +462,7 @@ fn get_spec(st: &mut St, bs: &Bs, ars: &sml_hir::Arenas, ac: &mut Env, spec: sml
     Some(x) => x,
     None => return,
   };
+  st.record_bs(spec, bs);
   match &ars.spec[spec] {
     // @def(68)
     sml_hir::Spec::Val(ty_vars, val_descs) => {
@@ -774,27 +778,65 @@ fn bound_ty_name_to_path<'e>(
 // exactly the same keys (names). or we could add a special env only for use here that has the
 // indices?
 
-fn env_enrich(st: &mut St, general: &Env, specific: &Env, idx: sml_hir::Idx) {
+/// if `stubs` is given, it is the ty names bound by the signature, and we make stub declarations
+/// for the missing items.
+fn env_enrich(
+  st: &mut St,
+  general: &Env,
+  specific: &Env,
+  idx: sml_hir::Idx,
+  stubs: Option<&TyNameSet>,
+) {
   for (name, specific) in &specific.str_env {
     match general.str_env.get(name) {
-      Some(general) => env_enrich(st, general, specific, idx),
-      None => st.err(idx, ErrorKind::Missing(Item::Struct, name.clone())),
+      Some(general) => {
+        add_impl(st, specific.def, general.def);
+        // the stubs would go in the substructure, which we don't know the location of.
+        env_enrich(st, general, specific, idx, None);
+      }
+      None => {
+        let stub = stub_cx(st, stubs).map(|cx| cx.str(name, specific));
+        st.err(idx, ErrorKind::Missing(Item::Struct, name.clone(), stub));
+      }
     }
   }
   for (name, specific) in &specific.ty_env {
     match general.ty_env.get(name) {
-      Some(general) => ty_info_enrich(st, general.clone(), specific.clone(), idx),
-      None => st.err(idx, ErrorKind::Missing(Item::Ty, name.clone())),
+      Some(general) => {
+        add_impl(st, specific.def, general.def);
+        ty_info_enrich(st, general.clone(), specific.clone(), idx);
+      }
+      None => {
+        let stub = stub_cx(st, stubs).map(|cx| cx.ty(name, specific));
+        st.err(idx, ErrorKind::Missing(Item::Ty, name.clone(), stub));
+      }
     }
   }
   for (name, specific) in &specific.val_env {
     match general.val_env.get(name) {
-      Some(general) => val_info_enrich(st, general.clone(), specific, name, idx),
-      None => st.err(idx, ErrorKind::Missing(Item::Val, name.clone())),
+      Some(general) => {
+        add_impl(st, specific.def, general.def);
+        val_info_enrich(st, general.clone(), specific, name, idx);
+      }
+      None => {
+        let stub = stub_cx(st, stubs).and_then(|cx| cx.val(name, specific));
+        st.err(idx, ErrorKind::Missing(Item::Val, name.clone(), stub));
+      }
     }
   }
 }
 
+fn stub_cx<'a>(st: &'a St, short: Option<&'a TyNameSet>) -> Option<stub::Cx<'a>> {
+  short.map(|short| stub::Cx { syms: &st.syms, mv_info: st.subst.meta_var_info(), short })
+}
+
+/// records that the `def` implements the `spec`, if we know where both are.
+fn add_impl(st: &mut St, spec: Option<Def>, def: Option<Def>) {
+  if let (Some(spec), Some(def)) = (spec, def) {
+    st.info.add_impl(spec, def);
+  }
+}
+
 fn ty_info_enrich(st: &mut St, mut general: TyInfo, specific: TyInfo, idx: sml_hir::Idx) {
   eq_ty_fn(st, specific.ty_scheme, general.ty_scheme.clone(), idx);
   if specific.val_env.is_empty() {
@@ -803,12 +845,13 @@ fn ty_info_enrich(st: &mut St, mut general: TyInfo, specific: TyInfo, idx: sml_h
   for (name, specific) in specific.val_env {
     match general.val_env.remove(&name) {
       Some(general) => {
+        add_impl(st, specific.def, general.def);
         if !general.id_status.same_kind_as(specific.id_status) {
           st.err(idx, ErrorKind::WrongIdStatus(name.clone()));
         }
         eq_ty_fn(st, specific.ty_scheme, general.ty_scheme.clone(), idx);
       }
-      None => st.err(idx, ErrorKind::Missing(Item::Val, name.clone())),
+      None => st.err(idx, ErrorKind::Missing(Item::Val, name.clone(), None)),
     }
   }
   for name in general.val_env.keys() {
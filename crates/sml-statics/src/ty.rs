@@ -38,7 +38,7 @@ pub(crate) fn get(
     // @def(44)
     sml_hir::Ty::Var(v) => match cx.fixed.get(v) {
       None => {
-        st.err(ty, ErrorKind::Undefined(Item::TyVar, v.as_name().clone()));
+        st.err(ty, ErrorKind::Undefined(Item::TyVar, v.as_name().clone(), None));
         Ty::None
       }
       Some(fv) => match (mode, fv.src()) {
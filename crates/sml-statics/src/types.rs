@@ -3,17 +3,20 @@
 //! Probably the single most important file in this crate. Lots of types used pervasively across
 //! this crate are defined here.
 
+use crate::def_map::DefMap;
 use crate::fmt_util::{idx_to_name, ty_var_name};
+use crate::serde_cx::{de_builtin_lib_name, de_fixed_var, ser_fixed_id};
 use drop_bomb::DropBomb;
 use fast_hash::{FxHashMap, FxHashSet};
 use fmt_util::comma_seq;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::fmt;
+use std::fmt::{self, Write as _};
 use std::sync::Arc;
 use uniq::{Uniq, UniqGen};
 
 /// Definition: Type
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum Ty {
   None,
   /// Can only appear when this Ty is wrapped in a TyScheme.
@@ -48,7 +51,7 @@ impl Ty {
     meta_vars: &'a MetaVarNames<'a>,
     syms: &'a Syms,
   ) -> impl fmt::Display + 'a {
-    TyDisplay { ty: self, bound_vars: None, meta_vars, syms, prec: TyPrec::Arrow }
+    TyDisplay { ty: self, bound_vars: None, meta_vars, syms, short: None, prec: TyPrec::Arrow }
   }
 
   pub(crate) fn desc(&self) -> &'static str {
@@ -69,12 +72,21 @@ struct TyDisplay<'a> {
   bound_vars: Option<&'a BoundTyVars>,
   meta_vars: &'a MetaVarNames<'a>,
   syms: &'a Syms,
+  /// these are written with only their last name, not their whole path.
+  short: Option<&'a TyNameSet>,
   prec: TyPrec,
 }
 
 impl<'a> TyDisplay<'a> {
   fn with(&self, ty: &'a Ty, prec: TyPrec) -> Self {
-    Self { ty, bound_vars: self.bound_vars, meta_vars: self.meta_vars, syms: self.syms, prec }
+    Self {
+      ty,
+      bound_vars: self.bound_vars,
+      meta_vars: self.meta_vars,
+      syms: self.syms,
+      short: self.short,
+      prec,
+    }
   }
 }
 
@@ -123,6 +135,7 @@ impl fmt::Display for TyDisplay<'_> {
               bound_vars: self.bound_vars,
               meta_vars: self.meta_vars,
               syms: self.syms,
+              short: self.short,
               lab,
               ty,
             }),
@@ -146,7 +159,10 @@ impl fmt::Display for TyDisplay<'_> {
           }
           f.write_str(" ")?;
         }
-        SymDisplay { sym: *sym, syms: self.syms }.fmt(f)?;
+        match (self.short, self.syms.get(*sym)) {
+          (Some(short), Some((path, _))) if short.contains(sym) => path.last().fmt(f)?,
+          _ => SymDisplay { sym: *sym, syms: self.syms }.fmt(f)?,
+        }
       }
       Ty::Fn(param, res) => {
         let needs_parens = self.prec > TyPrec::Arrow;
@@ -237,6 +253,7 @@ struct RowDisplay<'a> {
   bound_vars: Option<&'a BoundTyVars>,
   meta_vars: &'a MetaVarNames<'a>,
   syms: &'a Syms,
+  short: Option<&'a TyNameSet>,
   lab: &'a sml_hir::Lab,
   ty: &'a Ty,
 }
@@ -250,6 +267,7 @@ impl fmt::Display for RowDisplay<'_> {
       bound_vars: self.bound_vars,
       meta_vars: self.meta_vars,
       syms: self.syms,
+      short: self.short,
       prec: TyPrec::Arrow,
     };
     fmt::Display::fmt(&td, f)
@@ -257,7 +275,7 @@ impl fmt::Display for RowDisplay<'_> {
 }
 
 /// Definition: `TypeScheme`, `TypeFcn`
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct TyScheme {
   pub(crate) bound_vars: BoundTyVars,
   pub(crate) ty: Ty,
@@ -300,12 +318,78 @@ impl TyScheme {
       bound_vars: Some(&self.bound_vars),
       meta_vars,
       syms,
+      short: None,
+      prec: TyPrec::Arrow,
+    }
+  }
+
+  /// Like `display`, but displays `ty`, which should be part of this ty scheme, and writes the ty
+  /// names in `short` with only their last name, as if in the scope where they are defined.
+  pub(crate) fn display_short<'a>(
+    &'a self,
+    ty: &'a Ty,
+    meta_vars: &'a MetaVarNames<'a>,
+    syms: &'a Syms,
+    short: &'a TyNameSet,
+  ) -> impl fmt::Display + 'a {
+    TyDisplay {
+      ty,
+      bound_vars: Some(&self.bound_vars),
+      meta_vars,
+      syms,
+      short: Some(short),
       prec: TyPrec::Arrow,
     }
   }
+
+  /// Writes the type scheme, which is usually a curried function type, to `buf`. Returns the
+  /// ranges in `buf` of each of the curried parameters. A parameter that is a tuple has a range for
+  /// each component.
+  pub(crate) fn write_params(
+    &self,
+    meta_vars: &MetaVarNames<'_>,
+    syms: &Syms,
+    buf: &mut String,
+  ) -> Vec<Vec<std::ops::Range<usize>>> {
+    let display = |ty, prec| TyDisplay {
+      ty,
+      bound_vars: Some(&self.bound_vars),
+      meta_vars,
+      syms,
+      short: None,
+      prec,
+    };
+    let mut ret = Vec::<Vec<std::ops::Range<usize>>>::new();
+    let mut ty = &self.ty;
+    while let Ty::Fn(param, res) = ty {
+      let mut ranges = Vec::<std::ops::Range<usize>>::new();
+      let components: Vec<_> = match param.as_ref() {
+        Ty::Record(rows)
+          if rows.len() > 1
+            && rows.keys().enumerate().all(|(idx, lab)| sml_hir::Lab::tuple(idx) == *lab) =>
+        {
+          rows.values().map(|ty| (ty, TyPrec::App)).collect()
+        }
+        param => vec![(param, TyPrec::Star)],
+      };
+      for (idx, (ty, prec)) in components.into_iter().enumerate() {
+        if idx != 0 {
+          buf.push_str(" * ");
+        }
+        let start = buf.len();
+        write!(buf, "{}", display(ty, prec)).unwrap();
+        ranges.push(start..buf.len());
+      }
+      ret.push(ranges);
+      buf.push_str(" -> ");
+      ty = res;
+    }
+    write!(buf, "{}", display(ty, TyPrec::Arrow)).unwrap();
+    ret
+  }
 }
 
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct BoundTyVars(Vec<Option<TyVarKind>>);
 
 impl BoundTyVars {
@@ -322,14 +406,14 @@ impl BoundTyVars {
   }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum TyVarKind {
   Equality,
   Overloaded(Overload),
   Record(RecordTy),
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum BasicOverload {
   Int,
   Real,
@@ -356,7 +440,7 @@ impl fmt::Display for BasicOverload {
   }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum CompositeOverload {
   WordInt,
   RealInt,
@@ -408,7 +492,7 @@ impl fmt::Display for CompositeOverload {
   }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum Overload {
   Basic(BasicOverload),
   Composite(CompositeOverload),
@@ -450,7 +534,7 @@ impl fmt::Display for Overload {
 }
 
 /// Information about overloads.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub(crate) struct Overloads {
   pub(crate) int: Vec<Sym>,
   pub(crate) real: Vec<Sym>,
@@ -494,13 +578,17 @@ impl std::ops::IndexMut<BasicOverload> for Overloads {
 /// - [`sml_hir::TyVar`]
 ///
 /// Basically a de Bruijn index.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct BoundTyVar(idx::Idx);
 
 impl BoundTyVar {
   pub(crate) fn index_into<'a, T>(&self, xs: &'a [T]) -> &'a T {
     xs.get(self.0.to_usize()).unwrap()
   }
+
+  pub(crate) fn to_usize(&self) -> usize {
+    self.0.to_usize()
+  }
 }
 
 /// Generated, and to be solved for a real type, by the inference algorithm.
@@ -510,13 +598,13 @@ impl BoundTyVar {
 ///
 /// Internally contains a "rank" to know when it should be generalizable; see "Efficient ML Type
 /// Inference Using Ranked Type Variables" (doi:10.1145/1292535.1292538)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub(crate) struct MetaTyVar {
   id: u32,
   rank: MetaTyVarRank,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub(crate) enum MetaTyVarRank {
   Finite(u16),
   Infinite,
@@ -528,7 +616,7 @@ impl MetaTyVar {
   }
 }
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub(crate) struct MetaTyVarGen {
   id: u32,
   rank: u16,
@@ -605,6 +693,27 @@ impl FixedTyVar {
   }
 }
 
+/// the id is only unique within one run of statics, so see [`crate::serde_cx`].
+impl Serialize for FixedTyVar {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: serde::Serializer,
+  {
+    let id = ser_fixed_id::<S::Error>(self.id)?;
+    (id, &self.ty_var, self.src).serialize(serializer)
+  }
+}
+
+impl<'de> Deserialize<'de> for FixedTyVar {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    let (id, ty_var, src) = <(u32, sml_hir::TyVar, TyVarSrc)>::deserialize(deserializer)?;
+    de_fixed_var(id, ty_var, src)
+  }
+}
+
 impl fmt::Display for FixedTyVar {
   fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     self.ty_var.fmt(f)
@@ -612,7 +721,7 @@ impl fmt::Display for FixedTyVar {
 }
 
 /// Where a type variable was bound.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub(crate) enum TyVarSrc {
   /// Bound at `type` or `datatype` (or `where type`).
   Ty,
@@ -632,7 +741,7 @@ impl FixedTyVarGen {
 pub(crate) type RecordTy = BTreeMap<sml_hir::Lab, Ty>;
 
 /// Definition: `TyName`
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub(crate) struct Sym(idx::Idx);
 
 impl fmt::Debug for Sym {
@@ -723,7 +832,7 @@ impl fmt::Display for SymDisplay<'_> {
 ///
 /// Note the `Default` impl is "fake", in that it returns a totally empty `Syms`, which will lack
 /// even built-in items like `type int` and `exception Bind`.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct Syms {
   /// remember: always use Sym::idx to index
   store: Vec<(sml_hir::Path, TyInfo)>,
@@ -783,13 +892,63 @@ impl Syms {
   pub(crate) fn overloads_mut(&mut self) -> &mut Overloads {
     &mut self.overloads
   }
+
+  /// Returns how many items this has, to later get the items added after now.
+  #[must_use]
+  pub fn lens(&self) -> SymsLens {
+    SymsLens { store: self.store.len(), exns: self.exns.len() }
+  }
+
+  /// Returns the items added since this had the `lens`.
+  #[must_use]
+  pub fn added_since(&self, lens: SymsLens) -> SymsAdded {
+    SymsAdded { store: self.store[lens.store..].to_vec(), exns: self.exns[lens.exns..].to_vec() }
+  }
+
+  /// Adds the items, which must have been added to a `Syms` equal to this one when it had the
+  /// lens this has now.
+  pub fn add(&mut self, added: SymsAdded) {
+    self.store.extend(added.store);
+    self.exns.extend(added.exns);
+  }
+}
+
+/// How many items a [`Syms`] has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymsLens {
+  store: usize,
+  exns: usize,
+}
+
+/// The items added to a [`Syms`] after it had some [`SymsLens`].
+///
+/// Does not include overloads, which are only added by built-in libraries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymsAdded {
+  pub(crate) store: Vec<(sml_hir::Path, TyInfo)>,
+  pub(crate) exns: Vec<(sml_hir::Path, Option<Ty>)>,
+}
+
+impl SymsAdded {
+  /// Returns whether nothing was added.
+  #[must_use]
+  pub fn is_empty(&self) -> bool {
+    self.store.is_empty() && self.exns.is_empty()
+  }
+
+  /// Changes the defs in this by the map.
+  pub fn remap_defs(&mut self, map: &DefMap) {
+    for (_, ty_info) in &mut self.store {
+      map.apply_ty_info(ty_info);
+    }
+  }
 }
 
 /// A marker to determine when a `Sym` was generated.
 #[derive(Debug, Clone, Copy)]
 pub(crate) struct SymsMarker(usize);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub(crate) struct Exn(idx::Idx);
 
 /// A helper to construct information about [`Syms`]s.
@@ -805,7 +964,7 @@ impl StartedSym {
 }
 
 /// Definition: `TyStr`
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct TyInfo {
   pub(crate) ty_scheme: TyScheme,
   pub(crate) val_env: ValEnv,
@@ -821,7 +980,7 @@ pub(crate) type TyEnv = FxHashMap<str_util::Name, TyInfo>;
 /// Definition: `ValEnv`
 pub(crate) type ValEnv = FxHashMap<str_util::Name, ValInfo>;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct ValInfo {
   pub(crate) ty_scheme: TyScheme,
   pub(crate) id_status: IdStatus,
@@ -829,7 +988,7 @@ pub(crate) struct ValInfo {
 }
 
 /// Definition: `IdStatus`
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) enum IdStatus {
   Con,
   Exn(Exn),
@@ -854,11 +1013,13 @@ pub(crate) trait EnvLike {
   fn all_str(&self) -> Vec<&Env>;
   fn all_ty(&self) -> Vec<&TyInfo>;
   fn all_val(&self) -> Vec<&ValInfo>;
+  /// returns every env, which may contain shadowed names.
+  fn all_envs(&self) -> Vec<&Env>;
   fn into_env(self) -> Env;
 }
 
 /// Definition: Env
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct Env {
   pub(crate) str_env: StrEnv,
   pub(crate) ty_env: TyEnv,
@@ -903,6 +1064,10 @@ impl EnvLike for Env {
     self.val_env.values().collect()
   }
 
+  fn all_envs(&self) -> Vec<&Env> {
+    vec![self]
+  }
+
   fn into_env(self) -> Env {
     self
   }
@@ -910,7 +1075,10 @@ impl EnvLike for Env {
 
 /// A wrapper around a stack of [`Env`]s. Is meant to act like an `Env` in most respects, but is
 /// faster to `Clone`.
-#[derive(Debug, Default, Clone)]
+///
+/// Also faster to compare when the envs are shared, since `Arc`s of `Eq` things compare by pointer
+/// first.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct EnvStack(Vec<Arc<Env>>);
 
 impl EnvStack {
@@ -921,6 +1089,16 @@ impl EnvStack {
   pub(crate) fn push(&mut self, other: Env) {
     self.0.push(Arc::new(other));
   }
+
+  /// returns the envs, innermost (i.e. most recently pushed) first.
+  pub(crate) fn iter(&self) -> impl Iterator<Item = &Env> + '_ {
+    self.0.iter().rev().map(AsRef::as_ref)
+  }
+
+  /// like `iter`, but mutable, so the envs are no longer shared.
+  pub(crate) fn iter_mut(&mut self) -> impl Iterator<Item = &mut Env> + '_ {
+    self.0.iter_mut().rev().map(Arc::make_mut)
+  }
 }
 
 impl EnvLike for EnvStack {
@@ -975,6 +1153,10 @@ impl EnvLike for EnvStack {
       .collect()
   }
 
+  fn all_envs(&self) -> Vec<&Env> {
+    self.0.iter().map(AsRef::as_ref).collect()
+  }
+
   fn into_env(mut self) -> Env {
     let mut env = Env::default();
     for mut other in self.0.drain(..) {
@@ -1001,14 +1183,14 @@ pub(crate) struct Cx {
 pub(crate) type TyNameSet = FxHashSet<Sym>;
 
 /// Definition: Sig
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct Sig {
   pub(crate) ty_names: TyNameSet,
   pub(crate) env: Env,
 }
 
 /// Definition: `FunSig`
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct FunSig {
   pub(crate) param: Sig,
   pub(crate) body_ty_names: TyNameSet,
@@ -1020,7 +1202,7 @@ pub(crate) type SigEnv = FxHashMap<str_util::Name, Sig>;
 pub(crate) type FunEnv = FxHashMap<str_util::Name, FunSig>;
 
 /// Definition: Basis
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub(crate) struct Bs<E = EnvStack> {
   pub(crate) env: E,
   pub(crate) sig_env: Arc<SigEnv>,
@@ -1050,10 +1232,10 @@ impl<E: EnvLike> Bs<E> {
 }
 
 /// Information about meta type variables.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct MetaVarInfo(FxHashMap<MetaTyVar, TyVarKind>);
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub(crate) struct Subst {
   mv_info: MetaVarInfo,
   entries: FxHashMap<MetaTyVar, SubstEntry>,
@@ -1074,12 +1256,12 @@ impl Subst {
     self.entries.get(&mv)
   }
 
-  pub(crate) fn into_meta_var_info(self) -> MetaVarInfo {
-    self.mv_info
+  pub(crate) fn meta_var_info(&self) -> &MetaVarInfo {
+    &self.mv_info
   }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) enum SubstEntry {
   /// We solved this meta var to a `Ty`.
   Solved(Ty),
@@ -1310,7 +1492,7 @@ fn handle_bv(
 }
 
 /// A definition site.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Def {
   /// A def contained at a path.
   Path(DefPath, sml_hir::Idx),
@@ -1319,7 +1501,7 @@ pub enum Def {
 }
 
 /// A definition path.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
 pub enum DefPath {
   /// A regular path.
   Regular(paths::PathId),
@@ -1328,3 +1510,23 @@ pub enum DefPath {
   /// source file.
   BuiltinLib(&'static str),
 }
+
+/// like [`DefPath`], but owning the name of the built-in library, so it may be deserialized.
+#[derive(Deserialize)]
+enum SerdeDefPath {
+  Regular(paths::PathId),
+  BuiltinLib(String),
+}
+
+impl<'de> Deserialize<'de> for DefPath {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: serde::Deserializer<'de>,
+  {
+    let ret = match SerdeDefPath::deserialize(deserializer)? {
+      SerdeDefPath::Regular(path) => Self::Regular(path),
+      SerdeDefPath::BuiltinLib(name) => Self::BuiltinLib(de_builtin_lib_name::<D::Error>(&name)?),
+    };
+    Ok(ret)
+  }
+}
@@ -3,7 +3,7 @@
 use crate::equality;
 use crate::error::{ErrorKind, MismatchedTypesFlavor};
 use crate::st::St;
-use crate::types::{meta_vars, MetaTyVar, SubstEntry, Ty, TyVarKind};
+use crate::types::{meta_vars, MetaTyVar, MetaTyVarGen, Subst, SubstEntry, Syms, Ty, TyVarKind};
 use crate::util::apply;
 use fast_hash::FxHashMap;
 
@@ -18,12 +18,32 @@ pub(crate) fn unify_no_emit(st: &mut St, want: Ty, got: Ty) -> Result<(), ErrorK
   if st.info.mode().is_path_order() {
     return Ok(());
   }
-  unify_(st, want.clone(), got.clone()).map_err(|err| match err {
+  let mut cx = Cx { subst: &mut st.subst, meta_gen: &mut st.meta_gen, syms: &st.syms };
+  unify_(&mut cx, want.clone(), got.clone()).map_err(|err| match err {
     UnifyError::Circularity(mv, ty) => ErrorKind::Circularity(mv, ty),
     UnifyError::MismatchedTypes(flavor) => ErrorKind::MismatchedTypes(flavor, want, got),
   })
 }
 
+/// Unifies the types on the subst, which is usually a throwaway clone, outside of checking. Returns
+/// whether they unified.
+pub(crate) fn unify_on(
+  subst: &mut Subst,
+  meta_gen: &mut MetaTyVarGen,
+  syms: &Syms,
+  want: Ty,
+  got: Ty,
+) -> bool {
+  unify_(&mut Cx { subst, meta_gen, syms }, want, got).is_ok()
+}
+
+/// the parts of the state that unification uses.
+struct Cx<'a> {
+  subst: &'a mut Subst,
+  meta_gen: &'a mut MetaTyVarGen,
+  syms: &'a Syms,
+}
+
 #[derive(Debug)]
 enum UnifyError {
   Circularity(MetaTyVar, Ty),
@@ -39,9 +59,9 @@ impl From<MismatchedTypesFlavor> for UnifyError {
 /// does not emit any errors to the `st`, instead returns an error (if any).
 ///
 /// `want` and `got` will have `subst` applied to them upon entry to this function.
-fn unify_(st: &mut St, mut want: Ty, mut got: Ty) -> Result<(), UnifyError> {
-  apply(&st.subst, &mut want);
-  apply(&st.subst, &mut got);
+fn unify_(cx: &mut Cx<'_>, mut want: Ty, mut got: Ty) -> Result<(), UnifyError> {
+  apply(cx.subst, &mut want);
+  apply(cx.subst, &mut got);
   match (want, got) {
     (Ty::None, _) | (_, Ty::None) => Ok(()),
     (Ty::BoundVar(want), Ty::BoundVar(got)) => {
@@ -51,7 +71,7 @@ fn unify_(st: &mut St, mut want: Ty, mut got: Ty) -> Result<(), UnifyError> {
         Err(MismatchedTypesFlavor::BoundTyVar(want, got).into())
       }
     }
-    (Ty::MetaVar(mv), ty) | (ty, Ty::MetaVar(mv)) => unify_mv(st, mv, ty),
+    (Ty::MetaVar(mv), ty) | (ty, Ty::MetaVar(mv)) => unify_mv(cx, mv, ty),
     (Ty::FixedVar(want), Ty::FixedVar(got)) => {
       if want == got {
         Ok(())
@@ -63,7 +83,7 @@ fn unify_(st: &mut St, mut want: Ty, mut got: Ty) -> Result<(), UnifyError> {
       for (lab, want) in want_rows {
         match got_rows.remove(&lab) {
           None => return Err(MismatchedTypesFlavor::MissingRow(lab).into()),
-          Some(got) => unify_(st, want, got)?,
+          Some(got) => unify_(cx, want, got)?,
         }
       }
       if got_rows.is_empty() {
@@ -78,19 +98,19 @@ fn unify_(st: &mut St, mut want: Ty, mut got: Ty) -> Result<(), UnifyError> {
       }
       assert_eq!(want_args.len(), got_args.len());
       for (want, got) in want_args.into_iter().zip(got_args) {
-        unify_(st, want, got)?;
+        unify_(cx, want, got)?;
       }
       Ok(())
     }
     (Ty::Fn(want_param, want_res), Ty::Fn(got_param, got_res)) => {
-      unify_(st, *want_param, *got_param)?;
-      unify_(st, *want_res, *got_res)
+      unify_(cx, *want_param, *got_param)?;
+      unify_(cx, *want_res, *got_res)
     }
     (want, got) => Err(MismatchedTypesFlavor::Head(want, got).into()),
   }
 }
 
-fn unify_mv(st: &mut St, mv: MetaTyVar, mut ty: Ty) -> Result<(), UnifyError> {
+fn unify_mv(cx: &mut Cx<'_>, mv: MetaTyVar, mut ty: Ty) -> Result<(), UnifyError> {
   // return without doing anything if the meta vars are the same.
   if let Ty::MetaVar(mv2) = &ty {
     if mv == *mv2 {
@@ -104,31 +124,31 @@ fn unify_mv(st: &mut St, mv: MetaTyVar, mut ty: Ty) -> Result<(), UnifyError> {
   // tweak down the rank of all other meta vars in the ty.
   let mut map = FxHashMap::<MetaTyVar, (MetaTyVar, Option<TyVarKind>)>::default();
   meta_vars(
-    &st.subst,
+    cx.subst,
     &mut |x, k| {
       // this is crucial!
       if x.rank() > mv.rank() {
-        map.insert(x, (st.meta_gen.gen_same_rank(mv), k.cloned()));
+        map.insert(x, (cx.meta_gen.gen_same_rank(mv), k.cloned()));
       }
     },
     &ty,
   );
   for (k, (v, kind)) in map {
-    st.subst.insert(k, SubstEntry::Solved(Ty::MetaVar(v)));
+    cx.subst.insert(k, SubstEntry::Solved(Ty::MetaVar(v)));
     if let Some(kind) = kind {
-      st.subst.insert(v, SubstEntry::Kind(kind));
+      cx.subst.insert(v, SubstEntry::Kind(kind));
     }
   }
-  apply(&st.subst, &mut ty);
+  apply(cx.subst, &mut ty);
   // solve mv to ty. however, mv may already have an entry.
-  match st.subst.insert(mv, SubstEntry::Solved(ty.clone())) {
+  match cx.subst.insert(mv, SubstEntry::Solved(ty.clone())) {
     // do nothing if no entry.
     None => {}
     // unreachable because we applied upon entry.
     Some(SubstEntry::Solved(ty)) => unreachable!("meta var already solved to {ty:?}"),
     Some(SubstEntry::Kind(kind)) => match kind {
       TyVarKind::Equality => {
-        if let Some(not_eq) = equality::get(&mut st.subst, &ty) {
+        if let Some(not_eq) = equality::get(cx.subst, &ty) {
           return Err(MismatchedTypesFlavor::NotEqTy(ty.clone(), not_eq).into());
         }
       }
@@ -138,7 +158,7 @@ fn unify_mv(st: &mut St, mv: MetaTyVar, mut ty: Ty) -> Result<(), UnifyError> {
         Ty::None => {}
         // the simple case. check the sym is in the overload.
         Ty::Con(args, s) => {
-          if ov.as_basics().iter().any(|&ov| st.syms.overloads()[ov].contains(&s)) {
+          if ov.as_basics().iter().any(|&ov| cx.syms.overloads()[ov].contains(&s)) {
             assert!(args.is_empty());
           } else {
             return Err(MismatchedTypesFlavor::OverloadCon(ov, s).into());
@@ -147,13 +167,13 @@ fn unify_mv(st: &mut St, mv: MetaTyVar, mut ty: Ty) -> Result<(), UnifyError> {
         // we solved mv = mv2. now we give mv2 mv's old entry, to make it an overloaded ty var.
         // but mv2 itself may also have an entry.
         Ty::MetaVar(mv2) => {
-          let ov = match &mut st.subst.get(mv2) {
+          let ov = match cx.subst.get(mv2) {
             // it didn't have an entry.
             None => ov,
             // unreachable because of apply.
             Some(SubstEntry::Solved(ty)) => unreachable!("meta var already solved to {ty:?}"),
             Some(SubstEntry::Kind(kind)) => match kind {
-              TyVarKind::Equality => match equality::get(&mut st.subst, &Ty::MetaVar(mv)) {
+              TyVarKind::Equality => match equality::get(cx.subst, &Ty::MetaVar(mv)) {
                 Some(not_eq) => {
                   return Err(MismatchedTypesFlavor::NotEqTy(Ty::MetaVar(mv), not_eq).into())
                 }
@@ -171,7 +191,7 @@ fn unify_mv(st: &mut St, mv: MetaTyVar, mut ty: Ty) -> Result<(), UnifyError> {
             },
           };
           let k = SubstEntry::Kind(TyVarKind::Overloaded(ov));
-          st.subst.insert(mv2, k);
+          cx.subst.insert(mv2, k);
         }
         // none of these are overloaded types.
         Ty::BoundVar(_) | Ty::FixedVar(_) | Ty::Record(_) | Ty::Fn(_, _) => {
@@ -189,7 +209,7 @@ fn unify_mv(st: &mut St, mv: MetaTyVar, mut ty: Ty) -> Result<(), UnifyError> {
           for (lab, want) in want_rows {
             match got_rows.remove(&lab) {
               None => return Err(MismatchedTypesFlavor::UnresolvedRecordMissingRow(lab).into()),
-              Some(got) => unify_(st, want, got)?,
+              Some(got) => unify_(cx, want, got)?,
             }
           }
         }
@@ -197,14 +217,14 @@ fn unify_mv(st: &mut St, mv: MetaTyVar, mut ty: Ty) -> Result<(), UnifyError> {
         // setting mv2's entry to mv's old entry, which specifies the rows for this record ty
         // var.
         Ty::MetaVar(mv2) => {
-          match &mut st.subst.get(mv2) {
+          match cx.subst.get(mv2) {
             // there was no entry.
             None => {}
             // unreachable because of apply.
             Some(SubstEntry::Solved(ty)) => unreachable!("meta var already solved to {ty:?}"),
             Some(SubstEntry::Kind(kind)) => match kind {
               TyVarKind::Equality => {
-                if let Some(not_eq) = equality::get(&mut st.subst, &Ty::MetaVar(mv)) {
+                if let Some(not_eq) = equality::get(cx.subst, &Ty::MetaVar(mv)) {
                   return Err(MismatchedTypesFlavor::NotEqTy(Ty::MetaVar(mv), not_eq).into());
                 }
               }
@@ -217,8 +237,8 @@ fn unify_mv(st: &mut St, mv: MetaTyVar, mut ty: Ty) -> Result<(), UnifyError> {
               TyVarKind::Record(other_rows) => {
                 for (lab, mut want) in other_rows.clone() {
                   if let Some(got) = want_rows.get(&lab) {
-                    unify_(st, want.clone(), got.clone())?;
-                    apply(&st.subst, &mut want);
+                    unify_(cx, want.clone(), got.clone())?;
+                    apply(cx.subst, &mut want);
                   }
                   want_rows.insert(lab, want);
                 }
@@ -227,7 +247,7 @@ fn unify_mv(st: &mut St, mv: MetaTyVar, mut ty: Ty) -> Result<(), UnifyError> {
           }
           // set the entry to make mv2 a record ty var.
           let k = SubstEntry::Kind(TyVarKind::Record(want_rows));
-          st.subst.insert(mv2, k);
+          cx.subst.insert(mv2, k);
         }
         // none of these are record types.
         Ty::BoundVar(_) | Ty::FixedVar(_) | Ty::Con(_, _) | Ty::Fn(_, _) => {
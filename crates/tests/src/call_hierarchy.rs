@@ -0,0 +1,108 @@
+//! Calls between functions.
+
+use crate::check::Project;
+
+const A: &str = r#"fun leaf x = x
+fun mid y = leaf (leaf y)
+val top = fn z => mid z
+"#;
+
+const B: &str = r#"fun other w = leaf (mid w) + List.length [w]
+val v = leaf 3
+fun outer n = let val inner = leaf n in inner end
+"#;
+
+fn project() -> Project {
+  Project::new(
+    &[("sources.mlb", "$(SML_LIB)/basis/basis.mlb a.sml b.sml"), ("a.sml", A), ("b.sml", B)],
+    None,
+  )
+}
+
+/// returns the calls as sorted lines of the other item's name and file, then the zero-based line
+/// and character of each call. also checks the ranges of each item make sense.
+fn render(
+  p: &Project,
+  calls: Vec<analysis::CallHierarchyCall>,
+  caller_file: Option<&str>,
+) -> String {
+  let mut lines: Vec<_> = calls
+    .into_iter()
+    .map(|call| {
+      let file = item_file(p, &call.item);
+      let mut ranges: Vec<_> = call
+        .ranges
+        .iter()
+        .map(|r| {
+          let name = p.text(caller_file.unwrap_or(file), *r);
+          format!("{}:{} {name}", r.start.line, r.start.character)
+        })
+        .collect();
+      ranges.sort_unstable();
+      format!("{} {file} {}\n", call.item.name, ranges.join(", "))
+    })
+    .collect();
+  lines.sort_unstable();
+  lines.concat()
+}
+
+/// returns the file of the item, after checking its ranges.
+fn item_file<'p>(p: &'p Project, item: &analysis::CallHierarchyItem) -> &'p str {
+  let file = match item.path {
+    analysis::DefPath::Regular(x) => p.name(x),
+    analysis::DefPath::BuiltinLib(x) => panic!("item in built-in file {x}"),
+  };
+  assert_eq!(p.text(file, item.selection_range), item.name);
+  assert!(item.range.start <= item.selection_range.start);
+  assert!(item.selection_range.end <= item.range.end);
+  file
+}
+
+#[test]
+fn prepare() {
+  let p = project();
+  let item = p.an().prepare_call_hierarchy(p.pos("a.sml", "leaf x")).expect("no item");
+  assert_eq!(item_file(&p, &item), "a.sml");
+  assert_eq!(p.text("a.sml", item.range).trim_end(), "leaf x = x");
+  let item = p.an().prepare_call_hierarchy(p.pos("b.sml", "leaf")).expect("no item");
+  assert_eq!(item_file(&p, &item), "a.sml");
+  assert_eq!(item.name, "leaf");
+  let item = p.an().prepare_call_hierarchy(p.pos("a.sml", "top")).expect("no item");
+  assert_eq!(p.text("a.sml", item.range).trim_end(), "top = fn z => mid z");
+  assert!(p.an().prepare_call_hierarchy(p.pos("a.sml", "fun")).is_none());
+}
+
+#[test]
+fn incoming() {
+  let p = project();
+  let got = p.an().incoming_calls(p.pos("a.sml", "leaf")).expect("no calls");
+  let want = r#"
+inner b.sml 2:30 leaf
+mid a.sml 1:12 leaf, 1:18 leaf
+other b.sml 0:14 leaf
+v b.sml 1:8 leaf
+"#;
+  pretty_assertions::assert_str_eq!(want.trim_start(), render(&p, got, None));
+  let got = p.an().incoming_calls(p.pos("b.sml", "other")).expect("no calls");
+  assert!(got.is_empty());
+}
+
+#[test]
+fn outgoing() {
+  let p = project();
+  let got = p.an().outgoing_calls(p.pos("a.sml", "mid")).expect("no calls");
+  let want = r#"
+leaf a.sml 1:12 leaf, 1:18 leaf
+"#;
+  pretty_assertions::assert_str_eq!(want.trim_start(), render(&p, got, Some("a.sml")));
+  // calls to the std basis and primitives are left out.
+  let got = p.an().outgoing_calls(p.pos("b.sml", "other")).expect("no calls");
+  let want = r#"
+leaf a.sml 0:14 leaf
+mid a.sml 0:20 mid
+"#;
+  pretty_assertions::assert_str_eq!(want.trim_start(), render(&p, got, Some("b.sml")));
+  // calls in a nested binding belong to it.
+  let got = p.an().outgoing_calls(p.pos("b.sml", "outer")).expect("no calls");
+  assert!(got.is_empty());
+}
@@ -1,9 +1,11 @@
 //! Test infra.
 
+use crate::input::get_input;
 use diagnostic_util::Severity;
 use fast_hash::FxHashMap;
 use once_cell::sync::Lazy;
 use paths::FileSystem as _;
+use std::collections::BTreeMap;
 use std::fmt::{self, Write as _};
 
 /// Given the string of an SML program with some expectation comments, panics iff the expectation
@@ -73,16 +75,47 @@ pub(crate) fn check_with_std_basis(s: &str) {
   go(&[s], analysis::StdBasis::Full, Outcome::Pass, Severity::Error);
 }
 
+/// Like [`check`], but includes the full std basis, and checks these files, given by name relative
+/// to the [`ROOT`] and contents, with the config, if any.
+///
+/// Unlike the others, this does not make a group file asking for every built-in library. The files
+/// should include a group file, and only the built-in libraries it asks for are available.
+#[track_caller]
+pub(crate) fn check_project(files: &[(&str, &str)], config: Option<&str>) {
+  go_files(files, config, analysis::StdBasis::Full, Outcome::Pass, Severity::Error);
+}
+
 /// The low-level impl that all top-level functions delegate to.
 pub(crate) fn go(
   ss: &[&str],
   std_basis: analysis::StdBasis,
   want: Outcome,
   min_severity: Severity,
+) {
+  let mut mlb_file = String::new();
+  for lib in BUILTIN_LIBS {
+    writeln!(mlb_file, "{lib}").unwrap();
+  }
+  let names: Vec<_> = (0..ss.len()).map(|idx| format!("f{idx}.sml")).collect();
+  for name in &names {
+    writeln!(mlb_file, "{name}").unwrap();
+  }
+  let files: Vec<_> = std::iter::once(("sources.mlb", mlb_file.as_str()))
+    .chain(names.iter().map(String::as_str).zip(ss.iter().copied()))
+    .collect();
+  go_files(&files, None, std_basis, want, min_severity);
+}
+
+fn go_files(
+  files: &[(&str, &str)],
+  config: Option<&str>,
+  std_basis: analysis::StdBasis,
+  want: Outcome,
+  min_severity: Severity,
 ) {
   // ignore the Err if we already initialized logging, since that's fine.
   let _ = env_logger::builder().is_test(true).try_init();
-  let c = Check::new(ss, std_basis, min_severity);
+  let c = Check::new(files, config, std_basis, min_severity);
   match (want, c.reasons.is_empty()) {
     (Outcome::Pass, true) | (Outcome::Fail, false) => {}
     (Outcome::Pass, false) => panic!("UNEXPECTED FAIL: {c}"),
@@ -96,6 +129,187 @@ pub(crate) fn go(
 pub(crate) static ROOT: Lazy<paths::CanonicalPathBuf> =
   Lazy::new(|| paths::RealFileSystem::default().canonicalize(std::path::Path::new("/")).unwrap());
 
+/// A project of files, given by name relative to the [`ROOT`], for tests that call into analysis
+/// directly. Analyzed with the full std basis.
+///
+/// The positions and ranges assume the files are ASCII.
+pub(crate) struct Project {
+  files: Vec<(String, String)>,
+  config: Option<String>,
+  store: paths::Store,
+  input: analysis::input::Input,
+  an: analysis::Analysis,
+}
+
+impl Project {
+  /// Returns a new project with these files and config, after analyzing it once.
+  pub(crate) fn new(files: &[(&str, &str)], config: Option<&str>) -> Self {
+    Self::with(files, config, analysis::StdBasis::Full, |_| {})
+  }
+
+  /// Like [`Self::new`], but with this std basis.
+  pub(crate) fn with_std_basis(
+    files: &[(&str, &str)],
+    config: Option<&str>,
+    std_basis: analysis::StdBasis,
+  ) -> Self {
+    Self::with(files, config, std_basis, |_| {})
+  }
+
+  /// Like [`Self::new`], but parsing source files with this many threads.
+  pub(crate) fn with_threads(files: &[(&str, &str)], config: Option<&str>, threads: usize) -> Self {
+    Self::with(files, config, analysis::StdBasis::Full, |an| an.set_threads(threads))
+  }
+
+  fn with<F>(
+    files: &[(&str, &str)],
+    config: Option<&str>,
+    std_basis: analysis::StdBasis,
+    setup: F,
+  ) -> Self
+  where
+    F: FnOnce(&mut analysis::Analysis),
+  {
+    // ignore the Err if we already initialized logging, since that's fine.
+    let _ = env_logger::builder().is_test(true).try_init();
+    let files: Vec<_> = files.iter().map(|&(a, b)| (a.to_owned(), b.to_owned())).collect();
+    let config = config.map(ToOwned::to_owned);
+    let mut store = paths::Store::new();
+    let input =
+      get_input(&mut store, files.iter().map(|(a, b)| (a.as_str(), b.as_str())), config.as_deref())
+        .expect("invalid MemoryFileSystem");
+    let mut an = analysis::Analysis::new(
+      std_basis,
+      config::ErrorLines::One,
+      config::DiagnosticsFilter::None,
+      false,
+    );
+    setup(&mut an);
+    let mut ret = Self { files, config, store, input, an };
+    ret.diagnostics();
+    ret
+  }
+
+  pub(crate) fn an(&self) -> &analysis::Analysis {
+    &self.an
+  }
+
+  pub(crate) fn input(&self) -> &analysis::input::Input {
+    &self.input
+  }
+
+  /// Sets the contents of the file with this name, or removes it if `contents` is `None`. The
+  /// project must be analyzed again to see the change.
+  pub(crate) fn set(&mut self, name: &str, contents: Option<&str>) {
+    self.files.retain(|(n, _)| n != name);
+    if let Some(contents) = contents {
+      self.files.push((name.to_owned(), contents.to_owned()));
+    }
+    self.input = get_input(
+      &mut self.store,
+      self.files.iter().map(|(a, b)| (a.as_str(), b.as_str())),
+      self.config.as_deref(),
+    )
+    .expect("invalid MemoryFileSystem");
+  }
+
+  /// Analyzes the project, and returns the diagnostics, keyed and sorted by file name.
+  pub(crate) fn diagnostics(&mut self) -> BTreeMap<String, Vec<String>> {
+    let ds = self.an.get_many(&self.input);
+    ds.into_iter()
+      .filter(|(_, ds)| !ds.is_empty())
+      .map(|(path, ds)| {
+        let ds = ds.into_iter().map(|d| format!("{:?}: {}", d.range, d.message)).collect();
+        (self.name(path).to_owned(), ds)
+      })
+      .collect()
+  }
+
+  /// Returns the id of the source file with this name.
+  pub(crate) fn path(&self, name: &str) -> paths::PathId {
+    let want = ROOT.as_path().join(name);
+    self
+      .input
+      .iter_sources()
+      .find_map(|s| (self.store.get_path(s.path).as_path() == want).then_some(s.path))
+      .expect("no source file with that name")
+  }
+
+  /// Returns the name of the file with this id.
+  pub(crate) fn name(&self, path: paths::PathId) -> &str {
+    let path = self.store.get_path(path).as_path().strip_prefix(ROOT.as_path());
+    path.expect("not under the root").to_str().expect("not UTF-8")
+  }
+
+  /// Returns the contents of the file with this name.
+  pub(crate) fn contents(&self, name: &str) -> &str {
+    let contents = self.files.iter().find_map(|(n, c)| (n == name).then_some(c.as_str()));
+    contents.expect("no file with that name")
+  }
+
+  /// Returns the position of the start of the first occurrence of `needle` in the file with this
+  /// name.
+  pub(crate) fn pos(&self, name: &str, needle: &str) -> paths::WithPath<text_pos::Position> {
+    let contents = self.contents(name);
+    let idx = contents.find(needle).expect("no such needle");
+    let before = &contents[..idx];
+    let line = before.matches('\n').count();
+    let character = before.len() - before.rfind('\n').map_or(0, |x| x + 1);
+    let pos = text_pos::Position {
+      line: u32::try_from(line).unwrap(),
+      character: u32::try_from(character).unwrap(),
+    };
+    self.path(name).wrap(pos)
+  }
+
+  /// Returns the text in the range in the file with this name.
+  pub(crate) fn text(&self, name: &str, range: text_pos::Range) -> &str {
+    &self.contents(name)[self.offset(name, range.start)..self.offset(name, range.end)]
+  }
+
+  /// Returns the contents of the file with this name after replacing the ranges with the texts.
+  pub(crate) fn edit<'a, I>(&self, name: &str, edits: I) -> String
+  where
+    I: IntoIterator<Item = (text_pos::Range, &'a str)>,
+  {
+    let contents = self.contents(name);
+    let mut edits: Vec<_> = edits
+      .into_iter()
+      .map(|(r, s)| (self.offset(name, r.start), self.offset(name, r.end), s))
+      .collect();
+    edits.sort_unstable_by_key(|&(start, end, _)| (start, end));
+    let mut ret = contents.to_owned();
+    for (start, end, s) in edits.into_iter().rev() {
+      ret.replace_range(start..end, s);
+    }
+    ret
+  }
+
+  /// Returns the text in the range in the built-in library file with this name.
+  pub(crate) fn builtin_text(&self, name: &str, range: text_pos::Range) -> &str {
+    let contents = self.an.builtin_file_contents(name).expect("no built-in file with that name");
+    &contents[offset(contents, range.start)..offset(contents, range.end)]
+  }
+
+  fn offset(&self, name: &str, pos: text_pos::Position) -> usize {
+    offset(self.contents(name), pos)
+  }
+}
+
+fn offset(contents: &str, pos: text_pos::Position) -> usize {
+  let line_start: usize =
+    contents.split_inclusive('\n').take(usize::try_from(pos.line).unwrap()).map(str::len).sum();
+  line_start + usize::try_from(pos.character).unwrap()
+}
+
+/// Paths asking for all the built-in libraries.
+const BUILTIN_LIBS: [&str; 4] = [
+  "$(SML_LIB)/basis/basis.mlb",
+  "$(SML_LIB)/basis/sml-nj.mlb",
+  "$(SML_LIB)/basis/mlton.mlb",
+  "$(SML_LIB)/smlnj-lib/Util/smlnj-lib.mlb",
+];
+
 struct Check {
   store: paths::Store,
   files: paths::PathMap<ExpectFile>,
@@ -103,20 +317,15 @@ struct Check {
 }
 
 impl Check {
-  fn new(ss: &[&str], std_basis: analysis::StdBasis, min_severity: Severity) -> Self {
-    let mut m = FxHashMap::<std::path::PathBuf, String>::default();
-    let mut mlb_file = String::new();
-    for (idx, &s) in ss.iter().enumerate() {
-      let file_name = format!("f{idx}.sml");
-      writeln!(mlb_file, "{file_name}").unwrap();
-      let file_name = std::path::PathBuf::from(file_name);
-      m.insert(ROOT.as_path().join(file_name), s.to_owned());
-    }
-    m.insert(ROOT.as_path().join("sources.mlb"), mlb_file);
-    let fs = paths::MemoryFileSystem::new(m);
+  fn new(
+    files: &[(&str, &str)],
+    config: Option<&str>,
+    std_basis: analysis::StdBasis,
+    min_severity: Severity,
+  ) -> Self {
     let mut store = paths::Store::new();
     let input =
-      analysis::input::Input::new(&fs, &mut store, &ROOT).expect("invalid MemoryFileSystem");
+      get_input(&mut store, files.iter().copied(), config).expect("invalid MemoryFileSystem");
     let mut ret = Self {
       store,
       files: input
@@ -0,0 +1,75 @@
+//! Code lenses above top-level declarations.
+
+use crate::check::Project;
+use analysis::CodeLensKind;
+
+/// checks the lenses in the file are `want`, given by the name they are above and their kind, in
+/// order.
+#[track_caller]
+fn check(s: &str, want: &[(&str, CodeLensKind)]) {
+  let p = Project::new(&[("sources.mlb", "a.sml"), ("a.sml", s)], None);
+  let lenses = p.an().code_lenses(p.path("a.sml")).expect("should have lenses");
+  let got: Vec<_> = lenses.iter().map(|lens| (p.text("a.sml", lens.range), lens.kind)).collect();
+  assert_eq!(want, got);
+}
+
+#[test]
+fn placement() {
+  check(
+    r#"
+fun f x = x + 1
+val g = fn x => x + 1
+val h = 3
+structure S = struct fun inner x = x end
+functor F () = struct end
+datatype d = D
+"#,
+    &[
+      ("f", CodeLensKind::Type),
+      ("f", CodeLensKind::References),
+      ("g", CodeLensKind::Type),
+      ("g", CodeLensKind::References),
+      ("h", CodeLensKind::References),
+      ("S", CodeLensKind::References),
+      ("F", CodeLensKind::References),
+    ],
+  );
+}
+
+#[test]
+fn annotated() {
+  check(
+    r#"
+fun a (x : int) = x
+fun b x : int = x
+fun c {x : int} = x
+val d = fn (x : int) => x
+val e : int -> int = fn x => x
+"#,
+    &[
+      ("a", CodeLensKind::References),
+      ("b", CodeLensKind::References),
+      ("c", CodeLensKind::References),
+      ("d", CodeLensKind::References),
+      ("e", CodeLensKind::References),
+    ],
+  );
+}
+
+#[test]
+fn resolve() {
+  let p = Project::new(
+    &[
+      ("sources.mlb", "a.sml b.sml"),
+      ("a.sml", "fun id x = x\nfun inc x = x + 1\nval _ = inc 1"),
+      ("b.sml", "val _ = id (inc 2)\nval _ = id"),
+    ],
+    None,
+  );
+  let refs = |at| p.an().get_refs(p.pos("a.sml", at), false).expect("should have refs").len();
+  assert_eq!(refs("id"), 2);
+  assert_eq!(refs("inc"), 2);
+  let ty = |at| p.an().get_inferred_ty(p.pos("a.sml", at)).expect("should have a type");
+  assert_eq!(ty("id"), "id : 'a -> 'a");
+  assert_eq!(ty("inc"), "inc : int -> int");
+}
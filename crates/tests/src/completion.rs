@@ -0,0 +1,149 @@
+//! Completing names.
+
+use crate::check::Project;
+
+/// the files have no basis, so the only names in scope are the ones in the file. returns the
+/// completions just after the first occurrence of `before` in the file, as pairs of
+/// (label, detail), sorted by label.
+#[track_caller]
+fn completions(s: &str, before: &str) -> Vec<(String, Option<String>)> {
+  let p = Project::new(&[("sources.mlb", "a.sml"), ("a.sml", s)], None);
+  let mut pos = p.pos("a.sml", before);
+  pos.val.character += u32::try_from(before.len()).unwrap();
+  let mut ret: Vec<_> = p
+    .an()
+    .completions(pos)
+    .expect("no completions")
+    .into_iter()
+    .map(|item| (item.label, item.detail))
+    .collect();
+  ret.sort_unstable();
+  ret
+}
+
+#[track_caller]
+fn labels(s: &str, before: &str) -> Vec<String> {
+  completions(s, before).into_iter().map(|(label, _)| label).collect()
+}
+
+#[test]
+fn let_and_params() {
+  let got = labels(
+    r#"
+val top = 1
+fun f param = let val inner = param in inn end
+val later = 2
+"#,
+    "in inn",
+  );
+  assert_eq!(got, ["f", "inner", "param", "top"]);
+}
+
+#[test]
+fn fn_arm() {
+  let got = labels(
+    r#"
+val g = fn (arg, _) => ar | _ => 3
+"#,
+    "=> ar",
+  );
+  assert_eq!(got, ["arg"]);
+}
+
+#[test]
+fn not_yet_declared() {
+  let got = labels(
+    r#"
+val early = lat
+val late = 2
+"#,
+    "= lat",
+  );
+  assert!(got.is_empty(), "got {got:?}");
+}
+
+#[test]
+fn shadowed() {
+  let got = completions(
+    r#"
+val x = 1
+val x = "hi"
+val _ = x
+"#,
+    "_ = x",
+  );
+  assert_eq!(got, [("x".to_owned(), Some("string".to_owned()))]);
+}
+
+#[test]
+fn structure_members() {
+  let got = labels(
+    r#"
+structure Foo = struct
+  val bar = 1
+  fun baz x = x
+  structure Inner = struct end
+  type t = int
+end
+val top = 3
+val _ = Foo.b
+"#,
+    "Foo.b",
+  );
+  assert_eq!(got, ["Inner", "bar", "baz", "t"]);
+}
+
+#[test]
+fn nested_structure_members() {
+  let got = labels(
+    r#"
+structure Foo = struct
+  structure Inner = struct val deep = 1 end
+  val shallow = 2
+end
+val _ = Foo.Inner.d
+"#,
+    "Inner.d",
+  );
+  assert_eq!(got, ["deep"]);
+}
+
+#[test]
+fn signatures_and_functors() {
+  let got = labels(
+    r#"
+signature SIG = sig end
+functor Fun (S : SIG) = struct end
+structure S = Fu
+"#,
+    "= Fu",
+  );
+  assert_eq!(got, ["Fun", "SIG"]);
+}
+
+#[test]
+fn record_exp_fn_arg() {
+  let got = completions(
+    r#"
+fun h {alpha = 1, beta = "s"} = ()
+val _ = h {alpha = 1, beta = "x"}
+"#,
+    "h {",
+  );
+  assert_eq!(
+    got,
+    [("alpha".to_owned(), Some("int".to_owned())), ("beta".to_owned(), Some("string".to_owned()))]
+  );
+}
+
+#[test]
+fn record_pat() {
+  let got = labels(
+    r#"
+val r = {x = 1, y = "s"}
+val _ = case r of {x, y} => x
+"#,
+    "of {",
+  );
+  assert_eq!(got, ["x", "y"]);
+}
@@ -0,0 +1,90 @@
+//! Highlighting the occurrences of an item in a document.
+
+use crate::check::Project;
+use std::fmt::Write as _;
+
+const FILE: &str = r#"val x = 1
+val y = x + x
+val x = "s"
+val z = x
+fun 'a id (v : 'a) : 'a = v
+val w = fn (q : 'b) => q
+type r = {lab : int}
+val rv : r = {lab = 1}
+val {lab} = rv
+val _ = #lab rv
+"#;
+
+fn project() -> Project {
+  Project::new(&[("sources.mlb", "$(SML_LIB)/basis/basis.mlb a.sml"), ("a.sml", FILE)], None)
+}
+
+/// returns the highlights for the first occurrence of `at`, as lines of the zero-based line and
+/// character, and kind. also checks the text of each is the text at `at`.
+#[track_caller]
+fn check(at: &str, want: &str) {
+  let p = project();
+  let mut got = String::new();
+  for highlight in p.an().document_highlights(p.pos("a.sml", at)).expect("no highlights") {
+    let range = highlight.range;
+    assert!(at.starts_with(p.text("a.sml", range)));
+    writeln!(got, "{}:{} {:?}", range.start.line, range.start.character, highlight.kind).unwrap();
+  }
+  pretty_assertions::assert_str_eq!(want.trim_start(), got);
+}
+
+#[test]
+fn shadowed_value() {
+  check(
+    "x = 1",
+    r#"
+0:4 Write
+1:8 Read
+1:12 Read
+"#,
+  );
+  check(
+    "x = \"s\"",
+    r#"
+2:4 Write
+3:8 Read
+"#,
+  );
+}
+
+#[test]
+fn ty_var() {
+  check(
+    "'a id",
+    r#"
+4:4 Write
+4:15 Read
+4:21 Read
+"#,
+  );
+  check(
+    "'b",
+    r#"
+5:16 Read
+"#,
+  );
+}
+
+#[test]
+fn label() {
+  check(
+    "lab",
+    r#"
+6:10 Text
+7:14 Write
+8:5 Read
+9:9 Read
+"#,
+  );
+}
+
+#[test]
+fn keyword() {
+  let p = project();
+  assert!(p.an().document_highlights(p.pos("a.sml", "val")).is_none());
+}
@@ -0,0 +1,96 @@
+//! Symbols in a document, for an outline.
+
+use crate::check::Project;
+use std::fmt::Write as _;
+
+/// checks the symbols in the file are `want`, written one per line as the kind and name, indented
+/// by two spaces per level of nesting. also checks the ranges of each symbol make sense.
+#[track_caller]
+fn check(s: &str, want: &str) {
+  let p = Project::new(&[("sources.mlb", "a.sml"), ("a.sml", s)], None);
+  let symbols = p.an().document_symbols(p.path("a.sml")).expect("no symbols");
+  let mut got = String::new();
+  go(&p, &mut got, 0, &symbols);
+  pretty_assertions::assert_str_eq!(want.trim_start(), got);
+}
+
+fn go(p: &Project, ac: &mut String, level: usize, symbols: &[analysis::DocumentSymbol]) {
+  for symbol in symbols {
+    assert_eq!(p.text("a.sml", symbol.selection_range), symbol.name);
+    assert!(symbol.range.start <= symbol.selection_range.start);
+    assert!(symbol.selection_range.end <= symbol.range.end);
+    for child in &symbol.children {
+      assert!(symbol.range.start <= child.range.start && child.range.end <= symbol.range.end);
+    }
+    writeln!(ac, "{}{:?} {}", "  ".repeat(level), symbol.kind, symbol.name).unwrap();
+    go(p, ac, level + 1, &symbol.children);
+  }
+}
+
+#[test]
+fn structure() {
+  check(
+    r#"
+structure S = struct
+  val x = 1
+  fun f y = y
+  val g = fn z => z
+  datatype d = A | B of int
+  type t = int
+  exception E
+end
+"#,
+    r#"
+Structure S
+  Value x
+  Function f
+  Function g
+  Datatype d
+    Constructor A
+    Constructor B
+  Type t
+  Exception E
+"#,
+  );
+}
+
+#[test]
+fn signature_and_functor() {
+  check(
+    r#"
+signature SIG = sig
+  val v : int
+  structure T : sig type u end
+end
+functor F (X : SIG) = struct val w = X.v end
+"#,
+    r#"
+Signature SIG
+  Value v
+  Structure T
+    Type u
+Functor F
+  Structure X
+  Value w
+"#,
+  );
+}
+
+#[test]
+fn patterns_and_local() {
+  check(
+    r#"
+local
+  val (p, {q, r = s}) = (1, {q = 2, r = 3})
+in
+  val t = p
+end
+"#,
+    r#"
+Value p
+Value q
+Value s
+Value t
+"#,
+  );
+}
@@ -0,0 +1,63 @@
+//! Filling expression holes with values in scope.
+
+use crate::check::Project;
+
+/// checks the suggestions for filling the first `...` hole in the file, which is in a group without
+/// the std basis, are `want`, in order.
+#[track_caller]
+fn check(s: &str, want: &[&str]) {
+  let p = Project::new(&[("sources.mlb", "a.sml"), ("a.sml", s)], None);
+  let got = p.an().get_hole_fills(p.pos("a.sml", "...")).expect("should be a hole");
+  assert_eq!(want, got);
+}
+
+#[test]
+fn ranking() {
+  check(
+    r#"
+datatype t = A | B of t
+datatype u = U
+val a = A
+fun mk (_: u) = A
+fun pair (x: t) (_: u) = B x
+fun id x = x
+val _: t = ...
+"#,
+    &["A", "a", "B _", "mk _", "id _", "pair _ _"],
+  );
+}
+
+#[test]
+fn arg() {
+  check(
+    r#"
+datatype t = A | B of t
+val _ = B ...
+"#,
+    &["A", "(B _)"],
+  );
+}
+
+#[test]
+fn unconstrained() {
+  check(
+    r#"
+datatype t = A
+val _ = ...
+"#,
+    &[],
+  );
+}
+
+#[test]
+fn user_infix() {
+  check(
+    r#"
+datatype t = A
+infix <+>
+fun (_: t) <+> (_: t) = A
+val _: t * t -> t = ...
+"#,
+    &["op <+>"],
+  );
+}
@@ -0,0 +1,83 @@
+//! Ranges to fold and to select.
+
+use crate::check::Project;
+use std::fmt::Write as _;
+use text_pos::Position;
+
+const FILE: &str = r#"structure S = struct
+  val x =
+    let
+      val y = 1
+    in
+      y
+    end
+  val z = case x of
+    1 => 2
+  | _ => 3
+end
+(* a
+   comment *)
+val one = let in 1 end
+local
+  val hidden = 1
+in
+  val shown = hidden
+end
+signature SIG = sig
+  val v : int
+end
+"#;
+
+fn project() -> Project {
+  Project::new(&[("sources.mlb", "$(SML_LIB)/basis/basis.mlb a.sml"), ("a.sml", FILE)], None)
+}
+
+#[test]
+fn folding() {
+  let p = project();
+  let mut got = String::new();
+  for folding in p.an().folding_ranges(p.path("a.sml")).expect("no folding ranges") {
+    let range = folding.range;
+    let word = p.text("a.sml", range).split_whitespace().next().expect("empty range");
+    writeln!(got, "{}-{} {:?} {word}", range.start.line, range.end.line, folding.kind).unwrap();
+  }
+  let want = r#"
+0-10 Region struct
+2-6 Region let
+7-9 Region case
+11-12 Comment (*
+14-18 Region local
+19-21 Region sig
+"#;
+  pretty_assertions::assert_str_eq!(want.trim_start(), got);
+}
+
+#[test]
+fn selection() {
+  let p = project();
+  let positions = [
+    Position { line: 5, character: 6 },
+    Position { line: 15, character: 6 },
+    Position { line: 100, character: 0 },
+  ];
+  let got = p.an().selection_ranges(p.path("a.sml"), &positions).expect("no selection ranges");
+  assert_eq!(got.len(), positions.len());
+  let texts = |ranges: &[text_pos::Range]| -> Vec<String> {
+    assert!(!ranges.is_empty());
+    // each range strictly contains the one before it.
+    for w in ranges.windows(2) {
+      assert!(w[1].start <= w[0].start && w[0].end <= w[1].end && w[0] != w[1]);
+    }
+    let last = ranges.last().unwrap();
+    assert_eq!(last.start, Position { line: 0, character: 0 });
+    ranges.iter().map(|&r| p.text("a.sml", r).to_owned()).collect()
+  };
+  let y = texts(&got[0]);
+  assert_eq!(y[0], "y");
+  assert!(y.iter().any(|s| s.starts_with("let") && s.ends_with("end")));
+  assert!(y.iter().any(|s| s.starts_with("struct") && s.ends_with("end")));
+  let hidden = texts(&got[1]);
+  assert_eq!(hidden[0], "hidden");
+  assert!(hidden.iter().any(|s| s.starts_with("local") && s.ends_with("end")));
+  assert!(got[2].is_empty());
+}
@@ -0,0 +1,64 @@
+//! Going to definitions, including those in built-in library files.
+
+use crate::check::Project;
+
+const FILE: &str = r#"
+structure S = struct val x = 1 end
+val _ = S.x
+val _ = List.map
+val _ = op + (1, 2)
+val r = StringCvt.HEX
+"#;
+
+fn project() -> Project {
+  Project::new(&[("sources.mlb", "$(SML_LIB)/basis/basis.mlb a.sml"), ("a.sml", FILE)], None)
+}
+
+/// checks the def is in a built-in library file, and that its text there contains `want`.
+#[track_caller]
+fn check_builtin(p: &Project, def: Option<(analysis::DefPath, text_pos::Range)>, want: &str) {
+  let (path, range) = def.expect("no def");
+  let name = match path {
+    analysis::DefPath::BuiltinLib(x) => x,
+    analysis::DefPath::Regular(x) => panic!("def in regular file {}", p.name(x)),
+  };
+  let text = p.builtin_text(name, range);
+  assert!(text.contains(want), "{text:?} does not contain {want:?}");
+}
+
+#[test]
+fn regular() {
+  let p = project();
+  let mut pos = p.pos("a.sml", "S.x");
+  pos.val.character += 2;
+  let (path, range) = p.an().get_def(pos).expect("no def");
+  assert!(matches!(path, analysis::DefPath::Regular(x) if p.name(x) == "a.sml"));
+  assert_eq!(p.text("a.sml", range), "x");
+}
+
+#[test]
+fn builtin_val() {
+  let p = project();
+  check_builtin(&p, p.an().get_def(p.pos("a.sml", "map")), "map");
+  check_builtin(&p, p.an().get_def(p.pos("a.sml", "HEX")), "HEX");
+}
+
+#[test]
+fn builtin_ty() {
+  let p = project();
+  let defs = p.an().get_ty_defs(p.pos("a.sml", "r =")).expect("no ty defs");
+  assert_eq!(defs.len(), 1);
+  check_builtin(&p, defs.into_iter().next(), "radix");
+}
+
+#[test]
+fn primitive() {
+  let p = project();
+  assert!(p.an().get_def(p.pos("a.sml", "+")).is_none());
+}
+
+#[test]
+fn no_such_builtin_file() {
+  let p = project();
+  assert!(p.an().builtin_file_contents("no-such-file.sml").is_none());
+}
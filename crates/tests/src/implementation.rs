@@ -0,0 +1,71 @@
+//! Going between signature specs and their implementations.
+
+use crate::check::Project;
+
+const SIG: &str = r#"signature SIG = sig
+  type t
+  val v : t
+end
+"#;
+
+const STRUCTS: &str = r#"structure A : SIG = struct type t = int val v = 1 end
+structure B :> SIG = struct
+  type t = string
+  val v = "b"
+end
+val x = 3
+"#;
+
+/// returns the impls of the item at the first occurrence of `at` in `file`, as sorted pairs of
+/// (file name, zero-based line). also checks the text of each contains `name`.
+#[track_caller]
+fn impls(file: &str, at: &str, name: &str) -> Vec<(String, u32)> {
+  let p = Project::new(
+    &[
+      ("sources.mlb", "$(SML_LIB)/basis/basis.mlb a.sml b.sml"),
+      ("a.sml", SIG),
+      ("b.sml", STRUCTS),
+    ],
+    None,
+  );
+  let mut ret: Vec<_> = p
+    .an()
+    .get_impls(p.pos(file, at))
+    .expect("no impls")
+    .into_iter()
+    .map(|(path, range)| {
+      let path = match path {
+        analysis::DefPath::Regular(x) => x,
+        analysis::DefPath::BuiltinLib(x) => panic!("impl in built-in file {x}"),
+      };
+      let file = p.name(path);
+      let text = p.text(file, range);
+      assert!(text.contains(name), "{text:?} does not contain {name:?}");
+      (file.to_owned(), range.start.line)
+    })
+    .collect();
+  ret.sort_unstable();
+  ret
+}
+
+fn loc(name: &str, line: u32) -> (String, u32) {
+  (name.to_owned(), line)
+}
+
+#[test]
+fn spec_to_impls() {
+  assert_eq!(impls("a.sml", "v :", "v"), [loc("b.sml", 0), loc("b.sml", 3)]);
+  assert_eq!(impls("a.sml", "t\n", "t"), [loc("b.sml", 0), loc("b.sml", 2)]);
+}
+
+#[test]
+fn impl_to_spec() {
+  assert_eq!(impls("b.sml", "v = 1", "v"), [loc("a.sml", 2)]);
+  assert_eq!(impls("b.sml", "v = \"b\"", "v"), [loc("a.sml", 2)]);
+  assert_eq!(impls("b.sml", "t = string", "t"), [loc("a.sml", 1)]);
+}
+
+#[test]
+fn none() {
+  assert!(impls("b.sml", "x", "x").is_empty());
+}
@@ -0,0 +1,133 @@
+//! Analyzing a project again after it changes, reusing what we can from before.
+
+use crate::check::Project;
+
+/// analyzes the files, then sets the files in `changes` and analyzes them again. checks that gets
+/// the same diagnostics as analyzing the changed files from scratch, and that exactly the files in
+/// `want` have diagnostics. returns the changed project and the one from scratch.
+#[track_caller]
+fn check(
+  files: &[(&str, &str)],
+  changes: &[(&str, Option<&str>)],
+  want: &[&str],
+) -> (Project, Project) {
+  let mut p = Project::new(files, None);
+  for &(name, contents) in changes {
+    p.set(name, contents);
+  }
+  let got = p.diagnostics();
+  let mut fresh_files: Vec<_> =
+    files.iter().copied().filter(|&(name, _)| changes.iter().all(|&(n, _)| n != name)).collect();
+  fresh_files.extend(changes.iter().filter_map(|&(name, contents)| Some((name, contents?))));
+  let mut fresh = Project::new(&fresh_files, None);
+  pretty_assertions::assert_eq!(fresh.diagnostics(), got);
+  let names: Vec<_> = got.keys().map(String::as_str).collect();
+  assert_eq!(want, names.as_slice());
+  (p, fresh)
+}
+
+const MLB: &str = "$(SML_LIB)/basis/basis.mlb a.sml b.sml";
+
+#[test]
+fn upstream_edit() {
+  check(
+    &[
+      ("sources.mlb", MLB),
+      ("a.sml", "structure A = struct val x = 1 end"),
+      ("b.sml", "val y = A.x + 1"),
+    ],
+    &[("a.sml", Some(r#"structure A = struct val x = "hi" end"#))],
+    &["b.sml"],
+  );
+}
+
+#[test]
+fn upstream_edit_same_items() {
+  let (p, fresh) = check(
+    &[
+      ("sources.mlb", MLB),
+      ("a.sml", "structure A = struct val x = 1 end"),
+      ("b.sml", "val y = A.x + 1"),
+    ],
+    &[("a.sml", Some("val _ = 2\nstructure A = struct val x = 1 end"))],
+    &[],
+  );
+  // the definition moved, though nothing else about it changed.
+  let def = |p: &Project| p.an().get_def(p.pos("b.sml", "x")).expect("should have a def").1;
+  assert_eq!(def(&fresh), def(&p));
+}
+
+#[test]
+fn downstream_edit() {
+  check(
+    &[
+      ("sources.mlb", MLB),
+      ("a.sml", "structure A = struct val x = 1 end"),
+      ("b.sml", "val y = A.x + 1"),
+    ],
+    &[("b.sml", Some(r#"val y = A.x + "hi""#))],
+    &["b.sml"],
+  );
+}
+
+#[test]
+fn fixity() {
+  check(
+    &[
+      ("sources.mlb", MLB),
+      ("a.sml", "infix <+>\nfun a <+> b = a + b"),
+      ("b.sml", "val y = 1 <+> 2"),
+    ],
+    &[("a.sml", Some("fun <+> (a, b) = a + b"))],
+    &["b.sml"],
+  );
+}
+
+#[test]
+fn datatype() {
+  check(
+    &[("sources.mlb", MLB), ("a.sml", "datatype t = A | B"), ("b.sml", "fun f A = 1\n  | f B = 2")],
+    &[("a.sml", Some("datatype t = A | B | C"))],
+    &["b.sml"],
+  );
+}
+
+#[test]
+fn remove() {
+  check(
+    &[
+      ("sources.mlb", MLB),
+      ("a.sml", "structure A = struct val x = 1 end"),
+      ("b.sml", "val y = A.x + 1"),
+    ],
+    &[("sources.mlb", Some("$(SML_LIB)/basis/basis.mlb b.sml")), ("a.sml", None)],
+    &["b.sml"],
+  );
+}
+
+#[test]
+fn cm() {
+  check(
+    &[
+      ("sources.cm", "Group is $/basis.cm a.sml b.sml"),
+      ("a.sml", "structure A = struct val x = 1 end"),
+      ("b.sml", "val y = A.x + 1"),
+    ],
+    &[("a.sml", Some(r#"structure A = struct val x = "hi" end"#))],
+    &["b.sml"],
+  );
+}
+
+#[test]
+fn independent() {
+  check(
+    &[
+      ("sources.mlb", "$(SML_LIB)/basis/basis.mlb local a.sml in end local c.sml in end b.sml"),
+      ("a.sml", "datatype t = A | B"),
+      ("c.sml", "datatype u = C | D"),
+      ("b.sml", "structure S = struct datatype v = E end"),
+    ],
+    &[("a.sml", Some("datatype t = A | B | Z"))],
+    &[],
+  );
+}
@@ -0,0 +1,58 @@
+//! Hints for the inferred types of bindings.
+
+use crate::check::Project;
+use std::fmt::Write as _;
+use text_pos::{Position, Range};
+
+const FILE: &str = r#"val n = 1
+val (s, t : string) = ("a", "b")
+val (u, v) : int * bool = (1, true)
+fun f (p, q : int) r = p + q + r
+val g = fn (k : real) => k | k2 => k2
+val SOME m = SOME 3
+infix 5 ++
+fun a ++ b = a + b + 0
+val _ = let val inner = "x" in inner end
+"#;
+
+/// returns the hints in the range, as lines of the zero-based line and character, label, and kind.
+fn hints(range: Range) -> String {
+  let p =
+    Project::new(&[("sources.mlb", "$(SML_LIB)/basis/basis.mlb a.sml"), ("a.sml", FILE)], None);
+  let mut ret = String::new();
+  for hint in p.an().inlay_hints(p.path("a.sml"), range).expect("no hints") {
+    let pos = hint.position;
+    writeln!(ret, "{}:{} {} {:?}", pos.line, pos.character, hint.label, hint.kind).unwrap();
+  }
+  ret
+}
+
+#[test]
+fn whole_file() {
+  let range =
+    Range { start: Position { line: 0, character: 0 }, end: Position { line: 9, character: 0 } };
+  let want = r#"
+0:5 : int ValBind
+1:6 : string ValBind
+3:8 : int FunParam
+3:20 : int FunParam
+4:5 : real -> real ValBind
+4:31 : real FnParam
+5:10 : int ValBind
+7:5 : int FunParam
+7:10 : int FunParam
+8:21 : string ValBind
+"#;
+  pretty_assertions::assert_str_eq!(want.trim_start(), hints(range));
+}
+
+#[test]
+fn range() {
+  let range =
+    Range { start: Position { line: 3, character: 0 }, end: Position { line: 4, character: 0 } };
+  let want = r#"
+3:8 : int FunParam
+3:20 : int FunParam
+"#;
+  pretty_assertions::assert_str_eq!(want.trim_start(), hints(range));
+}
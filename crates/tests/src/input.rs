@@ -17,18 +17,31 @@ fn check_input<'a, I>(
   groups: I,
   config: Option<&str>,
 ) -> Result<analysis::input::Input, analysis::input::Error>
+where
+  I: IntoIterator<Item = (&'a str, &'a str)>,
+{
+  let mut store = paths::Store::new();
+  get_input(&mut store, groups, config)
+}
+
+/// Returns the input for the files, given by name relative to the root and contents, and the
+/// config, if any.
+pub(crate) fn get_input<'a, I>(
+  store: &mut paths::Store,
+  files: I,
+  config: Option<&str>,
+) -> Result<analysis::input::Input, analysis::input::Error>
 where
   I: IntoIterator<Item = (&'a str, &'a str)>,
 {
   let fs = paths::MemoryFileSystem::new(
-    groups
+    files
       .into_iter()
       .map(|(name, contents)| (ROOT.as_path().join(name), contents.to_owned()))
       .chain(config.map(|x| (ROOT.as_path().join(config::FILE_NAME), x.to_owned())))
       .collect(),
   );
-  let mut store = paths::Store::new();
-  analysis::input::Input::new(&fs, &mut store, &ROOT)
+  analysis::input::Input::new(&fs, store, &ROOT)
 }
 
 #[track_caller]
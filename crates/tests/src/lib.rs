@@ -6,33 +6,53 @@
 #![cfg(test)]
 #![deny(clippy::pedantic, rust_2018_idioms)]
 
+mod call_hierarchy;
 mod check;
+mod code_lens;
+mod completion;
 mod datatype_copy;
 mod deviations;
 mod docs;
+mod document_highlight;
+mod document_symbol;
 mod dupe;
 mod equality;
 mod exn;
+mod fill_hole;
 mod fixity;
+mod folding_and_selection;
 mod functor;
 mod generalize;
+mod goto_def;
 mod hover;
+mod implementation;
 mod incomplete;
+mod incremental;
 mod infix_without_op;
+mod inlay_hint;
 mod input;
 mod literal;
 mod local;
 mod matching;
 mod misc;
+mod missing_arms;
 mod num_record;
 mod overload;
+mod parallel;
 mod pat;
+mod references;
+mod rename;
 mod repo;
 mod rest_pat;
 mod rust;
+mod semantic_tokens;
 mod shadow;
 mod sig;
+mod signature_help;
 mod smoke;
 mod std_basis;
+mod stubs;
 mod ty_escape;
 mod ty_var;
+mod unused;
+mod workspace_symbol;
@@ -671,6 +671,21 @@ val lam = lambda x: x + 1
   );
 }
 
+#[test]
+fn suggest_similar() {
+  check(
+    r#"
+structure List = struct
+  fun length _ = 0
+end
+val _ = Lsit.length
+(**     ^^^^^^^^^^^ undefined structure: Lsit (did you mean `List`?) *)
+val _ = List.lenght
+(**     ^^^^^^^^^^^ undefined value: lenght (did you mean `length`?) *)
+"#,
+  );
+}
+
 #[test]
 fn op_bool_op() {
   check(
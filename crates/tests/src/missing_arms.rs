@@ -0,0 +1,122 @@
+//! Filling in the arms a non-exhaustive match is missing.
+
+use crate::check::Project;
+
+/// fills in the missing arms of the match at the first occurrence of `at` in `before`, and checks
+/// the file is then `after`.
+#[track_caller]
+fn check(before: &str, at: &str, after: &str) {
+  let p = Project::new(&[("sources.mlb", "a.sml"), ("a.sml", before)], None);
+  let pos = p.pos("a.sml", at);
+  assert!(p.an().can_fill_missing_arms(pos));
+  let (range, text) = p.an().fill_missing_arms(pos).expect("should fill arms");
+  let got = p.edit("a.sml", [(range, text.as_str())]);
+  pretty_assertions::assert_str_eq!(after, got.as_str());
+}
+
+#[test]
+fn case() {
+  check(
+    r#"
+datatype t = A | B of t
+val _ = fn x =>
+  case x of
+    A => 1
+  | B A => 2
+"#,
+    "case",
+    r#"
+datatype t = A | B of t
+val _ = fn x =>
+  case x of
+    A => 1
+  | B A => 2
+  | B (B _) => _
+"#,
+  );
+}
+
+#[test]
+fn fn_() {
+  check(
+    r#"
+datatype t = A | B
+val _ = fn
+    A => 1
+"#,
+    "fn",
+    r#"
+datatype t = A | B
+val _ = fn
+    A => 1
+  | B => _
+"#,
+  );
+}
+
+#[test]
+fn curried_fun() {
+  check(
+    r#"
+datatype t = A | B
+fun f A A = 1
+  | f B _ = 2
+"#,
+    "f A",
+    r#"
+datatype t = A | B
+fun f A A = 1
+  | f B _ = 2
+  | f A B = _
+"#,
+  );
+}
+
+#[test]
+fn infix_fun() {
+  check(
+    r#"
+datatype t = A | B
+infix <+>
+fun A <+> A = 1
+  | B <+> _ = 2
+"#,
+    "<+> A",
+    r#"
+datatype t = A | B
+infix <+>
+fun A <+> A = 1
+  | B <+> _ = 2
+  | op <+> (A, B) = _
+"#,
+  );
+}
+
+#[test]
+fn record() {
+  check(
+    r#"
+datatype t = A | B
+val _ = fn x =>
+  case x of
+    {a = A, b = _} => 1
+"#,
+    "case",
+    r#"
+datatype t = A | B
+val _ = fn x =>
+  case x of
+    {a = A, b = _} => 1
+  | {a = B, b = _} => _
+"#,
+  );
+}
+
+#[test]
+fn exhaustive() {
+  let s = "datatype t = A | B\nval _ = fn x => case x of A => 1 | B => 2";
+  let p = Project::new(&[("sources.mlb", "a.sml"), ("a.sml", s)], None);
+  let pos = p.pos("a.sml", "case");
+  assert!(!p.an().can_fill_missing_arms(pos));
+  assert!(p.an().fill_missing_arms(pos).is_none());
+}
@@ -0,0 +1,57 @@
+//! Parsing source files in parallel.
+
+use crate::check::Project;
+
+/// checks that analyzing the files with different numbers of threads gets the same diagnostics and
+/// hovers, and that exactly the files in `want` have diagnostics. the hovers are at the first
+/// occurrence of each needle in its file.
+#[track_caller]
+fn check(files: &[(&str, &str)], hovers: &[(&str, &str)], want: &[&str]) {
+  let results = [1, 2, 8].map(|threads| {
+    let mut p = Project::with_threads(files, None, threads);
+    let hovers: Vec<_> = hovers
+      .iter()
+      .map(|&(name, needle)| {
+        let (md, _) = p.an().get_md(p.pos(name, needle), true).expect("no hover");
+        md
+      })
+      .collect();
+    (p.diagnostics(), hovers)
+  });
+  let (diagnostics, _) = &results[0];
+  let names: Vec<_> = diagnostics.keys().map(String::as_str).collect();
+  assert_eq!(want, names.as_slice());
+  for got in &results[1..] {
+    pretty_assertions::assert_eq!(&results[0], got);
+  }
+}
+
+#[test]
+fn cm_source_path_set() {
+  check(
+    &[
+      ("sources.cm", "Group is $/basis.cm a.sml b.sml c.sml d.sml"),
+      ("a.sml", "structure A = struct val x = B.y + 1 end"),
+      ("b.sml", "structure B = struct val y = 3 end"),
+      ("c.sml", "structure C = struct val z = A.x + D.w end"),
+      ("d.sml", r#"structure D = struct val w = "hi" end"#),
+    ],
+    &[("a.sml", "x"), ("b.sml", "y"), ("d.sml", "w")],
+    &["c.sml"],
+  );
+}
+
+#[test]
+fn mlb_seq_fixity() {
+  check(
+    &[
+      ("sources.mlb", "$(SML_LIB)/basis/basis.mlb a.sml b.sml c.sml d.sml"),
+      ("a.sml", "fun f (x, y) = x + y"),
+      ("b.sml", "infix 6 f"),
+      ("c.sml", "val g = 1 f 2"),
+      ("d.sml", "val h = 1 f \"no\""),
+    ],
+    &[("a.sml", "f ("), ("c.sml", "g")],
+    &["d.sml"],
+  );
+}
@@ -0,0 +1,64 @@
+//! Finding all references to an item.
+
+use crate::check::Project;
+
+/// returns the references to the item at the first occurrence of `at` in `file`, as sorted
+/// triples of (file name, zero-based line, zero-based character) of their starts.
+#[track_caller]
+fn refs(
+  files: &[(&str, &str)],
+  file: &str,
+  at: &str,
+  include_def: bool,
+) -> Vec<(String, u32, u32)> {
+  let p = Project::new(files, None);
+  let mut ret: Vec<_> = p
+    .an()
+    .get_refs(p.pos(file, at), include_def)
+    .expect("no refs")
+    .into_iter()
+    .map(|r| (p.name(r.path).to_owned(), r.val.start.line, r.val.start.character))
+    .collect();
+  ret.sort_unstable();
+  ret
+}
+
+fn loc(name: &str, line: u32, character: u32) -> (String, u32, u32) {
+  (name.to_owned(), line, character)
+}
+
+const ACROSS_FILES: [(&str, &str); 3] = [
+  ("sources.mlb", "a.sml b.sml"),
+  ("a.sml", "structure S = struct val x = 1 end"),
+  ("b.sml", "val _ = (S.x, S.x)\nval x = 2\nval _ = x"),
+];
+
+#[test]
+fn across_files() {
+  let got = refs(&ACROSS_FILES, "b.sml", "x", true);
+  assert_eq!(got, [loc("a.sml", 0, 25), loc("b.sml", 0, 11), loc("b.sml", 0, 16)]);
+}
+
+#[test]
+fn without_def() {
+  let got = refs(&ACROSS_FILES, "a.sml", "x", false);
+  assert_eq!(got, [loc("b.sml", 0, 11), loc("b.sml", 0, 16)]);
+}
+
+#[test]
+fn shadowed() {
+  let files = [("sources.mlb", "a.sml"), ("a.sml", "val x = 1\nval y = x\nval x = 2\nval z = x")];
+  assert_eq!(refs(&files, "a.sml", "x", true), [loc("a.sml", 0, 4), loc("a.sml", 1, 8)]);
+  assert_eq!(refs(&files, "a.sml", "x = 2", true), [loc("a.sml", 2, 4), loc("a.sml", 3, 8)]);
+}
+
+#[test]
+fn spec_and_impl() {
+  let a = r#"signature SIG = sig type t val v : t end
+structure S : SIG = struct type t = {} val v = () end
+val _ = S.v"#;
+  let files = [("sources.mlb", "a.sml"), ("a.sml", a)];
+  let want = [loc("a.sml", 0, 31), loc("a.sml", 1, 43), loc("a.sml", 2, 10)];
+  assert_eq!(refs(&files, "a.sml", "v :", true), want);
+  assert_eq!(refs(&files, "a.sml", "v =", true), want);
+}
@@ -0,0 +1,137 @@
+//! Renaming items.
+
+use crate::check::Project;
+
+/// renames the item at the first occurrence of `at` in `file` to `new_name`, and checks the files
+/// are then `want`. the files not in `want` must not change.
+#[track_caller]
+fn check(files: &[(&str, &str)], file: &str, at: &str, new_name: &str, want: &[(&str, &str)]) {
+  let p = Project::new(files, None);
+  let ranges = p.an().rename(p.input(), p.pos(file, at), new_name).expect("should rename");
+  for &(name, _) in files {
+    let edits = ranges.iter().filter(|r| p.name(r.path) == name).map(|r| (r.val, new_name));
+    let got = p.edit(name, edits);
+    let want = want.iter().find_map(|&(n, c)| (n == name).then_some(c)).unwrap_or(p.contents(name));
+    pretty_assertions::assert_str_eq!(want, got.as_str(), "{name}");
+  }
+}
+
+/// checks renaming the item at the first occurrence of `at` in `file` to `new_name` is invalid.
+#[track_caller]
+fn check_invalid(file: &str, at: &str, new_name: &str) {
+  let p = Project::new(&[("sources.mlb", "a.sml"), ("a.sml", file)], None);
+  let got = p.an().rename(p.input(), p.pos("a.sml", at), new_name);
+  assert!(matches!(got, Err(analysis::RenameError::InvalidName)), "{got:?}");
+}
+
+#[test]
+fn across_files() {
+  check(
+    &[
+      ("sources.mlb", "a.sml b.sml"),
+      ("a.sml", "structure S = struct val x = 1 end"),
+      ("b.sml", "val _ = (S.x, S.x)"),
+    ],
+    "b.sml",
+    "x",
+    "y",
+    &[("a.sml", "structure S = struct val y = 1 end"), ("b.sml", "val _ = (S.y, S.y)")],
+  );
+}
+
+#[test]
+fn symbolic() {
+  check(
+    &[("sources.mlb", "a.sml"), ("a.sml", "fun ++ (a, b) = (b, a)\nval _ = ++ (1, 2)")],
+    "a.sml",
+    "++",
+    "<+>",
+    &[("a.sml", "fun <+> (a, b) = (b, a)\nval _ = <+> (1, 2)")],
+  );
+}
+
+#[test]
+fn cm_export() {
+  check(
+    &[
+      ("sources.cm", "Group structure S is a.sml b.sml"),
+      ("a.sml", "structure S = struct end"),
+      ("b.sml", "structure B = S"),
+    ],
+    "a.sml",
+    "S",
+    "T",
+    &[
+      ("sources.cm", "Group structure T is a.sml b.sml"),
+      ("a.sml", "structure T = struct end"),
+      ("b.sml", "structure B = T"),
+    ],
+  );
+}
+
+#[test]
+fn mlb_export() {
+  check(
+    &[
+      ("sources.mlb", "local a.sml in structure X = S structure S end"),
+      ("a.sml", "structure S = struct end"),
+    ],
+    "a.sml",
+    "S",
+    "T",
+    &[
+      ("sources.mlb", "local a.sml in structure X = T structure T end"),
+      ("a.sml", "structure T = struct end"),
+    ],
+  );
+}
+
+#[test]
+fn reserved() {
+  check_invalid("val x = 1", "x", "end");
+  check_invalid("val x = 1", "x", "val");
+  check_invalid("fun ++ (a, b) = (b, a)", "++", "=>");
+}
+
+#[test]
+fn alpha_to_symbolic() {
+  check_invalid("val x = 1", "x", "++");
+}
+
+#[test]
+fn symbolic_to_alpha() {
+  check_invalid("fun ++ (a, b) = (b, a)", "++", "plus");
+}
+
+#[test]
+fn sig_spec() {
+  check(
+    &[
+      ("sources.mlb", "a.sml b.sml"),
+      ("a.sml", "signature SIG = sig val x : int end"),
+      ("b.sml", "structure S : SIG = struct val x = 1 end\nval _ = S.x"),
+    ],
+    "b.sml",
+    "x",
+    "y",
+    &[
+      ("a.sml", "signature SIG = sig val y : int end"),
+      ("b.sml", "structure S : SIG = struct val y = 1 end\nval _ = S.y"),
+    ],
+  );
+}
+
+#[test]
+fn implements_builtin_spec() {
+  let p = Project::new(
+    &[
+      ("sources.mlb", "$(SML_LIB)/basis/basis.mlb $(SML_LIB)/smlnj-lib/Util/smlnj-lib.mlb a.sml"),
+      ("a.sml", "structure K : ORD_KEY = struct type ord_key = int val compare = Int.compare end"),
+    ],
+    None,
+  );
+  let pos = p.pos("a.sml", "compare");
+  assert!(p.an().prepare_rename(pos).is_none());
+  let got = p.an().rename(p.input(), pos, "cmp");
+  assert!(matches!(got, Err(analysis::RenameError::NoItem)), "{got:?}");
+}
@@ -0,0 +1,90 @@
+//! Classifying tokens with information from statics.
+
+use crate::check::Project;
+use std::fmt::Write as _;
+use text_pos::{Position, Range};
+
+const FILE: &str = r#"datatype 'a box = Box of 'a
+exception Oops
+structure S = struct val v = Box 1 end
+fun get (Box x) = x
+val y = get S.v + 1
+val r = {lab = y}
+val _ = Oops
+val z : int = 3
+infix 5 ++
+fun a ++ b = a
+val w = 1 ++ 2
+"#;
+
+/// returns the semantic tokens in the file, optionally only those in the range, as lines of the
+/// text, kind, and modifiers.
+fn tokens(range: Option<Range>) -> String {
+  let p =
+    Project::new(&[("sources.mlb", "$(SML_LIB)/basis/basis.mlb a.sml"), ("a.sml", FILE)], None);
+  let mut ret = String::new();
+  for token in p.an().semantic_tokens(p.path("a.sml"), range).expect("no tokens") {
+    assert_eq!(token.range.start.line, token.range.end.line);
+    write!(ret, "{} {:?}", p.text("a.sml", token.range), token.kind).unwrap();
+    if token.declaration {
+      ret.push_str(" decl");
+    }
+    if token.default_library {
+      ret.push_str(" lib");
+    }
+    ret.push('\n');
+  }
+  ret
+}
+
+#[test]
+fn whole_file() {
+  let want = r#"
+'a TypeVariable decl
+box Type decl
+Box Constructor decl
+'a TypeVariable
+Oops Exception decl
+S Structure decl
+v Value decl
+Box Constructor
+get Value decl
+Box Constructor
+x Value decl
+x Value
+y Value decl
+get Value
+S Structure
+v Value
++ Operator lib
+r Value decl
+lab Label
+y Value
+Oops Exception
+z Value decl
+int Type lib
+++ Operator
+a Value decl
+++ Operator decl
+b Value decl
+a Value
+w Value decl
+++ Operator
+"#;
+  pretty_assertions::assert_str_eq!(want.trim_start(), tokens(None));
+}
+
+#[test]
+fn range() {
+  let range =
+    Range { start: Position { line: 4, character: 8 }, end: Position { line: 5, character: 10 } };
+  let want = r#"
+get Value
+S Structure
+v Value
++ Operator lib
+r Value decl
+lab Label
+"#;
+  pretty_assertions::assert_str_eq!(want.trim_start(), tokens(Some(range)));
+}
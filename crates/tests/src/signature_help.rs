@@ -0,0 +1,86 @@
+//! Help with the signature of the function being applied.
+
+use crate::check::Project;
+
+const BASIS: &str = "$(SML_LIB)/basis/basis.mlb a.sml";
+
+/// returns the signature help just after the first occurrence of `before` in the file.
+#[track_caller]
+fn help(s: &str, before: &str) -> Option<analysis::SignatureHelp> {
+  let p = Project::new(&[("sources.mlb", BASIS), ("a.sml", s)], None);
+  let mut pos = p.pos("a.sml", before);
+  pos.val.character += u32::try_from(before.len()).unwrap();
+  p.an().signature_help(pos)
+}
+
+/// checks the label of the signature help, and that the text of the active parameter in the label
+/// is `active`.
+#[track_caller]
+fn check(s: &str, before: &str, label: &str, active: Option<&str>) {
+  let got = help(s, before).expect("no signature help");
+  assert_eq!(got.label, label);
+  let got_active = got.active_param.map(|idx| &got.label[got.params[idx].clone()]);
+  assert_eq!(got_active, active);
+}
+
+const TUPLED_AND_CURRIED: &str = r#"
+fun add (a : int, b : string) = a
+fun cur (x : int) (y : string) (z : bool) = x
+val _ = add (1, "x")
+val _ = cur 1 "s" true
+val _ = add (cur 2 "t" false, "y")
+"#;
+
+#[test]
+fn tupled() {
+  let label = "add : int * string -> int";
+  check(TUPLED_AND_CURRIED, "add (", label, Some("int"));
+  check(TUPLED_AND_CURRIED, "add (1", label, Some("int"));
+  check(TUPLED_AND_CURRIED, "add (1,", label, Some("string"));
+  check(TUPLED_AND_CURRIED, "add (1, \"x", label, Some("string"));
+}
+
+#[test]
+fn curried() {
+  let label = "cur : int -> string -> bool -> int";
+  check(TUPLED_AND_CURRIED, "cur 1", label, Some("int"));
+  check(TUPLED_AND_CURRIED, "cur 1 \"s", label, Some("string"));
+  check(TUPLED_AND_CURRIED, "cur 1 \"s\" tr", label, Some("bool"));
+}
+
+#[test]
+fn nested() {
+  check(TUPLED_AND_CURRIED, "cur 2 \"t", "cur : int -> string -> bool -> int", Some("string"));
+  check(TUPLED_AND_CURRIED, "false, \"y", "add : int * string -> int", Some("string"));
+}
+
+#[test]
+fn too_many_args() {
+  let s = r#"
+fun one (x : int) = x
+val _ = one 1 2
+"#;
+  check(s, "one 1", "one : int -> int", Some("int"));
+  check(s, "one 1 2", "one : int -> int", None);
+}
+
+#[test]
+fn qualified_and_doc() {
+  let s = r#"
+structure S = struct
+  (*!
+   * Returns the number incremented.
+   *)
+  fun inc (x : int) = x + 1
+end
+val _ = S.inc 3
+"#;
+  let got = help(s, "S.inc 3").expect("no signature help");
+  assert_eq!(got.label, "S.inc : int -> int");
+  assert!(got.doc.expect("no doc").contains("Returns the number incremented."));
+}
+
+#[test]
+fn not_applied() {
+  assert!(help("val x = 3\nval _ = x", "_ = x").is_none());
+}
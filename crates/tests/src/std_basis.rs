@@ -1,6 +1,6 @@
 //! Standard library and definitions.
 
-use crate::check::check_with_std_basis;
+use crate::check::{check_project, check_with_std_basis, Project};
 
 #[test]
 fn smoke() {
@@ -186,3 +186,222 @@ val _ = a ^ b
 "#,
   );
 }
+
+#[test]
+fn not_asked_for() {
+  check_project(
+    &[
+      ("sources.cm", "Group is a.sml"),
+      (
+        "a.sml",
+        r#"
+val _ = List.length [1, 2]
+(**     ^^^^^^^^^^^ undefined structure: List *)
+"#,
+      ),
+    ],
+    None,
+  );
+}
+
+#[test]
+fn asked_for() {
+  check_project(
+    &[("sources.cm", "Group is $/basis.cm a.sml"), ("a.sml", "val _ = List.length [1, 2]")],
+    None,
+  );
+}
+
+#[test]
+fn implicit() {
+  let config = r#"
+version = 1
+[language]
+implicit-std-basis = true
+"#;
+  check_project(
+    &[("sources.cm", "Group is a.sml"), ("a.sml", "val _ = List.length [1, 2]")],
+    Some(config),
+  );
+}
+
+#[test]
+fn dialect_libs() {
+  let config = r#"
+version = 1
+[language]
+implicit-std-basis = true
+dialect = "sml-nj"
+"#;
+  check_project(
+    &[
+      ("sources.cm", "Group is a.sml"),
+      (
+        "a.sml",
+        r#"
+val _ = MLton.isMLton
+(**     ^^^^^^^^^^^^^ contains: undefined structure: MLton *)
+"#,
+      ),
+    ],
+    Some(config),
+  );
+  let config = r#"
+version = 1
+[language]
+implicit-std-basis = true
+dialect = "mlton"
+"#;
+  check_project(
+    &[("sources.cm", "Group is a.sml"), ("a.sml", "val _ = MLton.isMLton")],
+    Some(config),
+  );
+}
+
+#[test]
+fn dialect_or_pat() {
+  let config = r#"
+version = 1
+[language]
+dialect = "standard"
+"#;
+  check_project(
+    &[
+      ("sources.cm", "Group is $/basis.cm a.sml"),
+      (
+        "a.sml",
+        r#"
+val _ = fn (1 | 2) => true | _ => false
+(** + not allowed in this dialect: or patterns *)
+"#,
+      ),
+    ],
+    Some(config),
+  );
+  check_project(
+    &[
+      ("sources.cm", "Group is $/basis.cm a.sml"),
+      ("a.sml", "val _ = fn (1 | 2) => true | _ => false"),
+    ],
+    None,
+  );
+}
+
+#[test]
+fn dialect_vector() {
+  let config = r#"
+version = 1
+[language]
+dialect = "poly"
+"#;
+  check_project(
+    &[
+      ("sources.cm", "Group is $/basis.cm a.sml"),
+      (
+        "a.sml",
+        r#"
+val _ = #[1, 2]
+(**     ^^^^^^^ not allowed in this dialect: vector expressions *)
+"#,
+      ),
+    ],
+    Some(config),
+  );
+  check_project(
+    &[
+      ("sources.cm", "Group is $/basis.cm a.sml"),
+      (
+        "a.sml",
+        r#"
+val _ = fn #[_] => 1 | _ => 2
+(**        ^^^^ not allowed in this dialect: vector patterns *)
+"#,
+      ),
+    ],
+    Some(config),
+  );
+}
+
+#[test]
+fn dialect_do_dec() {
+  let config = r#"
+version = 1
+[language]
+dialect = "standard"
+"#;
+  check_project(
+    &[
+      ("sources.cm", "Group is $/basis.cm a.sml"),
+      (
+        "a.sml",
+        r#"
+do print "hi"
+(** + not allowed in this dialect: `do` declarations *)
+"#,
+      ),
+    ],
+    Some(config),
+  );
+}
+
+/// the full std basis from the build script was serialized and deserialized, so it should be the
+/// same as analyzing it now.
+#[test]
+fn serde_round_trip() {
+  let mlb = r#"
+$(SML_LIB)/basis/basis.mlb
+$(SML_LIB)/basis/sml-nj.mlb
+$(SML_LIB)/smlnj-lib/Util/smlnj-lib.mlb
+a.sml
+"#;
+  let a = r#"
+structure K : ORD_KEY = struct type ord_key = int val compare = Int.compare end
+structure M = RedBlackMapFn (K)
+val m = M.insert (M.empty, 3, "hi")
+val _ = List.map (fn x => x + 1) [1, 2]
+val _ = Option.valOf (M.find (m, 3)) : string
+val _ = TextIO.print (Int.toString (List.length [SMLofNJ.Cont.callcc]))
+val _ = Vector.foldl op+ "nope"
+val _ = ListPair.zip ([1], ["a"]) : (int * int) list
+"#;
+  let files = [("sources.mlb", mlb), ("a.sml", a)];
+  let needles = [
+    "compare", "insert", "List.map", "valOf", "find", "print", "toString", "callcc", "foldl", "zip",
+  ];
+  let mut ps = [analysis::StdBasis::Full, analysis::StdBasis::FullLive].map(|std_basis| {
+    let p = Project::with_std_basis(&files, None, std_basis);
+    let hovers: Vec<_> = needles
+      .iter()
+      .map(|needle| p.an().get_md(p.pos("a.sml", needle), true).map(|(md, _)| md))
+      .collect();
+    (p, hovers)
+  });
+  let [(deserialized, deserialized_hovers), (live, live_hovers)] = &mut ps;
+  assert!(deserialized_hovers.iter().all(Option::is_some), "missing a hover");
+  pretty_assertions::assert_eq!(deserialized_hovers, live_hovers);
+  let deserialized = deserialized.diagnostics();
+  assert!(!deserialized.is_empty(), "no diagnostics");
+  pretty_assertions::assert_eq!(deserialized, live.diagnostics());
+}
+
+#[test]
+fn dialect_one_file() {
+  let an = analysis::Analysis::new(
+    analysis::StdBasis::Full,
+    config::ErrorLines::One,
+    config::DiagnosticsFilter::None,
+    false,
+  );
+  let s = r#"
+val _ = SMLofNJ.exnHistory
+val _ = #[1]
+"#;
+  let messages =
+    |dialect| -> Vec<_> { an.get_one(s, dialect).into_iter().map(|d| d.message).collect() };
+  assert_eq!(messages(None), ["unsupported: vector expressions"]);
+  assert_eq!(messages(Some(config::Dialect::SmlNj)), ["unsupported: vector expressions"]);
+  let standard = messages(Some(config::Dialect::Standard));
+  assert_eq!(standard.len(), 2);
+  assert_eq!(standard[0], "not allowed in this dialect: vector expressions");
+  assert!(standard[1].starts_with("undefined structure: SMLofNJ"));
+}
@@ -0,0 +1,140 @@
+//! Stubbing out the items a structure is missing from its signature.
+
+use crate::check::Project;
+
+/// stubs out the missing items of the structure at the first occurrence of `at` in `before`, and
+/// checks the file is then `after`.
+#[track_caller]
+fn check(before: &str, at: &str, after: &str) {
+  let p = Project::new(&[("sources.mlb", "a.sml"), ("a.sml", before)], None);
+  let pos = p.pos("a.sml", at);
+  assert!(p.an().can_stub_missing_items(pos));
+  let (range, text) = p.an().stub_missing_items(pos).expect("should stub items");
+  let got = p.edit("a.sml", [(range, text.as_str())]);
+  pretty_assertions::assert_str_eq!(after, got.as_str());
+}
+
+#[test]
+fn val() {
+  check(
+    r#"
+datatype d = D
+signature SIG = sig
+  val f : d -> d
+end
+structure S : SIG = struct
+end
+"#,
+    "struct\nend",
+    r#"
+datatype d = D
+signature SIG = sig
+  val f : d -> d
+end
+structure S : SIG = struct
+  val f : d -> d = fn _ => raise Fail "unimplemented"
+end
+"#,
+  );
+}
+
+#[test]
+fn datatype() {
+  check(
+    r#"
+signature SIG = sig
+  datatype t = A | B of t
+end
+structure S : SIG = struct
+end
+"#,
+    "struct\nend",
+    r#"
+signature SIG = sig
+  datatype t = A | B of t
+end
+structure S : SIG = struct
+  datatype t = A | B of t
+end
+"#,
+  );
+}
+
+#[test]
+fn exception() {
+  check(
+    r#"
+datatype d = D
+signature SIG = sig
+  exception E of d
+end
+structure S : SIG = struct
+end
+"#,
+    "struct\nend",
+    r#"
+datatype d = D
+signature SIG = sig
+  exception E of d
+end
+structure S : SIG = struct
+  exception E of d
+end
+"#,
+  );
+}
+
+#[test]
+fn substructure() {
+  check(
+    r#"
+signature SIG = sig
+  structure T : sig
+    type t
+    val x : t
+  end
+end
+structure S : SIG = struct
+end
+"#,
+    "struct\nend",
+    r#"
+signature SIG = sig
+  structure T : sig
+    type t
+    val x : t
+  end
+end
+structure S : SIG = struct
+  structure T = struct
+    type t = unit
+    val x : t = raise Fail "unimplemented"
+  end
+end
+"#,
+  );
+}
+
+#[test]
+fn ty_abbreviation() {
+  check(
+    r#"
+structure Foo = struct datatype t = A end
+signature SIG = sig
+  type t = Foo.t
+end
+structure S : SIG = struct
+end
+"#,
+    "struct\nend",
+    r#"
+structure Foo = struct datatype t = A end
+signature SIG = sig
+  type t = Foo.t
+end
+structure S : SIG = struct
+  type t = Foo.t
+end
+"#,
+  );
+}
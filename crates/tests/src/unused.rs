@@ -0,0 +1,87 @@
+//! Fixing unused values.
+
+use crate::check::Project;
+
+/// stops binding the unused value at the first occurrence of `at` in `before`, and checks the file
+/// is then `after`.
+#[track_caller]
+fn check_ignore(before: &str, at: &str, after: &str) {
+  let p = Project::new(&[("sources.mlb", "a.sml"), ("a.sml", before)], None);
+  let pos = p.pos("a.sml", at);
+  assert!(p.an().can_ignore_unused(pos));
+  let (range, text) = p.an().ignore_unused(pos).expect("should ignore");
+  let got = p.edit("a.sml", [(range, text.as_str())]);
+  pretty_assertions::assert_str_eq!(after, got.as_str());
+}
+
+/// deletes the declaration of the unused value at the first occurrence of `at` in `before`, and
+/// checks the file is then `after`, or that there was no such deletion if `after` is `None`.
+#[track_caller]
+fn check_delete(before: &str, at: &str, after: Option<&str>) {
+  let p = Project::new(&[("sources.mlb", "a.sml"), ("a.sml", before)], None);
+  let pos = p.pos("a.sml", at);
+  assert_eq!(after.is_some(), p.an().can_delete_unused_val(pos));
+  let got = p.an().delete_unused_val(pos).map(|range| p.edit("a.sml", [(range, "")]));
+  pretty_assertions::assert_eq!(after, got.as_deref());
+}
+
+#[test]
+fn con_pat() {
+  check_ignore("val _ = fn x => ()", "x", "val _ = fn _ => ()");
+}
+
+#[test]
+fn as_pat() {
+  check_ignore(
+    "datatype d = D\nval _ = fn x as D => ()",
+    "x as",
+    "datatype d = D\nval _ = fn D => ()",
+  );
+}
+
+#[test]
+fn record_pun() {
+  check_ignore("val _ = fn {a, b} => b", "a,", "val _ = fn {a = _, b} => b");
+}
+
+#[test]
+fn record_pun_as() {
+  check_ignore(
+    "datatype d = D\nval _ = fn {a as D, b} => b",
+    "a as",
+    "datatype d = D\nval _ = fn {a = D, b} => b",
+  );
+}
+
+#[test]
+fn delete_pure() {
+  check_delete(
+    r#"
+val _ = let
+  val x = (1, "hi")
+  val y = 2
+in y end
+"#,
+    "x",
+    Some(
+      r#"
+val _ = let
+  val y = 2
+in y end
+"#,
+    ),
+  );
+}
+
+#[test]
+fn delete_impure() {
+  check_delete(
+    r#"
+val _ = fn f => let
+  val x = f ()
+in () end
+"#,
+    "x",
+    None,
+  );
+}
@@ -0,0 +1,106 @@
+//! Searching for symbols across the workspace.
+
+use crate::check::Project;
+use std::fmt::Write as _;
+
+/// returns the symbols in the project files (not the std basis) matching the query, in order, as
+/// lines of the kind, name, and container name if any. also checks each symbol's range contains
+/// its name.
+#[track_caller]
+fn check(files: &[(&str, &str)], query: &str, want: &str) {
+  let p = Project::new(files, None);
+  let mut got = String::new();
+  for symbol in p.an().workspace_symbols(query) {
+    let path = match symbol.path {
+      analysis::DefPath::Regular(x) => x,
+      analysis::DefPath::BuiltinLib(_) => continue,
+    };
+    let text = p.text(p.name(path), symbol.range);
+    assert!(text.contains(symbol.name.as_str()), "{text:?} does not contain {}", symbol.name);
+    write!(got, "{:?} {}", symbol.kind, symbol.name).unwrap();
+    if let Some(container) = &symbol.container_name {
+      write!(got, " in {container}").unwrap();
+    }
+    got.push('\n');
+  }
+  pretty_assertions::assert_str_eq!(want.trim_start(), got);
+}
+
+#[test]
+fn ranking() {
+  check(
+    &[
+      ("sources.mlb", "a.sml b.sml"),
+      ("a.sml", "val fxrxoxb = 1\nval unfrob = 2\nval other = 3"),
+      ("b.sml", "val frobnicate = 4\nval frob = 5"),
+    ],
+    "Frob",
+    r#"
+Value frob
+Value frobnicate
+Value unfrob
+Value fxrxoxb
+"#,
+  );
+}
+
+#[test]
+fn containers() {
+  check(
+    &[
+      ("sources.mlb", "a.sml"),
+      (
+        "a.sml",
+        r#"
+structure Outer = struct
+  structure QqInner = struct
+    fun qqf x = x
+    val qqv = 1
+    exception QqE
+  end
+end
+signature QQSIG = sig
+  type t
+  val qqs : t
+  val qqg : t -> t
+end
+"#,
+      ),
+    ],
+    "qq",
+    r#"
+Exception QqE in QqInner
+Function qqf in QqInner
+Function qqg in QQSIG
+Value qqs in QQSIG
+Value qqv in QqInner
+Signature QQSIG
+Structure QqInner in Outer
+"#,
+  );
+}
+
+#[test]
+fn std_basis() {
+  let p = Project::new(&[("sources.mlb", "a.sml"), ("a.sml", "val optionally = 1")], None);
+  let symbols = p.an().workspace_symbols("option");
+  let first = symbols.first().expect("no symbols");
+  assert_eq!(first.name.to_lowercase(), "option");
+  assert!(matches!(first.path, analysis::DefPath::BuiltinLib(_)));
+  assert!(symbols.iter().any(
+    |symbol| symbol.name == "optionally" && matches!(symbol.path, analysis::DefPath::Regular(_))
+  ));
+}
+
+#[test]
+fn limit() {
+  let mut a = String::new();
+  for i in 0..150 {
+    writeln!(a, "val zqv{i} = {i}").unwrap();
+  }
+  let p = Project::new(&[("sources.mlb", "a.sml"), ("a.sml", a.as_str())], None);
+  let symbols = p.an().workspace_symbols("zqv");
+  assert_eq!(symbols.len(), 100);
+  let names: Vec<_> = symbols.iter().take(3).map(|symbol| symbol.name.as_str()).collect();
+  assert_eq!(names, ["zqv0", "zqv1", "zqv2"]);
+}
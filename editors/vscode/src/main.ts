@@ -3,6 +3,8 @@ import * as vscode from "vscode";
 import {
   LanguageClient,
   type LanguageClientOptions,
+  type Location,
+  type Position,
   type ServerOptions,
 } from "vscode-languageclient/node";
 
@@ -24,20 +26,60 @@ export async function activate(cx: vscode.ExtensionContext) {
         ? configPath
         : cx.asAbsolutePath(path.join("out", `lang-srv${ext}`)),
   };
+  // the server takes no dialect to mean any dialect.
+  const dialect = config.get("server.language.dialect");
   const clientOpts: LanguageClientOptions = {
     documentSelector: [{ scheme: "file", language: "sml" }],
     initializationOptions: {
       show_token_hover: config.get("server.hover.token.enable"),
+      inlay_hints_val_bind: config.get("server.inlayHints.valBind.enable"),
+      inlay_hints_fun_param: config.get("server.inlayHints.funParam.enable"),
+      inlay_hints_fn_param: config.get("server.inlayHints.fnParam.enable"),
       diagnostics_on_change: config.get("server.diagnostics.onChange.enable"),
       diagnostics_filter: config.get("server.diagnostics.filter"),
       diagnostics_more_info_hint: config.get(
         "server.diagnostics.moreInfoHint.enable",
       ),
       format: config.get("format.enable"),
+      language_dialect: dialect === "any" ? null : dialect,
     },
   };
   client = new LanguageClient("millet", serverOpts, clientOpts);
   client.start();
+  // built-in library files have no path on disk, so the server gives them `millet:` URIs.
+  cx.subscriptions.push(
+    vscode.workspace.registerTextDocumentContentProvider("millet", {
+      async provideTextDocumentContent(uri) {
+        if (client === null) {
+          return null;
+        }
+        return client.sendRequest<string | null>("millet/builtinFile", {
+          uri: uri.toString(),
+        });
+      },
+    }),
+  );
+  // the reference count code lenses run this with protocol types, but the built-in command needs
+  // vscode types.
+  cx.subscriptions.push(
+    vscode.commands.registerCommand(
+      "millet.showReferences",
+      (uri: string, position: Position, locations: Location[]) => {
+        if (client === null) {
+          return;
+        }
+        const conv = client.protocol2CodeConverter;
+        return vscode.commands.executeCommand(
+          "editor.action.showReferences",
+          vscode.Uri.parse(uri),
+          conv.asPosition(position),
+          locations.map(conv.asLocation),
+        );
+      },
+    ),
+  );
+  // the inferred type code lenses run this, since they are only informational.
+  cx.subscriptions.push(vscode.commands.registerCommand("millet.noop", () => {}));
 }
 
 export async function deactivate() {